@@ -0,0 +1,69 @@
+-- first page of the most favorited articles using the list articles query of the application with
+-- the favorites and comments denormalized onto the articles. the parameters are replaced by the
+-- values that are bound when listing articles without any filters as the viewer in seed.sql
+    SELECT
+        a.*,
+        CASE WHEN NULL::text IS NULL THEN NULL ELSE TS_HEADLINE('english', a.body, WEBSEARCH_TO_TSQUERY('english', NULL::text), 'StartSel=' || CHR(2) || ', StopSel=' || CHR(3) || ', MaxFragments=2, MaxWords=30, MinWords=10') END AS snippet,
+        (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id AND af.user_id = '00000000-0000-0000-0000-000000000001'::uuid)::int::bool AS favorited,
+        (ARRAY_TO_STRING(ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC), ',')) AS tags,
+        u.id AS author_id,
+        u.name AS author_name,
+        u.bio AS author_bio,
+        u.image AS author_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = '00000000-0000-0000-0000-000000000001'::uuid)::int::bool AS author_followed
+    FROM
+        articles AS a INNER JOIN users AS u ON a.user_id = u.id
+    WHERE
+        (CARDINALITY('{}'::text[]) = 0 OR CASE WHEN 'all'::text = 'any'
+            THEN EXISTS(SELECT 1 FROM article_tags AS at INNER JOIN tags AS t ON at.tag_id = t.id WHERE at.article_id = a.id AND t.name = ANY('{}'::text[]))
+            ELSE (SELECT COUNT(*) FROM article_tags AS at INNER JOIN tags AS t ON at.tag_id = t.id WHERE at.article_id = a.id AND t.name = ANY('{}'::text[])) = CARDINALITY('{}'::text[])
+        END)
+
+        AND
+
+        (CARDINALITY('{}'::text[]) = 0 OR NOT EXISTS(SELECT 1 FROM article_tags AS at INNER JOIN tags AS t ON at.tag_id = t.id WHERE at.article_id = a.id AND t.name = ANY('{}'::text[])))
+
+        AND
+
+        (CARDINALITY('{}'::text[]) = 0 OR u.name = ANY('{}'::text[]))
+
+        AND
+
+        (NULL::text IS NULL OR EXISTS(SELECT 1 FROM users AS u INNER JOIN article_favs AS af ON u.id = af.user_id WHERE af.article_id = a.id AND u.name = NULL::text))
+
+        AND
+
+        (NULL::text IS NULL OR a.search_vector @@ WEBSEARCH_TO_TSQUERY('english', NULL::text))
+
+        AND
+
+        (NULL::timestamptz IS NULL OR a.created >= NULL::timestamptz)
+
+        AND
+
+        (NULL::timestamptz IS NULL OR a.created < NULL::timestamptz)
+
+        AND
+
+        (a.status = 'published' OR a.user_id = '00000000-0000-0000-0000-000000000001'::uuid)
+
+        AND
+
+        (NULL::timestamptz IS NULL OR (a.created, a.id) < (NULL::timestamptz, NULL::uuid))
+
+        AND
+
+        (NULL::timestamptz IS NULL OR (a.created, a.id) > (NULL::timestamptz, NULL::uuid))
+    ORDER BY
+        CASE WHEN 'favorites'::text = 'relevance' THEN TS_RANK(a.search_vector, WEBSEARCH_TO_TSQUERY('english', NULL::text)) END DESC,
+        CASE WHEN 'favorites'::text = 'favorites' THEN a.favorites_count END DESC,
+        CASE WHEN 'favorites'::text = 'comments' THEN a.comments_count END DESC,
+        CASE WHEN 'favorites'::text = 'trending' THEN a.trending_score END DESC,
+        CASE WHEN false::bool THEN a.created END ASC,
+        CASE WHEN false::bool THEN a.id END ASC,
+        a.created DESC,
+        a.id DESC
+    LIMIT
+        20::bigint
+    OFFSET
+        0::bigint;
@@ -0,0 +1,23 @@
+#!/bin/sh
+#
+# Benchmarks the list articles query of the application as it was when the favorites and comments
+# were counted for every article against the query that uses the counters denormalized onto the
+# articles.
+#
+# Usage: benches/run.sh <database url>
+#
+# The database must have had the migrations applied and is seeded with the data in seed.sql, so it
+# should be a throwaway database. Requires psql and pgbench.
+set -e
+
+DATABASE_URL="${1:?usage: $0 <database url>}"
+DIR="$(cd "$(dirname "$0")" && pwd)"
+
+psql -q -v ON_ERROR_STOP=1 -d "$DATABASE_URL" -f "$DIR/seed.sql"
+
+for sort in newest favorites; do
+  for variant in counted denormalized; do
+    printf '%-10s %-13s ' "$sort" "$variant"
+    pgbench -n -c 4 -T 10 -f "$DIR/list_${sort}_${variant}.sql" "$DATABASE_URL" | awk '/latency average|^tps/ { printf "%s  ", $0 } END { print "" }'
+  done
+done
@@ -0,0 +1,69 @@
+-- seeds a database that has had the migrations applied with enough users, articles, favorites and
+-- comments for the cost of counting the favorites and comments of every article to be noticeable.
+-- the list articles queries are run as the viewer with the fixed id, who follows some of the
+-- authors, so that the favorited and followed subqueries do the same work as in the application.
+INSERT INTO users (name, email, password)
+SELECT 'bench' || i, 'bench' || i || '@example.com', 'x' FROM GENERATE_SERIES(1, 2000) AS i;
+
+INSERT INTO articles (user_id, slug, title, description, body, created)
+SELECT
+    (ARRAY(SELECT id FROM users ORDER BY name))[1 + i % 2000],
+    'bench-article-' || i,
+    'Bench article ' || i,
+    'Description of bench article ' || i,
+    'Body of bench article ' || i,
+    NOW() - (i || ' minutes')::interval
+FROM
+    GENERATE_SERIES(1, 20000) AS i;
+
+INSERT INTO users (id, name, email, password)
+VALUES ('00000000-0000-0000-0000-000000000001', 'bench-viewer', 'bench-viewer@example.com', 'x');
+
+-- popular articles have many more favorites and comments than the rest. the subqueries refer to
+-- the article so that they are run again for each article
+INSERT INTO article_favs (article_id, user_id, created)
+SELECT a.id, u.id, NOW() - RANDOM() * INTERVAL '60 days'
+FROM
+    (SELECT id FROM articles ORDER BY RANDOM() LIMIT 2000) AS a
+    CROSS JOIN LATERAL (SELECT id FROM users WHERE a.id IS NOT NULL ORDER BY RANDOM() LIMIT (1 + RANDOM() * 200)::int) AS u
+ON CONFLICT DO NOTHING;
+
+INSERT INTO article_comments (article_id, user_id, body)
+SELECT a.id, u.id, 'Bench comment'
+FROM
+    (SELECT id FROM articles ORDER BY RANDOM() LIMIT 2000) AS a
+    CROSS JOIN LATERAL (SELECT id FROM users WHERE a.id IS NOT NULL ORDER BY RANDOM() LIMIT (1 + RANDOM() * 50)::int) AS u;
+
+-- every article has four of the tags and every user has fifty followers. the tags subquery refers
+-- to the article so that it is run again for each article
+INSERT INTO tags (name)
+SELECT 'tag' || i FROM GENERATE_SERIES(1, 100) AS i;
+
+INSERT INTO article_tags (article_id, tag_id)
+SELECT a.id, t.id
+FROM
+    articles AS a
+    CROSS JOIN LATERAL (SELECT id FROM tags WHERE a.id IS NOT NULL ORDER BY RANDOM() LIMIT 4) AS t;
+
+INSERT INTO user_follows (user_id, follower_id)
+SELECT u.id, f.id
+FROM
+    users AS u
+    CROSS JOIN LATERAL (SELECT id FROM users WHERE id <> u.id ORDER BY RANDOM() LIMIT 50) AS f
+ON CONFLICT DO NOTHING;
+
+INSERT INTO user_follows (user_id, follower_id)
+SELECT id, '00000000-0000-0000-0000-000000000001'
+FROM users
+WHERE id <> '00000000-0000-0000-0000-000000000001'
+ORDER BY RANDOM()
+LIMIT 200
+ON CONFLICT DO NOTHING;
+
+UPDATE
+    articles AS a
+SET
+    favorites_count = (SELECT COUNT(*) FROM article_favs AS af WHERE af.article_id = a.id),
+    comments_count = (SELECT COUNT(*) FROM article_comments AS ac WHERE ac.article_id = a.id);
+
+ANALYZE;
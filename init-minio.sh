@@ -0,0 +1,4 @@
+#!/bin/sh
+
+docker exec minio mc alias set local http://localhost:9000 minioadmin minioadmin
+docker exec minio mc mb --ignore-existing local/realworld
@@ -0,0 +1,5 @@
+-- listings are paged by the position of a row ordered by the time it was created, using the id to
+-- break ties, so the indexes include both columns in the same order
+CREATE INDEX IF NOT EXISTS articles_created_id_idx ON articles (created DESC, id DESC);
+CREATE INDEX IF NOT EXISTS articles_user_id_created_id_idx ON articles (user_id, created DESC, id DESC);
+CREATE INDEX IF NOT EXISTS article_comments_article_id_created_id_idx ON article_comments (article_id, created, id);
@@ -0,0 +1,6 @@
+-- articles sorted by their favorites count the favorites of each article and trending articles only
+-- consider the favorites made recently, both of which are served by an index on the article and the
+-- time the favorite was made. articles sorted by comments are served by the index on the article
+-- and time of the comments while the newest and oldest sorts scan the index on the time the article
+-- was created in either direction.
+CREATE INDEX IF NOT EXISTS article_favs_article_id_created_idx ON article_favs (article_id, created);
@@ -0,0 +1,39 @@
+-- denormalize the number of favorites and comments of an article onto the article row so that the
+-- list queries do not need to count them for every article. the counters are maintained in the same
+-- statements that add and remove favorites and comments and reconciled periodically.
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS favorites_count BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS comments_count BIGINT NOT NULL DEFAULT 0;
+
+-- changes to the counters are not modifications of the article so the updated time is left as is.
+-- the comparison is made in the function since a WHEN condition cannot reference the whole row of a
+-- table with generated columns, which are also excluded as they are not computed yet.
+CREATE OR REPLACE FUNCTION set_article_updated()
+  RETURNS TRIGGER
+  LANGUAGE PLPGSQL
+  AS
+$$
+BEGIN
+  IF TO_JSONB(OLD) - '{favorites_count,comments_count,search_vector}'::text[] IS DISTINCT FROM TO_JSONB(NEW) - '{favorites_count,comments_count,search_vector}'::text[] THEN
+    NEW.updated = NOW();
+  END IF;
+  RETURN NEW;
+END
+$$;
+
+DROP TRIGGER IF EXISTS articles_set_updated ON articles;
+CREATE TRIGGER articles_set_updated
+    BEFORE UPDATE
+    ON articles
+    FOR EACH ROW
+    EXECUTE FUNCTION set_article_updated();
+
+UPDATE
+    articles AS a
+SET
+    favorites_count = (SELECT COUNT(*) FROM article_favs AS af WHERE af.article_id = a.id),
+    comments_count = (SELECT COUNT(*) FROM article_comments AS ac WHERE ac.article_id = a.id);
+
+-- the favorites and comments sorts order by the counters, using the time the article was created
+-- and the id to break ties
+CREATE INDEX IF NOT EXISTS articles_favorites_count_idx ON articles (favorites_count DESC, created DESC, id DESC);
+CREATE INDEX IF NOT EXISTS articles_comments_count_idx ON articles (comments_count DESC, created DESC, id DESC);
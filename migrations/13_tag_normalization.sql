@@ -0,0 +1,32 @@
+-- normalize the names of existing tags using the default rules: trimmed, lowercase, at most 32
+-- characters and without control or forbidden characters
+CREATE TEMPORARY TABLE tag_names AS
+SELECT id, created, TRIM(LEFT(LOWER(TRIM(REGEXP_REPLACE(name, '[,/?#&%[:cntrl:]]', '', 'g'))), 32)) AS name
+FROM tags;
+
+-- tags whose names normalize to the same value are merged into the one that was created first
+CREATE TEMPORARY TABLE tag_merges AS
+SELECT id, FIRST_VALUE(id) OVER (PARTITION BY name ORDER BY created ASC, id ASC) AS canonical_id, name
+FROM tag_names
+WHERE name <> '';
+
+INSERT INTO article_tags (article_id, tag_id, created)
+SELECT at.article_id, m.canonical_id, at.created
+FROM article_tags AS at
+INNER JOIN tag_merges AS m ON m.id = at.tag_id
+WHERE m.id <> m.canonical_id
+ON CONFLICT DO NOTHING;
+
+-- removes the merged tags along with tags that have no name left after normalization
+DELETE FROM article_tags
+WHERE tag_id NOT IN (SELECT id FROM tag_merges WHERE id = canonical_id);
+
+DELETE FROM tags
+WHERE id NOT IN (SELECT id FROM tag_merges WHERE id = canonical_id);
+
+UPDATE tags AS t
+SET name = m.name
+FROM tag_merges AS m
+WHERE t.id = m.id AND t.name <> m.name;
+
+DROP TABLE tag_merges, tag_names;
@@ -0,0 +1,15 @@
+-- create the article_bookmarks table to store the private reading lists of users along with how
+-- far through each bookmarked article the user has read, as a percentage
+CREATE TABLE IF NOT EXISTS article_bookmarks (
+  article_id UUID NOT NULL,
+  user_id UUID NOT NULL,
+  progress SMALLINT NOT NULL DEFAULT 0 CHECK (progress BETWEEN 0 AND 100),
+  created TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated TIMESTAMPTZ,
+  PRIMARY KEY(article_id, user_id),
+  CONSTRAINT fk_aid FOREIGN KEY(article_id) REFERENCES articles(id),
+  CONSTRAINT fk_uid FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+-- the bookmarks of a user are listed by the most recently bookmarked first
+CREATE INDEX IF NOT EXISTS article_bookmarks_user_id_created_article_id_idx ON article_bookmarks (user_id, created DESC, article_id DESC);
@@ -0,0 +1,28 @@
+-- create the collections table that stores the series of articles that users put together, such
+-- as multi-part tutorials
+CREATE TABLE IF NOT EXISTS collections (
+  id UUID PRIMARY KEY DEFAULT UUID_GENERATE_V4(),
+  user_id UUID NOT NULL,
+  slug TEXT NOT NULL UNIQUE,
+  title TEXT NOT NULL,
+  description TEXT NOT NULL,
+  created TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated TIMESTAMPTZ,
+  CONSTRAINT fk_uid FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+-- create the collection_articles table that stores the ordered articles of a collection. an article
+-- can be part of at most one collection so that it has a single previous and next article.
+CREATE TABLE IF NOT EXISTS collection_articles (
+  collection_id UUID NOT NULL,
+  article_id UUID NOT NULL UNIQUE,
+  position INTEGER NOT NULL,
+  created TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY(collection_id, article_id),
+  UNIQUE(collection_id, position),
+  CONSTRAINT fk_cid FOREIGN KEY(collection_id) REFERENCES collections(id),
+  CONSTRAINT fk_aid FOREIGN KEY(article_id) REFERENCES articles(id)
+);
+
+-- the collections of a user are listed by the most recently created first
+CREATE INDEX IF NOT EXISTS collections_user_id_created_id_idx ON collections (user_id, created DESC, id DESC);
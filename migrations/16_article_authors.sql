@@ -0,0 +1,24 @@
+-- create the article_authors table that stores the users who can edit an article. the primary author
+-- of an article is its owner, who can invite other users to become editors. editors only pass the
+-- checks to edit the article once they have accepted the invitation.
+CREATE TABLE IF NOT EXISTS article_authors (
+  article_id UUID NOT NULL,
+  user_id UUID NOT NULL,
+  role TEXT NOT NULL CHECK (role IN ('owner', 'editor')),
+  status TEXT NOT NULL CHECK (status IN ('pending', 'accepted')),
+  invited_by UUID,
+  created TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  responded TIMESTAMPTZ,
+  PRIMARY KEY(article_id, user_id),
+  CONSTRAINT fk_aid FOREIGN KEY(article_id) REFERENCES articles(id),
+  CONSTRAINT fk_uid FOREIGN KEY(user_id) REFERENCES users(id),
+  CONSTRAINT fk_iid FOREIGN KEY(invited_by) REFERENCES users(id)
+);
+
+-- the pending invitations of a user and the articles co-authored by a user are looked up by user
+CREATE INDEX IF NOT EXISTS article_authors_user_id_status_idx ON article_authors (user_id, status);
+
+-- every existing article is owned by its author
+INSERT INTO article_authors (article_id, user_id, role, status, created, responded)
+SELECT id, user_id, 'owner', 'accepted', created, created FROM articles
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,28 @@
+-- denormalize the total number of views of an article onto the article row. views are not written
+-- as they happen but rather aggregated from the events published for them and added periodically.
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS views_count BIGINT NOT NULL DEFAULT 0;
+
+-- changes to the views counter are not modifications of the article either so the updated time is
+-- left as is
+CREATE OR REPLACE FUNCTION set_article_updated()
+  RETURNS TRIGGER
+  LANGUAGE PLPGSQL
+  AS
+$$
+BEGIN
+  IF TO_JSONB(OLD) - '{favorites_count,comments_count,views_count,search_vector}'::text[] IS DISTINCT FROM TO_JSONB(NEW) - '{favorites_count,comments_count,views_count,search_vector}'::text[] THEN
+    NEW.updated = NOW();
+  END IF;
+  RETURN NEW;
+END
+$$;
+
+-- create the article_daily_views table to store the number of views of an article per day in UTC
+-- which make up the time series of the article stats
+CREATE TABLE IF NOT EXISTS article_daily_views (
+  article_id UUID NOT NULL,
+  day DATE NOT NULL,
+  views BIGINT NOT NULL DEFAULT 0,
+  PRIMARY KEY(article_id, day),
+  CONSTRAINT fk_aid FOREIGN KEY(article_id) REFERENCES articles(id)
+);
@@ -0,0 +1,25 @@
+-- store the number of words in the body of an article, excluding code, and the estimated time to
+-- read it which are computed from the Markdown whenever the body is saved
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS word_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS reading_time_minutes INTEGER NOT NULL DEFAULT 0;
+
+-- the Markdown of existing articles cannot be parsed here so approximate the counts by removing
+-- fenced code blocks and counting the remaining words at the default of 200 words per minute. the
+-- counts are computed exactly the next time that the article is updated.
+UPDATE
+    articles AS a
+SET
+    word_count = c.word_count,
+    reading_time_minutes = CEIL(c.word_count / 200.0)
+FROM (
+    SELECT
+        ar.id,
+        COALESCE(ARRAY_LENGTH(REGEXP_SPLIT_TO_ARRAY(NULLIF(TRIM(REGEXP_REPLACE(REGEXP_REPLACE(ar.body, '```.*?(```|$)', ' ', 'gs'), '~~~.*?(~~~|$)', ' ', 'gs')), ''), '\s+'), 1), 0) AS word_count
+    FROM
+        articles AS ar
+) AS c
+WHERE
+    a.id = c.id;
+
+-- listings can be filtered by the estimated reading time
+CREATE INDEX IF NOT EXISTS articles_reading_time_minutes_idx ON articles (reading_time_minutes);
@@ -0,0 +1,8 @@
+-- create the feed_tokens table to store the secret token that identifies a user in the URL of their
+-- private feed since feed readers are not able to send the authentication header
+CREATE TABLE IF NOT EXISTS feed_tokens (
+  user_id UUID PRIMARY KEY,
+  token TEXT UNIQUE NOT NULL,
+  created TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  CONSTRAINT fk_uid FOREIGN KEY(user_id) REFERENCES users(id)
+);
@@ -0,0 +1,45 @@
+-- articles and comments are moved to the trash when they are deleted and only removed for good once
+-- they have been in the trash for the configured retention period so that they can be restored
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE article_comments ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+-- moving an article to the trash and restoring it are not modifications of the article so the
+-- updated time is left as is
+CREATE OR REPLACE FUNCTION set_article_updated()
+  RETURNS TRIGGER
+  LANGUAGE PLPGSQL
+  AS
+$$
+BEGIN
+  IF TO_JSONB(OLD) - '{favorites_count,comments_count,views_count,search_vector,deleted_at}'::text[] IS DISTINCT FROM TO_JSONB(NEW) - '{favorites_count,comments_count,views_count,search_vector,deleted_at}'::text[] THEN
+    NEW.updated = NOW();
+  END IF;
+  RETURN NEW;
+END
+$$;
+
+-- the same applies to comments, which previously had their updated time set on any change
+CREATE OR REPLACE FUNCTION set_comment_updated()
+  RETURNS TRIGGER
+  LANGUAGE PLPGSQL
+  AS
+$$
+BEGIN
+  IF TO_JSONB(OLD) - 'deleted_at' IS DISTINCT FROM TO_JSONB(NEW) - 'deleted_at' THEN
+    NEW.updated = NOW();
+  END IF;
+  RETURN NEW;
+END
+$$;
+
+DROP TRIGGER IF EXISTS article_comments_set_updated ON article_comments;
+CREATE TRIGGER article_comments_set_updated
+    BEFORE UPDATE
+    ON article_comments
+    FOR EACH ROW
+    EXECUTE FUNCTION set_comment_updated();
+
+-- the trash of a user is listed most recently deleted first and the purge selects the rows that have
+-- been in the trash the longest, neither of which needs to consider the rows that are not deleted
+CREATE INDEX IF NOT EXISTS articles_deleted_at_idx ON articles (deleted_at, id) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS article_comments_deleted_at_idx ON article_comments (deleted_at, id) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,18 @@
+-- articles created before the rendered HTML of their body was stored are backfilled by a task that
+-- runs when the application starts. rendering the stored body does not modify the article so the
+-- updated time is left as is when only the rendered HTML is set
+CREATE OR REPLACE FUNCTION set_article_updated()
+  RETURNS TRIGGER
+  LANGUAGE PLPGSQL
+  AS
+$$
+BEGIN
+  IF TO_JSONB(OLD) - '{favorites_count,comments_count,views_count,search_vector,deleted_at,body_html}'::text[] IS DISTINCT FROM TO_JSONB(NEW) - '{favorites_count,comments_count,views_count,search_vector,deleted_at,body_html}'::text[] THEN
+    NEW.updated = NOW();
+  END IF;
+  RETURN NEW;
+END
+$$;
+
+-- the backfill only needs to find the articles that have not been rendered yet
+CREATE INDEX IF NOT EXISTS articles_unrendered_idx ON articles (id) WHERE body_html IS NULL;
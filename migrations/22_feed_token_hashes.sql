@@ -0,0 +1,6 @@
+-- store a hash of the secret token in the URL of the private feed of a user rather than the token
+-- itself so that the URLs cannot be recovered from the database. the tokens are random so a single
+-- unsalted sha256 is enough, and existing tokens keep working since they are hashed in place.
+ALTER TABLE feed_tokens RENAME COLUMN token TO token_hash;
+
+UPDATE feed_tokens SET token_hash = ENCODE(SHA256(CONVERT_TO(token_hash, 'UTF8')), 'hex');
@@ -0,0 +1,37 @@
+-- the word count and reading time are derived from the body so setting them does not modify the
+-- article and the updated time is left as is when only they change
+CREATE OR REPLACE FUNCTION set_article_updated()
+  RETURNS TRIGGER
+  LANGUAGE PLPGSQL
+  AS
+$$
+BEGIN
+  IF TO_JSONB(OLD) - '{favorites_count,comments_count,views_count,search_vector,deleted_at,body_html,word_count,reading_time_minutes}'::text[] IS DISTINCT FROM TO_JSONB(NEW) - '{favorites_count,comments_count,views_count,search_vector,deleted_at,body_html,word_count,reading_time_minutes}'::text[] THEN
+    NEW.updated = NOW();
+  END IF;
+  RETURN NEW;
+END
+$$;
+
+-- the backfill of the counts in the reading time migration ran before they were excluded above, so
+-- every article with a body was marked as updated at the time it was applied. those articles are
+-- found by the time the migration was recorded as installed, which is the time of the transaction
+-- that it ran in. their updated time is restored from the latest revision of their content or the
+-- time they were published, whichever is later, falling back to the time they were created.
+DO
+$$
+BEGIN
+  IF TO_REGCLASS('_sqlx_migrations') IS NOT NULL THEN
+    ALTER TABLE articles DISABLE TRIGGER articles_set_updated;
+
+    UPDATE
+        articles AS a
+    SET
+        updated = GREATEST(a.created, a.published_at, (SELECT MAX(r.created) FROM article_revisions AS r WHERE r.article_id = a.id))
+    WHERE
+        a.updated = (SELECT m.installed_on FROM _sqlx_migrations AS m WHERE m.version = 18);
+
+    ALTER TABLE articles ENABLE TRIGGER articles_set_updated;
+  END IF;
+END
+$$;
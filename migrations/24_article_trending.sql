@@ -0,0 +1,44 @@
+-- sorting by trending used to sum the decayed favorites of every article that matched the filters
+-- when the listing was requested. the sum is now stored on the article and refreshed periodically
+-- so that, like the favorites and comments sorts, the trending sort scans an index on the stored
+-- value with the time the article was created and its id as the tie-breaks. the newest and oldest
+-- sorts scan the index on the time the article was created in either direction.
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS trending_score DOUBLE PRECISION NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS articles_trending_score_idx ON articles (trending_score DESC, created DESC, id DESC);
+
+-- the refresh only sums the favorites made in the last thirty days
+CREATE INDEX IF NOT EXISTS article_favs_created_idx ON article_favs (created);
+
+-- the trending score is derived from the favorites so refreshing it does not modify the article and
+-- the updated time is left as is
+CREATE OR REPLACE FUNCTION set_article_updated()
+  RETURNS TRIGGER
+  LANGUAGE PLPGSQL
+  AS
+$$
+BEGIN
+  IF TO_JSONB(OLD) - '{favorites_count,comments_count,views_count,trending_score,search_vector,deleted_at,body_html,word_count,reading_time_minutes}'::text[] IS DISTINCT FROM TO_JSONB(NEW) - '{favorites_count,comments_count,views_count,trending_score,search_vector,deleted_at,body_html,word_count,reading_time_minutes}'::text[] THEN
+    NEW.updated = NOW();
+  END IF;
+  RETURN NEW;
+END
+$$;
+
+UPDATE
+    articles AS a
+SET
+    trending_score = c.trending_score
+FROM (
+    SELECT
+        af.article_id,
+        SUM(POWER(0.5, EXTRACT(EPOCH FROM NOW() - af.created) / 86400)) AS trending_score
+    FROM
+        article_favs AS af
+    WHERE
+        af.created > NOW() - INTERVAL '30 days'
+    GROUP BY
+        af.article_id
+) AS c
+WHERE
+    a.id = c.article_id;
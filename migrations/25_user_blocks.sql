@@ -0,0 +1,13 @@
+-- create the table that tracks the users that each user has blocked. blocked users are never
+-- suggested to the user who blocked them and vice versa.
+CREATE TABLE IF NOT EXISTS user_blocks (
+  user_id UUID NOT NULL,
+  blocker_id UUID NOT NULL,
+  created TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY(user_id, blocker_id),
+  CONSTRAINT fk_uid FOREIGN KEY(user_id) REFERENCES users(id),
+  CONSTRAINT fk_bid FOREIGN KEY(blocker_id) REFERENCES users(id)
+);
+
+-- the users that a user has blocked are looked up when their suggestions are fetched
+CREATE INDEX IF NOT EXISTS user_blocks_blocker_id_idx ON user_blocks (blocker_id);
@@ -0,0 +1,25 @@
+-- create the user_settings table to store the notification and privacy preferences of a user
+CREATE TABLE IF NOT EXISTS user_settings (
+  user_id UUID PRIMARY KEY,
+  email_on_comment BOOLEAN NOT NULL DEFAULT TRUE,
+  email_on_follow BOOLEAN NOT NULL DEFAULT TRUE,
+  digest_frequency TEXT NOT NULL DEFAULT 'weekly',
+  show_favorites_publicly BOOLEAN NOT NULL DEFAULT TRUE,
+  default_article_visibility TEXT NOT NULL DEFAULT 'public',
+  created TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated TIMESTAMPTZ,
+  CONSTRAINT fk_uid FOREIGN KEY(user_id) REFERENCES users(id),
+  CONSTRAINT chk_digest_frequency CHECK (digest_frequency IN ('never', 'daily', 'weekly')),
+  CONSTRAINT chk_default_article_visibility CHECK (default_article_visibility IN ('public', 'unlisted'))
+);
+
+-- create trigger on user_settings table that sets updated column when a row is changed
+CREATE TRIGGER user_settings_set_updated
+    BEFORE UPDATE
+    ON user_settings
+    FOR EACH ROW
+    WHEN (OLD IS DISTINCT FROM NEW)
+    EXECUTE FUNCTION set_updated();
+
+-- every user has a settings row so create one with the defaults for any existing users
+INSERT INTO user_settings (user_id) SELECT id FROM users ON CONFLICT DO NOTHING;
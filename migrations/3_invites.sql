@@ -0,0 +1,28 @@
+-- flag users that are allowed to perform administrative actions such as creating invites. there is
+-- no API to grant the flag so it must be set directly in the database.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS admin BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- create the invites table to store the invite codes required to register when the application is
+-- running in invite-only mode
+CREATE TABLE IF NOT EXISTS invites (
+  id UUID PRIMARY KEY DEFAULT UUID_GENERATE_V4(),
+  code TEXT UNIQUE NOT NULL,
+  user_id UUID NOT NULL,
+  max_uses INT NOT NULL DEFAULT 1,
+  uses INT NOT NULL DEFAULT 0,
+  expires TIMESTAMPTZ,
+  created TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  CONSTRAINT fk_uid FOREIGN KEY(user_id) REFERENCES users(id),
+  CONSTRAINT chk_max_uses CHECK (max_uses > 0),
+  CONSTRAINT chk_uses CHECK (uses >= 0 AND uses <= max_uses)
+);
+
+-- create the invite_redemptions table to track which users registered using an invite
+CREATE TABLE IF NOT EXISTS invite_redemptions (
+  invite_id UUID NOT NULL,
+  user_id UUID NOT NULL,
+  created TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY(invite_id, user_id),
+  CONSTRAINT fk_iid FOREIGN KEY(invite_id) REFERENCES invites(id),
+  CONSTRAINT fk_uid FOREIGN KEY(user_id) REFERENCES users(id)
+);
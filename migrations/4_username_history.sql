@@ -0,0 +1,13 @@
+-- create the username_history table to store the previous usernames of a user so that links using
+-- an old username can still be resolved and the username can be reserved against squatting
+CREATE TABLE IF NOT EXISTS username_history (
+  id UUID PRIMARY KEY DEFAULT UUID_GENERATE_V4(),
+  user_id UUID NOT NULL,
+  name TEXT NOT NULL,
+  created TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  CONSTRAINT fk_uid FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+-- previous usernames are looked up by name when resolving profiles and by user when rate limiting
+CREATE INDEX IF NOT EXISTS username_history_name_idx ON username_history (name, created DESC);
+CREATE INDEX IF NOT EXISTS username_history_user_id_idx ON username_history (user_id, created DESC);
@@ -0,0 +1,15 @@
+-- add the publication status of an article along with the time it was first published. articles
+-- that already exist were published at the time they were created.
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'published';
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS published_at TIMESTAMPTZ;
+ALTER TABLE articles ADD CONSTRAINT chk_status CHECK (status IN ('draft', 'published', 'unlisted'));
+
+-- the trigger is disabled while backfilling so the updated time of existing articles is preserved
+ALTER TABLE articles DISABLE TRIGGER articles_set_updated;
+UPDATE articles SET published_at = created WHERE status = 'published' AND published_at IS NULL;
+ALTER TABLE articles ENABLE TRIGGER articles_set_updated;
+
+-- list queries only ever return published articles, other than those belonging to the viewer, and
+-- the drafts listing looks up the articles of a single user by status
+CREATE INDEX IF NOT EXISTS articles_status_created_idx ON articles (status, created DESC);
+CREATE INDEX IF NOT EXISTS articles_user_id_status_idx ON articles (user_id, status, created DESC);
@@ -0,0 +1,8 @@
+-- articles can be scheduled to be published at a time in the future in which case they remain
+-- hidden until a background task publishes them once the publish time has passed
+ALTER TABLE articles DROP CONSTRAINT IF EXISTS chk_status;
+ALTER TABLE articles ADD CONSTRAINT chk_status CHECK (status IN ('draft', 'published', 'scheduled', 'unlisted'));
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS publish_at TIMESTAMPTZ;
+
+-- the scheduler only ever looks for scheduled articles ordered by the time they are due
+CREATE INDEX IF NOT EXISTS articles_publish_at_idx ON articles (publish_at) WHERE status = 'scheduled';
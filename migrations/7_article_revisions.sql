@@ -0,0 +1,24 @@
+-- create the article_revisions table to store the content of an article each time it is created or
+-- updated along with the user who made the change
+CREATE TABLE IF NOT EXISTS article_revisions (
+  id UUID PRIMARY KEY DEFAULT UUID_GENERATE_V4(),
+  article_id UUID NOT NULL,
+  user_id UUID NOT NULL,
+  revision INTEGER NOT NULL,
+  title TEXT NOT NULL,
+  description TEXT NOT NULL,
+  body TEXT NOT NULL,
+  created TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE(article_id, revision),
+  CONSTRAINT fk_aid FOREIGN KEY(article_id) REFERENCES articles(id),
+  CONSTRAINT fk_uid FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+-- the current content of existing articles becomes their first revision
+INSERT INTO
+  article_revisions (article_id, user_id, revision, title, description, body, created)
+SELECT
+  id, user_id, 1, title, description, body, COALESCE(updated, created)
+FROM
+  articles
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,4 @@
+-- cache the sanitized HTML rendered from the Markdown body of an article so that it does not have
+-- to be rendered each time the article is read. articles created before this migration have no
+-- rendered HTML until they are next updated.
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS body_html TEXT;
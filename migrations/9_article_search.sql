@@ -0,0 +1,9 @@
+-- maintain a weighted full-text search document for each article so that articles can be searched
+-- by the terms in their title, description and body with matches in the title ranked highest
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS search_vector TSVECTOR GENERATED ALWAYS AS (
+  SETWEIGHT(TO_TSVECTOR('english', title), 'A') ||
+  SETWEIGHT(TO_TSVECTOR('english', description), 'B') ||
+  SETWEIGHT(TO_TSVECTOR('english', body), 'C')
+) STORED;
+
+CREATE INDEX IF NOT EXISTS articles_search_vector_idx ON articles USING GIN (search_vector);
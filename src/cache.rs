@@ -0,0 +1,222 @@
+use crate::{
+    config::Config,
+    db::{sitemap::SitemapPage, user::Profile},
+};
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+/// Minimum number of entries a [`Cache`] holds before inserting into it drops the expired entries.
+const MIN_PRUNE_LEN: usize = 1024;
+
+/// The [`Cache`] struct is a simple thread-safe in-memory cache where each entry expires once the
+/// configured time-to-live has elapsed since it was inserted. In a real production application
+/// with multiple instances you would most likely want to reach for something like Redis instead so
+/// that all instances share the same cached data.
+#[derive(Debug)]
+pub struct Cache<K, V> {
+    /// Amount of time an entry remains valid after being inserted.
+    ttl: Duration,
+    /// Cached values keyed by the cache key along with the time they were inserted.
+    entries: RwLock<HashMap<K, (Instant, V)>>,
+    /// Number of entries at which [`Cache::insert`] or [`Cache::insert_if_absent`] next drops the
+    /// expired entries. Only changed while holding the write lock on the entries.
+    prune_len: AtomicUsize,
+}
+
+impl<K, V> Cache<K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    /// Creates a new empty [`Cache`] whose entries expire after the given [`Duration`].
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+            prune_len: AtomicUsize::new(MIN_PRUNE_LEN),
+        }
+    }
+
+    /// Returns a clone of the cached value for the key if it exists and has not yet expired.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.read().expect("cache lock poisoned");
+
+        entries
+            .get(key)
+            .filter(|(inserted, _)| inserted.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Inserts the value into the cache for the key replacing any value that already exists.
+    pub fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.write().expect("cache lock poisoned");
+
+        self.prune(&mut entries);
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    /// Inserts the value into the cache for the key unless a value that has not yet expired already
+    /// exists. Returns `true` if the value was inserted.
+    ///
+    /// This is called on hot paths such as counting the views of articles, so an expired value for
+    /// the key is replaced in place rather than waiting for the expired entries to be dropped.
+    pub fn insert_if_absent(&self, key: K, value: V) -> bool {
+        let mut entries = self.entries.write().expect("cache lock poisoned");
+
+        self.prune(&mut entries);
+
+        match entries.entry(key) {
+            Entry::Occupied(entry) if entry.get().0.elapsed() < self.ttl => false,
+            Entry::Occupied(mut entry) => {
+                entry.insert((Instant::now(), value));
+                true
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((Instant::now(), value));
+                true
+            }
+        }
+    }
+
+    /// Drops the expired entries so the map does not grow unbounded. Rather than scanning every entry
+    /// on each insert, the expired entries are only dropped once the number of entries has doubled
+    /// since they were last dropped, which keeps the cost of inserting constant on average.
+    fn prune(&self, entries: &mut HashMap<K, (Instant, V)>) {
+        if entries.len() >= self.prune_len.load(Ordering::Relaxed) {
+            entries.retain(|_, (inserted, _)| inserted.elapsed() < self.ttl);
+            self.prune_len
+                .store((entries.len() * 2).max(MIN_PRUNE_LEN), Ordering::Relaxed);
+        }
+    }
+
+    /// Removes the cached value for the key, if one exists.
+    pub fn invalidate(&self, key: &K) {
+        self.entries
+            .write()
+            .expect("cache lock poisoned")
+            .remove(key);
+    }
+
+    /// Removes the cached values for which the predicate returns `true`.
+    pub fn invalidate_if<F>(&self, f: F)
+    where
+        F: Fn(&K, &V) -> bool,
+    {
+        self.entries
+            .write()
+            .expect("cache lock poisoned")
+            .retain(|key, (_, value)| !f(key, value));
+    }
+}
+
+/// The [`Caches`] struct contains all of the in-memory caches used by the application so they can
+/// be shared between the HTTP handlers that populate them and the event consumer that invalidates
+/// them.
+#[derive(Debug)]
+pub struct Caches {
+    /// Follow suggestions for a user keyed by the id of the user.
+    pub suggestions: Cache<Uuid, Vec<Profile>>,
+    /// Ids of the articles related to an article, most similar first, keyed by the id of the
+    /// article.
+    pub related: Cache<Uuid, Vec<Uuid>>,
+    /// Pages of the sitemap, which are shared by all users so the key is always the unit value.
+    pub sitemap: Cache<(), Vec<SitemapPage>>,
+    /// Recent views of articles keyed by the id of the article and the user or anonymous visitor
+    /// who viewed it, which are used to only count repeated views once per configured window.
+    pub views: Cache<(Uuid, String), ()>,
+}
+
+impl Caches {
+    /// Creates a new set of empty [`Caches`] configured using the given [`Config`].
+    pub fn with_config(config: &Config) -> Self {
+        let ttl = Duration::from_millis(config.cache.ttl);
+
+        Self {
+            suggestions: Cache::new(ttl),
+            related: Cache::new(ttl),
+            sitemap: Cache::new(ttl),
+            views: Cache::new(Duration::from_millis(config.views.window)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that a cached value is returned until it is invalidated.
+    #[test]
+    fn verify_insert_and_invalidate() {
+        let cache = Cache::new(Duration::from_secs(60));
+
+        cache.insert(1, "one");
+        assert_eq!(Some("one"), cache.get(&1));
+
+        cache.invalidate(&1);
+        assert_eq!(None, cache.get(&1));
+    }
+
+    /// Verifies that only the cached values matching the predicate are invalidated.
+    #[test]
+    fn verify_invalidate_if() {
+        let cache = Cache::new(Duration::from_secs(60));
+
+        cache.insert(1, vec![2, 3]);
+        cache.insert(2, vec![1]);
+        cache.invalidate_if(|key, value| *key == 3 || value.contains(&3));
+
+        assert_eq!(None, cache.get(&1));
+        assert_eq!(Some(vec![1]), cache.get(&2));
+    }
+
+    /// Verifies that a value is only inserted if the key does not already have a value.
+    #[test]
+    fn verify_insert_if_absent() {
+        let cache = Cache::new(Duration::from_secs(60));
+
+        assert!(cache.insert_if_absent(1, "one"));
+        assert!(!cache.insert_if_absent(1, "uno"));
+        assert_eq!(Some("one"), cache.get(&1));
+    }
+
+    /// Verifies that an expired value is replaced when inserting a value if the key is absent.
+    #[test]
+    fn verify_insert_if_absent_replaces_expired() {
+        let cache = Cache::new(Duration::ZERO);
+
+        assert!(cache.insert_if_absent(1, "one"));
+        assert!(cache.insert_if_absent(1, "uno"));
+    }
+
+    /// Verifies that a cached value is no longer returned once it has expired.
+    #[test]
+    fn verify_expired_entry_not_returned() {
+        let cache = Cache::new(Duration::ZERO);
+
+        cache.insert(1, "one");
+        assert_eq!(None, cache.get(&1));
+    }
+
+    /// Verifies that the expired entries are only dropped once the cache holds enough entries.
+    #[test]
+    fn verify_insert_prunes_expired_entries() {
+        let cache = Cache::new(Duration::ZERO);
+
+        for key in 0..MIN_PRUNE_LEN {
+            cache.insert(key, "value");
+        }
+        assert_eq!(MIN_PRUNE_LEN, cache.entries.read().unwrap().len());
+
+        cache.insert(MIN_PRUNE_LEN, "value");
+        assert_eq!(1, cache.entries.read().unwrap().len());
+    }
+}
@@ -82,6 +82,207 @@ pub struct Outbox {
     pub batch_size: u64,
 }
 
+/// The [`Scheduler`] struct contains all of the configuration values related to the background task
+/// that publishes scheduled articles once their publish time has passed.
+#[derive(Debug, Deserialize)]
+pub struct Scheduler {
+    /// Time in milliseconds between checks for scheduled articles that are due to be published.
+    pub interval: u64,
+    /// Maximum number of scheduled articles that should be published in a single check.
+    pub batch_size: u64,
+}
+
+/// The [`Reconciliation`] struct contains all of the configuration values related to the background
+/// task that reconciles the favorites, comments and views counts of articles.
+#[derive(Debug, Deserialize)]
+pub struct Reconciliation {
+    /// Time in milliseconds between reconciliations of the counters of all articles.
+    pub interval: u64,
+    /// Maximum number of articles whose counters are reconciled in a single transaction.
+    pub batch_size: u64,
+}
+
+/// The [`Trending`] struct contains all of the configuration values related to the background task
+/// that recomputes the trending scores of articles as their favorites decay.
+#[derive(Debug, Deserialize)]
+pub struct Trending {
+    /// Time in milliseconds between refreshes of the trending scores of articles.
+    pub interval: u64,
+}
+
+/// The [`Trash`] struct contains all of the configuration values related to deleted articles and
+/// comments, which are kept in the trash so that they can be restored until a background task
+/// purges them.
+#[derive(Debug, Deserialize)]
+pub struct Trash {
+    /// Time in seconds that a deleted article or comment is kept in the trash before it is purged.
+    pub retention: u64,
+    /// Time in milliseconds between checks for articles and comments that are due to be purged.
+    pub interval: u64,
+    /// Maximum number of articles or comments that should be purged in a single transaction.
+    pub batch_size: u64,
+}
+
+/// The [`Bulk`] struct contains all of the configuration values related to importing and exporting
+/// articles in bulk as an archive of Markdown files.
+#[derive(Debug, Deserialize)]
+pub struct Bulk {
+    /// Maximum size in bytes of an uploaded archive, which also limits the size of the files in it.
+    pub max_import_size: usize,
+    /// Maximum number of articles that can be imported from a single archive.
+    pub max_import_articles: usize,
+}
+
+/// The [`Registration`] struct contains all of the configuration values related to registering new
+/// users with the application.
+#[derive(Debug, Deserialize)]
+pub struct Registration {
+    /// Flag indicating whether a valid invite code is required to register a new user.
+    pub invite_only: bool,
+}
+
+/// The [`Username`] struct contains all of the configuration values related to users changing
+/// their username.
+#[derive(Debug, Deserialize)]
+pub struct Username {
+    /// Time in seconds that a previous username continues to resolve to the user after it has been
+    /// changed.
+    pub grace_period: u64,
+    /// Time in seconds that a previous username is reserved for the user after it has been changed
+    /// so that it cannot be taken by another user.
+    pub reservation_period: u64,
+    /// Minimum time in seconds that must pass between changes to the username of a user.
+    pub change_interval: u64,
+}
+
+/// The [`Cache`] struct contains all of the configuration values related to the in-memory caches
+/// used by the application.
+#[derive(Debug, Deserialize)]
+pub struct Cache {
+    /// Time in milliseconds that an entry is kept in a cache before it expires.
+    pub ttl: u64,
+}
+
+/// The [`Tags`] struct contains all of the configuration values related to the normalization of the
+/// names of the tags that are set on articles.
+///
+/// The names of the tags that existed before normalization was introduced were normalized by a
+/// migration using the default values, since migrations cannot read the configuration. Changing
+/// these values later only applies to the tags that are set on articles after the change, so any
+/// existing tags keep the names they were given under the previous rules.
+#[derive(Debug, Deserialize)]
+pub struct Tags {
+    /// Flag indicating whether the names of tags are converted to lowercase.
+    pub lowercase: bool,
+    /// Maximum number of characters in the name of a tag.
+    pub max_length: usize,
+    /// Characters that are not allowed in the name of a tag, in addition to control characters.
+    pub forbidden_characters: String,
+}
+
+/// The [`Views`] struct contains all of the configuration values related to recording and
+/// aggregating the views of articles.
+#[derive(Debug, Deserialize)]
+pub struct Views {
+    /// Time in milliseconds during which repeated views of an article by the same user or anonymous
+    /// visitor are only counted once.
+    pub window: u64,
+    /// Time in milliseconds between writes of the aggregated view counts to the database. The Kafka
+    /// offsets of the view events are only committed after the views are written, so views are not
+    /// lost when the application stops, although views written just before it stops may be counted
+    /// again.
+    pub flush_interval: u64,
+    /// Default number of days included in the daily time series of the stats of an article.
+    pub stats_days: u32,
+    /// Maximum number of days that can be requested in the daily time series of the stats of an
+    /// article.
+    pub max_stats_days: u32,
+}
+
+/// The [`Reading`] struct contains all of the configuration values related to estimating the time
+/// it takes to read an article.
+#[derive(Debug, Deserialize)]
+pub struct Reading {
+    /// Number of words that a reader is assumed to read per minute.
+    pub words_per_minute: u32,
+}
+
+/// The [`Feeds`] struct contains all of the configuration values related to the RSS and Atom feeds
+/// of articles.
+#[derive(Debug, Deserialize)]
+pub struct Feeds {
+    /// Title of the application that the titles of the feeds are prefixed with.
+    pub title: String,
+    /// Base URL of the web application that the articles and profiles in the feeds link to.
+    pub site_url: String,
+    /// Base URL from which the feeds are publicly served.
+    pub public_url: String,
+    /// Maximum number of articles included in a feed.
+    pub size: i32,
+}
+
+/// The [`Sitemap`] struct contains all of the configuration values related to the XML sitemap of
+/// the published content of the application.
+#[derive(Debug, Deserialize)]
+pub struct Sitemap {
+    /// Base URL of the web application that the entries in the sitemap link to.
+    pub site_url: String,
+    /// Base URL from which the sitemap is publicly served.
+    pub public_url: String,
+    /// Maximum number of entries in a single page of the sitemap, which must not exceed 50,000.
+    pub page_size: i64,
+}
+
+/// Enumerates the supported backends for storing objects uploaded to the application.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// Objects are stored on the local file system.
+    Local,
+    /// Objects are stored in an S3 compatible bucket.
+    S3,
+}
+
+/// The [`LocalStorage`] struct contains the configuration values for storing objects on the local
+/// file system.
+#[derive(Debug, Deserialize)]
+pub struct LocalStorage {
+    /// Path to the directory in which objects are stored.
+    pub path: String,
+}
+
+/// The [`S3Storage`] struct contains the configuration values for storing objects in an S3
+/// compatible bucket, e.g. AWS S3 or MinIO.
+#[derive(Debug, Deserialize)]
+pub struct S3Storage {
+    /// Name of the bucket in which objects are stored.
+    pub bucket: String,
+    /// Region that the bucket resides in.
+    pub region: String,
+    /// URL of the S3 compatible API endpoint.
+    pub endpoint: String,
+    /// Access key used to authenticate with the S3 compatible API.
+    pub access_key: String,
+    /// Secret key used to authenticate with the S3 compatible API.
+    pub secret_key: String,
+}
+
+/// The [`Storage`] struct contains all of the configuration values related to storing objects,
+/// such as user avatars, that are uploaded to the application.
+#[derive(Debug, Deserialize)]
+pub struct Storage {
+    /// Backend used to store uploaded objects.
+    pub backend: StorageBackend,
+    /// Base URL from which stored objects are publicly served.
+    pub public_url: String,
+    /// Maximum size in bytes of an uploaded object.
+    pub max_upload_size: usize,
+    /// Configuration for the local file system backend.
+    pub local: LocalStorage,
+    /// Configuration for the S3 compatible backend.
+    pub s3: S3Storage,
+}
+
 /// The [`Config`] struct contains all of the available application configuration.
 #[derive(Debug, Deserialize)]
 pub struct Config {
@@ -93,6 +294,34 @@ pub struct Config {
     pub kafka: Kafka,
     /// Outbox configuration for the application.
     pub outbox: Outbox,
+    /// Article publishing scheduler configuration for the application.
+    pub scheduler: Scheduler,
+    /// Article counter reconciliation configuration for the application.
+    pub reconciliation: Reconciliation,
+    /// Article trending score configuration for the application.
+    pub trending: Trending,
+    /// Trash configuration for the application.
+    pub trash: Trash,
+    /// Bulk import and export configuration for the application.
+    pub bulk: Bulk,
+    /// Registration configuration for the application.
+    pub registration: Registration,
+    /// Username configuration for the application.
+    pub username: Username,
+    /// Cache configuration for the application.
+    pub cache: Cache,
+    /// Tag configuration for the application.
+    pub tags: Tags,
+    /// Article view configuration for the application.
+    pub views: Views,
+    /// Reading time configuration for the application.
+    pub reading: Reading,
+    /// Article feed configuration for the application.
+    pub feeds: Feeds,
+    /// Sitemap configuration for the application.
+    pub sitemap: Sitemap,
+    /// Object storage configuration for the application.
+    pub storage: Storage,
 }
 
 impl Config {
@@ -159,6 +388,56 @@ mod tests {
 
         assert_eq!(30000, config.outbox.interval);
         assert_eq!(100, config.outbox.batch_size);
+
+        assert_eq!(60000, config.scheduler.interval);
+        assert_eq!(100, config.scheduler.batch_size);
+
+        assert_eq!(3600000, config.reconciliation.interval);
+        assert_eq!(1000, config.reconciliation.batch_size);
+
+        assert_eq!(300000, config.trending.interval);
+
+        assert_eq!(2592000, config.trash.retention);
+        assert_eq!(3600000, config.trash.interval);
+        assert_eq!(100, config.trash.batch_size);
+
+        assert_eq!(10485760, config.bulk.max_import_size);
+        assert_eq!(500, config.bulk.max_import_articles);
+
+        assert!(!config.registration.invite_only);
+
+        assert_eq!(2592000, config.username.grace_period);
+        assert_eq!(7776000, config.username.reservation_period);
+        assert_eq!(2592000, config.username.change_interval);
+
+        assert_eq!(300000, config.cache.ttl);
+
+        assert!(config.tags.lowercase);
+        assert_eq!(32, config.tags.max_length);
+        assert_eq!(",/?#&%", config.tags.forbidden_characters);
+
+        assert_eq!(1800000, config.views.window);
+        assert_eq!(60000, config.views.flush_interval);
+        assert_eq!(30, config.views.stats_days);
+        assert_eq!(365, config.views.max_stats_days);
+
+        assert_eq!(200, config.reading.words_per_minute);
+
+        assert_eq!("Conduit", config.feeds.title);
+        assert_eq!("http://localhost:4100", config.feeds.site_url);
+        assert_eq!("http://localhost:7100/feeds", config.feeds.public_url);
+        assert_eq!(20, config.feeds.size);
+
+        assert_eq!("http://localhost:4100", config.sitemap.site_url);
+        assert_eq!("http://localhost:7100", config.sitemap.public_url);
+        assert_eq!(10000, config.sitemap.page_size);
+
+        assert_eq!(StorageBackend::Local, config.storage.backend);
+        assert_eq!("http://localhost:7100/assets", config.storage.public_url);
+        assert_eq!(5242880, config.storage.max_upload_size);
+        assert_eq!("uploads", config.storage.local.path);
+        assert_eq!("realworld", config.storage.s3.bucket);
+        assert_eq!("http://localhost:9000", config.storage.s3.endpoint);
     }
 
     /// Verifies that a configured env variable correctly overrides the corresponding configuration
@@ -1,98 +1,407 @@
-use crate::db::tag::Tag;
+use crate::db::{
+    in_page_order,
+    tag::Tag,
+    user::{ArticleVisibility, Profile},
+    Page,
+};
 
 use chrono::{DateTime, Utc};
-use serde::Serialize;
-use sqlx::{FromRow, PgConnection};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, FromRow, PgConnection};
 use uuid::Uuid;
 
+/// Expands to the conditions of the `WHERE` clause shared by the list articles query and the count
+/// query so that the total count always matches the filters of the listing. The filters are bound to
+/// the parameters `$1` through `$11`.
+macro_rules! list_articles_filters {
+    () => {
+        concat!(
+            r#"
+        a.deleted_at IS NULL
+
+        AND
+
+        (CARDINALITY($2::text[]) = 0 OR CASE WHEN $6::text = 'any'
+            THEN EXISTS(SELECT 1 FROM article_tags AS at INNER JOIN tags AS t ON at.tag_id = t.id WHERE at.article_id = a.id AND t.name = ANY($2))
+            ELSE (SELECT COUNT(*) FROM article_tags AS at INNER JOIN tags AS t ON at.tag_id = t.id WHERE at.article_id = a.id AND t.name = ANY($2)) = CARDINALITY($2)
+        END)
+
+        AND
+
+        (CARDINALITY($7::text[]) = 0 OR NOT EXISTS(SELECT 1 FROM article_tags AS at INNER JOIN tags AS t ON at.tag_id = t.id WHERE at.article_id = a.id AND t.name = ANY($7)))
+
+        AND
+
+        (CARDINALITY($3::text[]) = 0 OR u.name = ANY($3) OR EXISTS(SELECT 1 FROM article_authors AS aa INNER JOIN users AS cu ON aa.user_id = cu.id WHERE aa.article_id = a.id AND aa.status = 'accepted' AND cu.name = ANY($3)))
+
+        AND
+
+        ($4::text IS NULL OR EXISTS(SELECT 1 FROM users AS u INNER JOIN article_favs AS af ON u.id = af.user_id WHERE af.article_id = a.id AND u.name = $4))
+
+        AND
+
+        ($5::text IS NULL OR a.search_vector @@ WEBSEARCH_TO_TSQUERY('english', $5))
+
+        AND
+
+        ($8::timestamptz IS NULL OR a.created >= $8)
+
+        AND
+
+        ($9::timestamptz IS NULL OR a.created < $9)
+
+        AND
+
+        ($10::int IS NULL OR a.reading_time_minutes >= $10)
+
+        AND
+
+        ($11::int IS NULL OR a.reading_time_minutes <= $11)
+
+        AND
+
+        (a.status = 'published' OR "#,
+            article_editor!("a"),
+            ")"
+        )
+    };
+}
+
 /// SQL query used to fetch a page of articles allowing for filters which can be used to narrow the
-/// search results.
-const LIST_ARTICLE_VIEWS_QUERY: &str = r#"
+/// search results. When a full-text search query is given a snippet of the body is returned with the
+/// matching terms delimited by the `\x02` and `\x03` control characters.
+///
+/// The articles are ordered by the [`ArticleSort`] and then by the time they were created, with the
+/// id as the final tie-break. A page can also be selected by the position of an article when sorted
+/// by creation time using the upper and lower bounds, in which case the direction is given
+/// explicitly since the rows of a page that precedes a position are selected in the reverse order.
+///
+/// Trending articles are ranked by their stored trending score, the sum of their favorites, each of
+/// which is worth one when it is made and half as much for every day that passes. The score is
+/// adjusted as favorites are added and removed and recomputed periodically so that it decays, see
+/// [`refresh_trending_scores`].
+///
+/// The tags, favorited, bookmarked and followed columns are still selected with a subquery for each
+/// article, but unlike the counts they are lookups on the primary keys of the mapping tables that
+/// only run for the rows of the page, so their cost doesn't grow with the popularity of an article.
+const LIST_ARTICLE_VIEWS_QUERY: &str = concat!(
+    r#"
     SELECT
         a.*,
+        s.*,
+        co.coauthors,
+        CASE WHEN $5::text IS NULL THEN NULL ELSE TS_HEADLINE('english', a.body, WEBSEARCH_TO_TSQUERY('english', $5), 'StartSel=' || CHR(2) || ', StopSel=' || CHR(3) || ', MaxFragments=2, MaxWords=30, MinWords=10') END AS snippet,
         (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id AND af.user_id = $1)::int::bool AS favorited,
-        (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id) as favorites_count,
-        (ARRAY_TO_STRING(ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC), ',')) AS tags,
+        (SELECT COUNT(ab.*) FROM article_bookmarks AS ab WHERE ab.article_id = a.id AND ab.user_id = $1)::int::bool AS bookmarked,
+        ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC) AS tags,
         u.id AS author_id,
         u.name AS author_name,
         u.bio AS author_bio,
         u.image AS author_image,
         (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS author_followed
     FROM
-        articles AS a INNER JOIN users AS u ON a.user_id = u.id
-    WHERE
-        ($2::text IS NULL OR EXISTS(SELECT 1 FROM article_tags AS at INNER JOIN tags AS t ON at.tag_id = t.id WHERE at.article_id = a.id AND t.name = $2))
+        articles AS a INNER JOIN users AS u ON a.user_id = u.id"#,
+    article_series_join!(),
+    article_coauthors_join!(),
+    r#"
+    WHERE"#,
+    list_articles_filters!(),
+    r#"
 
         AND
 
-        ($3::text IS NULL OR u.name = $3)
+        ($14::timestamptz IS NULL OR (a.created, a.id) < ($14, $15::uuid))
 
         AND
 
-        ($4::text IS NULL OR EXISTS(SELECT 1 FROM users AS u INNER JOIN article_favs AS af ON u.id = af.user_id WHERE af.article_id = a.id AND u.name = $4))
+        ($16::timestamptz IS NULL OR (a.created, a.id) > ($16, $17::uuid))
     ORDER BY
-        a.created DESC
+        CASE WHEN $18::text = 'relevance' THEN TS_RANK(a.search_vector, WEBSEARCH_TO_TSQUERY('english', $5)) END DESC,
+        CASE WHEN $18::text = 'favorites' THEN a.favorites_count END DESC,
+        CASE WHEN $18::text = 'comments' THEN a.comments_count END DESC,
+        CASE WHEN $18::text = 'trending' THEN a.trending_score END DESC,
+        CASE WHEN $19::bool THEN a.created END ASC,
+        CASE WHEN $19::bool THEN a.id END ASC,
+        a.created DESC,
+        a.id DESC
     LIMIT
-        $5
+        $12
     OFFSET
-        $6"#;
+        $13"#
+);
 
 /// SQL query used to get a total count of a list articles query using the same filters.
-const COUNT_ARTICLE_VIEWS_QUERY: &str = r#"
+const COUNT_ARTICLE_VIEWS_QUERY: &str = concat!(
+    r#"
     SELECT
         COUNT(a.id)
     FROM
         articles AS a INNER JOIN users AS u ON a.user_id = u.id
+    WHERE"#,
+    list_articles_filters!()
+);
+
+/// SQL query used to fetch a single page of the article feed for a user. The page can be selected by
+/// offset or by the position of an article, as described for [`LIST_ARTICLE_VIEWS_QUERY`].
+const GET_USER_FEED_PAGE_QUERY: &str = concat!(
+    r#"
+    SELECT
+        a.*,
+        s.*,
+        co.coauthors,
+        (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id AND af.user_id = $1)::int::bool AS favorited,
+        (SELECT COUNT(ab.*) FROM article_bookmarks AS ab WHERE ab.article_id = a.id AND ab.user_id = $1)::int::bool AS bookmarked,
+        ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC) AS tags,
+        u.id AS author_id,
+        u.name AS author_name,
+        u.bio AS author_bio,
+        u.image AS author_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS author_followed
+    FROM
+        articles AS a INNER JOIN users AS u ON a.user_id = u.id INNER JOIN user_follows AS uf ON a.user_id = uf.user_id"#,
+    article_series_join!(),
+    article_coauthors_join!(),
+    r#"
     WHERE
-        ($1::text IS NULL OR EXISTS(SELECT 1 FROM article_tags AS at INNER JOIN tags AS t ON at.tag_id = t.id WHERE at.article_id = a.id AND t.name = $1))
+        uf.follower_id = $1 AND a.status = 'published' AND a.deleted_at IS NULL
 
         AND
 
-        ($2::text IS NULL OR u.name = $2)
+        ($4::timestamptz IS NULL OR (a.created, a.id) < ($4, $5::uuid))
 
         AND
 
-        ($3::text IS NULL OR EXISTS(SELECT 1 FROM users AS u INNER JOIN article_favs AS af ON u.id = af.user_id WHERE af.article_id = a.id AND u.name = $3))"#;
+        ($6::timestamptz IS NULL OR (a.created, a.id) > ($6, $7::uuid))
+    ORDER BY
+        CASE WHEN $6::timestamptz IS NULL THEN NULL ELSE a.created END ASC,
+        CASE WHEN $6::timestamptz IS NULL THEN NULL ELSE a.id END ASC,
+        a.created DESC,
+        a.id DESC
+    LIMIT
+        $2
+    OFFSET
+        $3"#
+);
 
-/// SQL query used to fetch a single page of the article feed for a user.
-const GET_USER_FEED_PAGE_QUERY: &str = r#"
+/// SQL query used to get a total count of the articles in a user's feed.
+const COUNT_USER_FEED_QUERY: &str = r#"
+    SELECT
+        COUNT(a.id)
+    FROM
+        articles AS a INNER JOIN users AS u ON a.user_id = u.id INNER JOIN user_follows AS uf ON a.user_id = uf.user_id
+    WHERE
+        uf.follower_id = $1 AND a.status = 'published' AND a.deleted_at IS NULL"#;
+
+/// SQL query used to fetch a single page of the draft articles of a user, including those that the
+/// user co-authors. The page can be selected by offset or by the position of an article, as
+/// described for [`LIST_ARTICLE_VIEWS_QUERY`].
+const GET_USER_DRAFTS_PAGE_QUERY: &str = concat!(
+    r#"
     SELECT
         a.*,
+        s.*,
+        co.coauthors,
         (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id AND af.user_id = $1)::int::bool AS favorited,
-        (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id) as favorites_count,
-        (ARRAY_TO_STRING(ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC), ',')) AS tags,
+        (SELECT COUNT(ab.*) FROM article_bookmarks AS ab WHERE ab.article_id = a.id AND ab.user_id = $1)::int::bool AS bookmarked,
+        ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC) AS tags,
         u.id AS author_id,
         u.name AS author_name,
         u.bio AS author_bio,
         u.image AS author_image,
-        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS author_followed
+        false AS author_followed
     FROM
-        articles AS a INNER JOIN users AS u ON a.user_id = u.id INNER JOIN user_follows AS uf ON a.user_id = uf.user_id
+        articles AS a INNER JOIN users AS u ON a.user_id = u.id"#,
+    article_series_join!(),
+    article_coauthors_join!(),
+    r#"
     WHERE
-        uf.follower_id = $1
+        a.status IN ('draft', 'scheduled') AND a.deleted_at IS NULL AND "#,
+    article_editor!("a"),
+    r#"
+
+        AND
+
+        ($4::timestamptz IS NULL OR (a.created, a.id) < ($4, $5::uuid))
+
+        AND
+
+        ($6::timestamptz IS NULL OR (a.created, a.id) > ($6, $7::uuid))
     ORDER BY
-        a.created DESC
+        CASE WHEN $6::timestamptz IS NULL THEN NULL ELSE a.created END ASC,
+        CASE WHEN $6::timestamptz IS NULL THEN NULL ELSE a.id END ASC,
+        a.created DESC,
+        a.id DESC
     LIMIT
         $2
     OFFSET
-        $3"#;
+        $3"#
+);
 
-/// SQL query used to get a total count of the articles in a user's feed.
-const COUNT_USER_FEED_QUERY: &str = r#"
+/// SQL query used to get a total count of the draft articles of a user, including those that the
+/// user co-authors.
+const COUNT_USER_DRAFTS_QUERY: &str = concat!(
+    "SELECT COUNT(a.id) FROM articles AS a WHERE a.status IN ('draft', 'scheduled') AND a.deleted_at IS NULL AND ",
+    article_editor!("a")
+);
+
+/// SQL query used to create a new article in the database.
+const CREATE_ARTICLE_QUERY: &str = r#"
+    INSERT INTO
+        articles (user_id, slug, title, description, body, body_html, word_count, reading_time_minutes, status, published_at, publish_at)
+    VALUES
+        ($1, $2, $3, $4, $5, $6, $7, $8, $9, CASE WHEN $9::text = 'published' THEN NOW() END, $10)
+    RETURNING *"#;
+
+/// SQL query used to import an article into the database under the given slug, keeping the times
+/// that it was created and published if they are known. Nothing is inserted if an article with the
+/// slug already exists, including one in the trash.
+const IMPORT_ARTICLE_QUERY: &str = r#"
+    INSERT INTO
+        articles (user_id, slug, title, description, body, body_html, word_count, reading_time_minutes, status, created, published_at, publish_at)
+    VALUES
+        ($1, $2, $3, $4, $5, $6, $7, $8, $9, COALESCE($10, NOW()), CASE WHEN $9::text = 'published' THEN COALESCE($11, NOW()) ELSE $11 END, $12)
+    ON CONFLICT (slug) DO NOTHING
+    RETURNING *"#;
+
+/// SQL query used to update an existing article in the database. The time the article was
+/// published is only set the first time that it is published.
+const UPDATE_ARTICLE_QUERY: &str = r#"
+    UPDATE
+        articles
+    SET
+        slug = $1,
+        title = $2,
+        description = $3,
+        body = $4,
+        body_html = $5,
+        word_count = $6,
+        reading_time_minutes = $7,
+        status = $8,
+        published_at = CASE WHEN $8::text = 'published' THEN COALESCE(published_at, NOW()) ELSE published_at END,
+        publish_at = $9
+    WHERE
+        id = $10"#;
+
+/// SQL query used to publish an existing article in the database.
+const PUBLISH_ARTICLE_QUERY: &str = r#"
+    UPDATE
+        articles
+    SET
+        status = 'published',
+        published_at = COALESCE(published_at, NOW()),
+        publish_at = NULL
+    WHERE
+        id = $1"#;
+
+/// SQL query used to publish a batch of scheduled articles whose publish time has passed. Rows that
+/// are locked by another instance of the application publishing the same batch are skipped.
+const PUBLISH_DUE_ARTICLES_QUERY: &str = r#"
+    WITH due_articles AS (
+        SELECT
+            id
+        FROM
+            articles
+        WHERE
+            status = 'scheduled' AND publish_at <= NOW() AND deleted_at IS NULL
+        ORDER BY
+            publish_at ASC
+        LIMIT
+            $1
+        FOR UPDATE SKIP LOCKED
+    )
+    UPDATE
+        articles AS a
+    SET
+        status = 'published',
+        published_at = COALESCE(a.published_at, a.publish_at),
+        publish_at = NULL
+    FROM
+        due_articles AS da
+    WHERE
+        a.id = da.id
+    RETURNING
+        a.slug"#;
+
+/// SQL query used to lock a batch of articles, ordered by id, whose counters are to be reconciled.
+/// Holding the locks while the counters are recomputed means that any favorite or comment added
+/// concurrently either is counted or adjusts the reconciled counter once the locks are released.
+const LOCK_ARTICLES_BATCH_QUERY: &str = r#"
     SELECT
-        COUNT(a.id)
+        id
     FROM
-        articles AS a INNER JOIN users AS u ON a.user_id = u.id INNER JOIN user_follows AS uf ON a.user_id = uf.user_id
+        articles
     WHERE
-        uf.follower_id = $1"#;
+        id > $1
+    ORDER BY
+        id ASC
+    LIMIT
+        $2
+    FOR UPDATE"#;
 
-/// SQL query used to create a new article in the database.
-const CREATE_ARTICLE_QUERY: &str =
-    "INSERT INTO articles (user_id, slug, title, description, body) VALUES ($1, $2, $3, $4, $5) RETURNING *";
+/// SQL query used to lock a batch of the articles whose body has not been rendered to HTML, which
+/// are those created before the rendered HTML was stored. Rows that are locked by another instance
+/// of the application rendering the same batch are skipped.
+const LOCK_UNRENDERED_ARTICLES_QUERY: &str = r#"
+    SELECT
+        id,
+        body
+    FROM
+        articles
+    WHERE
+        body_html IS NULL
+    ORDER BY
+        id ASC
+    LIMIT
+        $1
+    FOR UPDATE SKIP LOCKED"#;
+
+/// SQL query used to store the HTML rendered from the body of an article.
+const UPDATE_ARTICLE_BODY_HTML_QUERY: &str = "UPDATE articles SET body_html = $1 WHERE id = $2";
 
-/// SQL query used to update an existing article in the database.
-const UPDATE_ARTICLE_QUERY: &str =
-    "UPDATE articles SET slug = $1, title = $2, description = $3, body = $4 WHERE id = $5";
+/// SQL query used to correct the favorites, comments and views counts of a batch of articles that
+/// differ from the actual number of favorites and comments and the sum of the daily views.
+const RECONCILE_ARTICLE_COUNTERS_QUERY: &str = r#"
+    UPDATE
+        articles AS a
+    SET
+        favorites_count = c.favorites_count,
+        comments_count = c.comments_count,
+        views_count = c.views_count
+    FROM (
+        SELECT
+            ar.id,
+            (SELECT COUNT(*) FROM article_favs AS af WHERE af.article_id = ar.id) AS favorites_count,
+            (SELECT COUNT(*) FROM article_comments AS ac WHERE ac.article_id = ar.id AND ac.deleted_at IS NULL) AS comments_count,
+            (SELECT COALESCE(SUM(adv.views), 0) FROM article_daily_views AS adv WHERE adv.article_id = ar.id)::bigint AS views_count
+        FROM
+            articles AS ar
+        WHERE
+            ar.id = ANY($1)
+    ) AS c
+    WHERE
+        a.id = c.id AND (a.favorites_count, a.comments_count, a.views_count) IS DISTINCT FROM (c.favorites_count, c.comments_count, c.views_count)"#;
+
+/// SQL query used to recompute the trending scores of the articles that have been favorited in the
+/// last thirty days, or that still have a score from favorites made before then. Favorites older
+/// than thirty days contribute a negligible amount so they are excluded.
+const REFRESH_TRENDING_SCORES_QUERY: &str = r#"
+    WITH scores AS (
+        SELECT
+            af.article_id,
+            SUM(POWER(0.5, EXTRACT(EPOCH FROM NOW() - af.created) / 86400)) AS trending_score
+        FROM
+            article_favs AS af
+        WHERE
+            af.created > NOW() - INTERVAL '30 days'
+        GROUP BY
+            af.article_id
+    )
+    UPDATE
+        articles AS a
+    SET
+        trending_score = COALESCE((SELECT s.trending_score FROM scores AS s WHERE s.article_id = a.id), 0)
+    WHERE
+        a.trending_score > 0 OR a.id IN (SELECT s.article_id FROM scores AS s)"#;
 
 /// SQL query used to create a new tag in the database.
 const CREATE_TAG_QUERY: &str = r#"
@@ -103,43 +412,178 @@ const CREATE_TAG_QUERY: &str = r#"
     ON CONFLICT(name) DO UPDATE SET name = EXCLUDED.name
     RETURNING *"#;
 
+/// SQL query used to record the author of a new article as its owner.
+const CREATE_ARTICLE_OWNER_QUERY: &str = r#"
+    INSERT INTO
+        article_authors (article_id, user_id, role, status, responded)
+    VALUES
+        ($1, $2, 'owner', 'accepted', NOW())"#;
+
+/// SQL query used to determine whether a user is the owner or an editor who has accepted the
+/// invitation to co-author an article.
+const IS_ARTICLE_EDITOR_QUERY: &str = r#"
+    SELECT EXISTS(
+        SELECT 1 FROM article_authors WHERE article_id = $1 AND user_id = $2 AND status = 'accepted'
+    )"#;
+
 /// SQL query used to create the association of a tag to an article.
 const CREATE_ARTICLE_TAG_QUERY: &str =
     "INSERT INTO article_tags (article_id, tag_id) VALUES ($1, $2)";
 
-/// SQL query used to fetch an article by slug.
-const GET_ARTICLE_BY_SLUG_QUERY: &str = "SELECT * FROM articles WHERE slug = $1";
+/// SQL query used to fetch an article by slug, unless it is in the trash.
+const GET_ARTICLE_BY_SLUG_QUERY: &str =
+    "SELECT * FROM articles WHERE slug = $1 AND deleted_at IS NULL";
+
+/// SQL query used to fetch an article in the trash by slug.
+const GET_TRASHED_ARTICLE_BY_SLUG_QUERY: &str =
+    "SELECT * FROM articles WHERE slug = $1 AND deleted_at IS NOT NULL";
 
 /// SQL query used to fetch a computed view of an article by slug.
-const GET_ARTICLE_VIEW_BY_SLUG_QUERY: &str = r#"
+const GET_ARTICLE_VIEW_BY_SLUG_QUERY: &str = concat!(
+    r#"
     SELECT
         a.*,
+        s.*,
+        co.coauthors,
         (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id AND af.user_id = $1)::int::bool AS favorited,
-        (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id) as favorites_count,
-        (ARRAY_TO_STRING(ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC), ',')) AS tags,
+        (SELECT COUNT(ab.*) FROM article_bookmarks AS ab WHERE ab.article_id = a.id AND ab.user_id = $1)::int::bool AS bookmarked,
+        ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC) AS tags,
         u.id AS author_id,
         u.name AS author_name,
         u.bio AS author_bio,
         u.image AS author_image,
         (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS author_followed
     FROM
-        articles AS a INNER JOIN users AS u ON a.user_id = u.id
-     WHERE
-        a.slug = $2"#;
+        articles AS a INNER JOIN users AS u ON a.user_id = u.id"#,
+    article_series_join!(),
+    article_coauthors_join!(),
+    r#"
+    WHERE
+        a.slug = $2 AND a.deleted_at IS NULL"#
+);
+
+/// SQL query used to fetch the ids of the published articles most similar to an article, excluding
+/// the article itself. Each tag shared with the article is worth three points, each user who
+/// favorited both articles is worth one point and having the same author is worth two points. Only
+/// articles that score at least one point are returned, highest score first and then most recently
+/// created first.
+const LIST_RELATED_ARTICLE_IDS_QUERY: &str = r#"
+    WITH shared_tags AS (
+        SELECT
+            at.article_id,
+            COUNT(*) * 3 AS score
+        FROM
+            article_tags AS at INNER JOIN article_tags AS st ON at.tag_id = st.tag_id
+        WHERE
+            st.article_id = $1 AND at.article_id <> $1
+        GROUP BY
+            at.article_id
+    ), co_favorites AS (
+        SELECT
+            af.article_id,
+            COUNT(*) AS score
+        FROM
+            article_favs AS af INNER JOIN article_favs AS sf ON af.user_id = sf.user_id
+        WHERE
+            sf.article_id = $1 AND af.article_id <> $1
+        GROUP BY
+            af.article_id
+    )
+    SELECT
+        r.id
+    FROM (
+        SELECT
+            a.id,
+            a.created,
+            COALESCE(st.score, 0) + COALESCE(cf.score, 0) + CASE WHEN a.user_id = sa.user_id THEN 2 ELSE 0 END AS score
+        FROM
+            articles AS a
+            INNER JOIN articles AS sa ON sa.id = $1
+            LEFT JOIN shared_tags AS st ON a.id = st.article_id
+            LEFT JOIN co_favorites AS cf ON a.id = cf.article_id
+        WHERE
+            a.id <> $1 AND a.status = 'published' AND a.deleted_at IS NULL
+    ) AS r
+    WHERE
+        r.score > 0
+    ORDER BY
+        r.score DESC,
+        r.created DESC,
+        r.id DESC
+    LIMIT
+        $2"#;
+
+/// SQL query used to fetch computed views of the published articles with the given ids in the order
+/// of the ids.
+const LIST_PUBLISHED_ARTICLE_VIEWS_BY_IDS_QUERY: &str = concat!(
+    r#"
+    SELECT
+        a.*,
+        s.*,
+        co.coauthors,
+        (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id AND af.user_id = $1)::int::bool AS favorited,
+        (SELECT COUNT(ab.*) FROM article_bookmarks AS ab WHERE ab.article_id = a.id AND ab.user_id = $1)::int::bool AS bookmarked,
+        ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC) AS tags,
+        u.id AS author_id,
+        u.name AS author_name,
+        u.bio AS author_bio,
+        u.image AS author_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS author_followed
+    FROM
+        articles AS a INNER JOIN users AS u ON a.user_id = u.id"#,
+    article_series_join!(),
+    article_coauthors_join!(),
+    r#"
+    WHERE
+        a.id = ANY($2) AND a.status = 'published' AND a.deleted_at IS NULL
+    ORDER BY
+        ARRAY_POSITION($2, a.id) ASC"#
+);
+
+/// SQL query used to move an article to the trash.
+const TRASH_ARTICLE_QUERY: &str =
+    "UPDATE articles SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL";
+
+/// SQL query used to restore an article from the trash.
+const RESTORE_ARTICLE_QUERY: &str =
+    "UPDATE articles SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL";
 
 /// SQL query used to delete entries from the user favorites join table for an article.
 const DELETE_ARTICLE_FAVS_QUERY: &str = "DELETE FROM article_favs WHERE article_id = $1";
 
+/// SQL query used to delete the bookmarks of an article.
+const DELETE_ARTICLE_BOOKMARKS_QUERY: &str = "DELETE FROM article_bookmarks WHERE article_id = $1";
+
+/// SQL query used to remove an article from the collection it is part of.
+const DELETE_ARTICLE_COLLECTIONS_QUERY: &str =
+    "DELETE FROM collection_articles WHERE article_id = $1";
+
+/// SQL query used to delete the authors and pending co-author invitations of an article.
+const DELETE_ARTICLE_AUTHORS_QUERY: &str = "DELETE FROM article_authors WHERE article_id = $1";
+
+/// SQL query used to delete the daily views of an article.
+const DELETE_ARTICLE_DAILY_VIEWS_QUERY: &str =
+    "DELETE FROM article_daily_views WHERE article_id = $1";
+
 /// SQL query used to delete the links from a tag to an article.
 const DELETE_ARTICLE_TAGS_QUERY: &str = "DELETE FROM article_tags WHERE article_id = $1";
 
+/// SQL query used to delete the comments of an article, including those in the trash.
+const DELETE_ARTICLE_COMMENTS_QUERY: &str = "DELETE FROM article_comments WHERE article_id = $1";
+
+/// SQL query used to delete the revisions of an article.
+const DELETE_ARTICLE_REVISIONS_QUERY: &str = "DELETE FROM article_revisions WHERE article_id = $1";
+
 /// SQL query used to delete an article.
 const DELETE_ARTICLE_QUERY: &str = "DELETE FROM articles WHERE id = $1";
 
-/// SQL query used to create a new comment for an article.
+/// SQL query used to create a new comment for an article and increment the comments count of the
+/// article.
 const CREATE_ARTICLE_COMMENT_QUERY: &str = r#"
     WITH inserted_comment AS (
         INSERT INTO article_comments (user_id, article_id, body) VALUES ($1, $2, $3) RETURNING *
+    ), updated_article AS (
+        UPDATE articles SET comments_count = comments_count + 1 WHERE id = $2
     )
     SELECT
         ic.*,
@@ -151,11 +595,54 @@ const CREATE_ARTICLE_COMMENT_QUERY: &str = r#"
     FROM
         inserted_comment AS ic INNER JOIN users AS u ON ic.user_id = u.id"#;
 
-/// SQL query used to delete a comment from an article.
-const DELETE_ARTICLE_COMMENT_QUERY: &str =
-    "DELETE FROM article_comments WHERE id = $1 AND user_id = $2";
+/// SQL query used to move a comment on an article to the trash and decrement the comments count of
+/// the article if the comment existed and was not already in the trash.
+const TRASH_ARTICLE_COMMENT_QUERY: &str = r#"
+    WITH trashed_comment AS (
+        UPDATE
+            article_comments
+        SET
+            deleted_at = NOW()
+        WHERE
+            id = $1 AND article_id = $2 AND user_id = $3 AND deleted_at IS NULL
+        RETURNING
+            article_id
+    )
+    UPDATE
+        articles
+    SET
+        comments_count = comments_count - 1
+    WHERE
+        id IN (SELECT article_id FROM trashed_comment)"#;
 
-/// SQL query used to fetch the comments for a single article by slug.
+/// SQL query used to restore a comment on an article from the trash and increment the comments
+/// count of the article if the comment was in the trash.
+const RESTORE_ARTICLE_COMMENT_QUERY: &str = r#"
+    WITH restored_comment AS (
+        UPDATE
+            article_comments
+        SET
+            deleted_at = NULL
+        WHERE
+            id = $1 AND article_id = $2 AND user_id = $3 AND deleted_at IS NOT NULL
+        RETURNING
+            *
+    ), updated_article AS (
+        UPDATE articles SET comments_count = comments_count + 1 WHERE id IN (SELECT article_id FROM restored_comment)
+    )
+    SELECT
+        rc.*,
+        u.id AS author_id,
+        u.name AS author_name,
+        u.bio AS author_bio,
+        u.image AS author_image,
+        false AS author_followed
+    FROM
+        restored_comment AS rc INNER JOIN users AS u ON rc.user_id = u.id"#;
+
+/// SQL query used to fetch the comments for a single article by slug, oldest first. All of the
+/// comments are returned when no limit is given. Pages that precede the position of a comment are
+/// selected in descending order and must be reversed by the caller.
 const GET_ARTICLE_COMMENTS_BY_SLUG_QUERY: &str = r#"
     SELECT
         ac.*,
@@ -167,28 +654,118 @@ const GET_ARTICLE_COMMENTS_BY_SLUG_QUERY: &str = r#"
     FROM
         article_comments AS ac INNER JOIN articles AS a ON ac.article_id = a.id INNER JOIN users AS u ON ac.user_id = u.id
     WHERE
-        a.slug = $2
+        a.slug = $2 AND ac.deleted_at IS NULL
+
+        AND
+
+        ($5::timestamptz IS NULL OR (ac.created, ac.id) > ($5, $6::uuid))
+
+        AND
+
+        ($7::timestamptz IS NULL OR (ac.created, ac.id) < ($7, $8::uuid))
     ORDER BY
-        ac.created ASC"#;
+        CASE WHEN $7::timestamptz IS NULL THEN NULL ELSE ac.created END DESC,
+        CASE WHEN $7::timestamptz IS NULL THEN NULL ELSE ac.id END DESC,
+        ac.created ASC,
+        ac.id ASC
+    LIMIT
+        $3
+    OFFSET
+        $4"#;
 
-/// SQL query used to create an entry in the table that captures favorited articles for a user.
+/// SQL query used to create an entry in the table that captures favorited articles for a user. The
+/// favorites count and trending score of the article are only incremented if the user had not
+/// already favorited it.
 const CREATE_USER_ARTICLE_FAV_QUERY: &str = r#"
     WITH target_article AS (
         SELECT slug FROM articles WHERE id = $1
     ), inserted_fav AS (
-        INSERT INTO article_favs (article_id, user_id) VALUES($1, $2) ON CONFLICT DO NOTHING
+        INSERT INTO article_favs (article_id, user_id) VALUES($1, $2) ON CONFLICT DO NOTHING RETURNING article_id
+    ), updated_article AS (
+        UPDATE articles SET favorites_count = favorites_count + 1, trending_score = trending_score + 1 WHERE id IN (SELECT article_id FROM inserted_fav)
     )
     SELECT slug FROM target_article"#;
 
-/// SQL query used to delete the entry in the table that captures favorited articles for a user.
+/// SQL query used to delete the entry in the table that captures favorited articles for a user. The
+/// favorites count of the article is only decremented if the user had favorited it, and the decayed
+/// value of the favorite is taken off its trending score if it was made in the last thirty days.
 const DELETE_USER_ARTICLE_FAV_QUERY: &str = r#"
     WITH target_article AS (
         SELECT slug FROM articles WHERE id = $1
     ), deleted_fav AS (
-        DELETE FROM article_favs WHERE article_id = $1 AND user_id = $2
+        DELETE FROM article_favs WHERE article_id = $1 AND user_id = $2 RETURNING article_id, created
+    ), updated_article AS (
+        UPDATE articles AS a SET favorites_count = a.favorites_count - 1, trending_score = GREATEST(a.trending_score - CASE WHEN df.created > NOW() - INTERVAL '30 days' THEN POWER(0.5, EXTRACT(EPOCH FROM NOW() - df.created) / 86400) ELSE 0 END, 0) FROM deleted_fav AS df WHERE a.id = df.article_id
     )
     SELECT slug FROM target_article"#;
 
+/// Enumerates the publication statuses of an article. Only published articles are returned by the
+/// list and feed queries, unless the viewer is the author of the article.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum ArticleStatus {
+    /// The article is a work in progress that only the author can see.
+    Draft,
+    /// The article is listed publicly.
+    Published,
+    /// The article will be published once its publish time has passed and until then only the
+    /// author can see it.
+    Scheduled,
+    /// The article is only accessible to those who know the slug.
+    Unlisted,
+}
+
+/// Enumerates the orders in which a list of articles can be sorted. Articles that are equal in the
+/// sort order are ordered by the most recently created first.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum ArticleSort {
+    /// Most recently created articles first.
+    #[default]
+    Newest,
+    /// Least recently created articles first.
+    Oldest,
+    /// Articles with the most favorites first.
+    Favorites,
+    /// Articles with the most comments first.
+    Comments,
+    /// Articles that have been favorited the most recently first.
+    Trending,
+    /// Articles that best match the full-text search query first.
+    Relevance,
+}
+
+impl ArticleSort {
+    /// Determines whether articles in the sort order can be paged by their position, which is only
+    /// the case when they are sorted by the time they were created.
+    pub fn is_keyset(&self) -> bool {
+        matches!(self, ArticleSort::Newest | ArticleSort::Oldest)
+    }
+}
+
+/// Enumerates the ways in which multiple tags are matched when filtering a list of articles.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum TagMode {
+    /// Articles must have every one of the tags.
+    #[default]
+    All,
+    /// Articles must have at least one of the tags.
+    Any,
+}
+
+impl From<ArticleVisibility> for ArticleStatus {
+    fn from(visibility: ArticleVisibility) -> Self {
+        match visibility {
+            ArticleVisibility::Public => ArticleStatus::Published,
+            ArticleVisibility::Unlisted => ArticleStatus::Unlisted,
+        }
+    }
+}
+
 /// The [`Article`] struct is used to let the `sqlx` library easily map a row from the `articles`
 /// table in the database to a struct value. It is a one-to-one mapping from the database table.
 #[derive(Debug, FromRow, Serialize)]
@@ -209,12 +786,36 @@ pub struct Article {
     /// Body of the article.
     #[allow(dead_code)]
     pub body: String,
+    /// Sanitized HTML rendered from the Markdown body of the article.
+    #[allow(dead_code)]
+    pub body_html: Option<String>,
     /// Time the article was created.
     #[allow(dead_code)]
     pub created: DateTime<Utc>,
     /// Time the article was last modified.
     #[allow(dead_code)]
     pub updated: Option<DateTime<Utc>>,
+    /// Publication status of the article.
+    pub status: ArticleStatus,
+    /// Time the article was first published.
+    #[allow(dead_code)]
+    pub published_at: Option<DateTime<Utc>>,
+    /// Time the article is scheduled to be published.
+    pub publish_at: Option<DateTime<Utc>>,
+    /// Count of the total number of views of the article.
+    pub views_count: i64,
+}
+
+impl Article {
+    /// Determines whether the article can be seen by anyone who knows the slug, which is the case
+    /// for published and unlisted articles. Use [`is_article_visible`] to determine whether a draft
+    /// or scheduled article can be seen by one of its authors.
+    pub fn is_public(&self) -> bool {
+        matches!(
+            self.status,
+            ArticleStatus::Published | ArticleStatus::Unlisted
+        )
+    }
 }
 
 /// The [`ArticleView`] struct is used to let the `sqlx` library easily map a view of the `articles`
@@ -233,16 +834,54 @@ pub struct ArticleView {
     pub description: String,
     /// Body of the article.
     pub body: String,
-    /// CSV of tags associated with the article.
-    pub tags: Option<String>,
+    /// Sanitized HTML rendered from the Markdown body of the article.
+    pub body_html: Option<String>,
+    /// Names of the tags associated with the article in alphabetical order.
+    pub tags: Vec<String>,
     /// Time the article was created.
     pub created: DateTime<Utc>,
     /// Time the article was last modified.
     pub updated: Option<DateTime<Utc>>,
+    /// Publication status of the article.
+    pub status: ArticleStatus,
+    /// Time the article was first published.
+    pub published_at: Option<DateTime<Utc>>,
+    /// Time the article is scheduled to be published.
+    pub publish_at: Option<DateTime<Utc>>,
+    /// Snippet of the body with the terms matching the full-text search query delimited by the
+    /// `\x02` and `\x03` control characters. Only populated when listing articles with a query.
+    #[sqlx(default)]
+    pub snippet: Option<String>,
     /// Flag indicating whether the logged in user, if available, has favorited the article.
     pub favorited: bool,
     /// Count of the total number of users who have favorited the article.
     pub favorites_count: i64,
+    /// Count of the total number of comments on the article.
+    pub comments_count: i64,
+    /// Count of the total number of views of the article.
+    pub views_count: i64,
+    /// Number of words in the body of the article, excluding code.
+    pub word_count: i32,
+    /// Estimated time to read the article in minutes.
+    pub reading_time_minutes: i32,
+    /// Flag indicating whether the logged in user, if available, has bookmarked the article.
+    pub bookmarked: bool,
+    /// Slug of the collection that the article is part of, if any.
+    pub series_slug: Option<String>,
+    /// Title of the collection that the article is part of, if any.
+    pub series_title: Option<String>,
+    /// Position of the article in its collection, starting at one.
+    pub series_position: Option<i64>,
+    /// Number of articles in the collection of the article.
+    pub series_length: Option<i64>,
+    /// Slug of the article that precedes the article in its collection, if any.
+    pub series_prev_slug: Option<String>,
+    /// Title of the article that precedes the article in its collection, if any.
+    pub series_prev_title: Option<String>,
+    /// Slug of the article that follows the article in its collection, if any.
+    pub series_next_slug: Option<String>,
+    /// Title of the article that follows the article in its collection, if any.
+    pub series_next_title: Option<String>,
     /// Id of the author.
     pub author_id: Uuid,
     /// Username of the author.
@@ -254,6 +893,49 @@ pub struct ArticleView {
     /// Flag indicating whether or not the author is being followed by the currently authenticated
     /// user. If no user is curently logged in, then the value will be set to `false`.
     pub author_followed: bool,
+    /// Profiles of the editors who have accepted the invitation to co-author the article.
+    pub coauthors: Json<Vec<Profile>>,
+}
+
+impl ArticleView {
+    /// Determines whether the article can be seen by the user with the given id, if available.
+    /// Drafts and scheduled articles can only be seen by their authors while published and unlisted
+    /// articles can be seen by anyone who knows the slug.
+    pub fn is_visible_to(&self, user_ctx: Option<Uuid>) -> bool {
+        matches!(
+            self.status,
+            ArticleStatus::Published | ArticleStatus::Unlisted
+        ) || user_ctx == Some(self.author_id)
+            || self.coauthors.iter().any(|c| user_ctx == Some(c.id))
+    }
+}
+
+/// The [`ListFilters`] struct contains the filters that narrow down the articles returned by the
+/// list articles query. The same filters are used to count the total number of matching articles.
+#[derive(Debug, Default)]
+pub struct ListFilters<'a> {
+    /// Distinct names of the tags that an article must have, as determined by the [`TagMode`].
+    pub tags: &'a [String],
+    /// Determines whether an article must have all or any of the tags.
+    pub tag_mode: TagMode,
+    /// Names of the tags that an article must not have.
+    pub exclude_tags: &'a [String],
+    /// Names of the users, any of whom authored the article.
+    pub authors: &'a [String],
+    /// Name of the user who favorited the article.
+    pub favorited: Option<&'a String>,
+    /// Full-text search query that the title, description or body of the article must match.
+    pub query: Option<&'a String>,
+    /// Time at or after which the article must have been created.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Time before which the article must have been created.
+    pub created_before: Option<DateTime<Utc>>,
+    /// Minimum estimated reading time of the article in minutes.
+    pub min_reading_time: Option<i32>,
+    /// Maximum estimated reading time of the article in minutes.
+    pub max_reading_time: Option<i32>,
+    /// Order in which the articles are sorted.
+    pub sort: ArticleSort,
 }
 
 /// The [`CreateArticle`] struct contains the data required to create an article in the database.
@@ -265,8 +947,49 @@ pub struct CreateArticle<'a> {
     pub description: &'a String,
     /// Body of the article.
     pub body: &'a String,
-    /// List of tags associated with the article.
-    pub tags: Option<&'a Vec<String>>,
+    /// Sanitized HTML rendered from the body of the article.
+    pub body_html: &'a str,
+    /// Number of words in the body of the article, excluding code.
+    pub word_count: i32,
+    /// Estimated time to read the article in minutes.
+    pub reading_time_minutes: i32,
+    /// Distinct, normalized names of the tags associated with the article.
+    pub tags: &'a [String],
+    /// Publication status of the article.
+    pub status: ArticleStatus,
+    /// Time the article is scheduled to be published.
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+/// The [`ImportArticle`] struct contains the data required to import an article into the database,
+/// e.g. from an export of the articles of a user.
+#[derive(Debug)]
+pub struct ImportArticle<'a> {
+    /// Slug that the article is imported under.
+    pub slug: &'a str,
+    /// Title of the article.
+    pub title: &'a str,
+    /// Description of the article.
+    pub description: &'a str,
+    /// Body of the article.
+    pub body: &'a str,
+    /// Sanitized HTML rendered from the body of the article.
+    pub body_html: &'a str,
+    /// Number of words in the body of the article, excluding code.
+    pub word_count: i32,
+    /// Estimated time to read the article in minutes.
+    pub reading_time_minutes: i32,
+    /// Distinct, normalized names of the tags associated with the article.
+    pub tags: &'a [String],
+    /// Publication status of the article.
+    pub status: ArticleStatus,
+    /// Time the article was originally created, the current time is used if not known.
+    pub created: Option<DateTime<Utc>>,
+    /// Time the article was originally published, the current time is used if not known and the
+    /// article is imported as published.
+    pub published_at: Option<DateTime<Utc>>,
+    /// Time the article is scheduled to be published.
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
 /// The [`UpdateArticle`] struct contains the data required to update an existing article in the
@@ -279,6 +1002,16 @@ pub struct UpdateArticle<'a> {
     pub description: &'a String,
     /// New body of the article.
     pub body: &'a String,
+    /// Sanitized HTML rendered from the new body of the article.
+    pub body_html: &'a str,
+    /// Number of words in the new body of the article, excluding code.
+    pub word_count: i32,
+    /// Estimated time to read the new body of the article in minutes.
+    pub reading_time_minutes: i32,
+    /// New publication status of the article.
+    pub status: ArticleStatus,
+    /// New time the article is scheduled to be published.
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
 /// The [`Comment`] struct is used to let the `sqlx` library easily map a row from the `comments`
@@ -338,40 +1071,70 @@ pub struct CreateComment<'a> {
 }
 
 /// Retrives a [`Vec`] of [`ArticleView`]s that make up a page of articles based on the specified
-/// filters and paging parameters.
+/// filters and [`Page`].
 pub async fn query_articles(
     cxn: &mut PgConnection,
     user_ctx: Option<Uuid>,
-    tag: Option<&String>,
-    author: Option<&String>,
-    favorited: Option<&String>,
-    limit: i32,
-    offset: i32,
+    filters: &ListFilters<'_>,
+    page: &Page,
 ) -> Result<Vec<ArticleView>, sqlx::Error> {
     let user_context = user_ctx.unwrap_or_else(Uuid::nil);
 
-    sqlx::query_as(LIST_ARTICLE_VIEWS_QUERY)
+    // the articles following a position are those created before it unless sorted oldest first
+    let oldest = filters.sort == ArticleSort::Oldest;
+    let (upper, lower) = if oldest {
+        (page.before(), page.after())
+    } else {
+        (page.after(), page.before())
+    };
+    let ascending = oldest != matches!(page, Page::Before { .. });
+
+    let articles = sqlx::query_as(LIST_ARTICLE_VIEWS_QUERY)
         .bind(user_context)
-        .bind(tag)
-        .bind(author)
-        .bind(favorited)
-        .bind(limit)
-        .bind(offset)
+        .bind(filters.tags)
+        .bind(filters.authors)
+        .bind(filters.favorited)
+        .bind(filters.query)
+        .bind(filters.tag_mode)
+        .bind(filters.exclude_tags)
+        .bind(filters.created_after)
+        .bind(filters.created_before)
+        .bind(filters.min_reading_time)
+        .bind(filters.max_reading_time)
+        .bind(page.limit())
+        .bind(page.offset())
+        .bind(upper.map(|k| k.created))
+        .bind(upper.map(|k| k.id))
+        .bind(lower.map(|k| k.created))
+        .bind(lower.map(|k| k.id))
+        .bind(filters.sort)
+        .bind(ascending)
         .fetch_all(&mut *cxn)
-        .await
+        .await?;
+
+    Ok(in_page_order(articles, page))
 }
 
 /// Counts the total number of articles based on the set of filters specified.
 pub async fn count_articles(
     cxn: &mut PgConnection,
-    tag: Option<&String>,
-    author: Option<&String>,
-    favorited: Option<&String>,
+    user_ctx: Option<Uuid>,
+    filters: &ListFilters<'_>,
 ) -> Result<i64, sqlx::Error> {
+    let user_context = user_ctx.unwrap_or_else(Uuid::nil);
+
     sqlx::query_scalar(COUNT_ARTICLE_VIEWS_QUERY)
-        .bind(tag)
-        .bind(author)
-        .bind(favorited)
+        .bind(user_context)
+        .bind(filters.tags)
+        .bind(filters.authors)
+        .bind(filters.favorited)
+        .bind(filters.query)
+        .bind(filters.tag_mode)
+        .bind(filters.exclude_tags)
+        .bind(filters.created_after)
+        .bind(filters.created_before)
+        .bind(filters.min_reading_time)
+        .bind(filters.max_reading_time)
         .fetch_one(&mut *cxn)
         .await
 }
@@ -395,30 +1158,86 @@ pub async fn create_article(
         .bind(article.title)
         .bind(article.description)
         .bind(article.body)
+        .bind(article.body_html)
+        .bind(article.word_count)
+        .bind(article.reading_time_minutes)
+        .bind(article.status)
+        .bind(article.publish_at)
         .fetch_one(&mut *cxn)
         .await?;
 
-    if let Some(tags) = article.tags {
-        // TODO: could probably be more efficient
-        for name in tags {
-            let tag: Tag = sqlx::query_as(CREATE_TAG_QUERY)
-                .bind(name)
-                .fetch_one(&mut *cxn)
-                .await?;
-
-            let _ = sqlx::query(CREATE_ARTICLE_TAG_QUERY)
-                .bind(row.id)
-                .bind(tag.id)
-                .execute(&mut *cxn)
-                .await?;
-        }
-    }
+    create_article_owner_and_tags(cxn, &row.id, user_id, article.tags).await?;
 
     query_article_view_by_slug(cxn, &row.slug, Some(*user_id))
         .await
         .map(|av| av.expect("article should exist"))
 }
 
+/// Imports a new [`Article`] row into the database under the slug contained in the given
+/// [`ImportArticle`]. If an article with the slug already exists, then nothing is imported and
+/// `None` is returned.
+pub async fn import_article(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+    article: ImportArticle<'_>,
+) -> Result<Option<ArticleView>, sqlx::Error> {
+    let row: Option<Article> = sqlx::query_as(IMPORT_ARTICLE_QUERY)
+        .bind(user_id)
+        .bind(article.slug)
+        .bind(article.title)
+        .bind(article.description)
+        .bind(article.body)
+        .bind(article.body_html)
+        .bind(article.word_count)
+        .bind(article.reading_time_minutes)
+        .bind(article.status)
+        .bind(article.created)
+        .bind(article.published_at)
+        .bind(article.publish_at)
+        .fetch_optional(&mut *cxn)
+        .await?;
+
+    let row = match row {
+        Some(row) => row,
+        None => return Ok(None),
+    };
+
+    create_article_owner_and_tags(cxn, &row.id, user_id, article.tags).await?;
+
+    query_article_view_by_slug(cxn, &row.slug, Some(*user_id)).await
+}
+
+/// Records the user as the owner of a newly created article and associates the tags with it,
+/// creating any of the tags that do not exist yet.
+async fn create_article_owner_and_tags(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_id: &Uuid,
+    tags: &[String],
+) -> Result<(), sqlx::Error> {
+    let _ = sqlx::query(CREATE_ARTICLE_OWNER_QUERY)
+        .bind(article_id)
+        .bind(user_id)
+        .execute(&mut *cxn)
+        .await?;
+
+    // TODO: could probably be more efficient
+    for name in tags {
+        let tag: Tag = sqlx::query_as(CREATE_TAG_QUERY)
+            .bind(name)
+            .fetch_one(&mut *cxn)
+            .await?;
+
+        let _ = sqlx::query(CREATE_ARTICLE_TAG_QUERY)
+            .bind(article_id)
+            .bind(tag.id)
+            .execute(&mut *cxn)
+            .await?;
+    }
+
+    Ok(())
+}
+
 /// Updates an existing [`Article`] row in the database identified by id using the details contained
 /// in the given [`UpdateArticle`].
 pub async fn update_article(
@@ -436,6 +1255,11 @@ pub async fn update_article(
         .bind(article.title)
         .bind(article.description)
         .bind(article.body)
+        .bind(article.body_html)
+        .bind(article.word_count)
+        .bind(article.reading_time_minutes)
+        .bind(article.status)
+        .bind(article.publish_at)
         .bind(id)
         .execute(&mut *cxn)
         .await?;
@@ -445,6 +1269,157 @@ pub async fn update_article(
         .map(|av| av.expect("article should exist"))
 }
 
+/// Publishes an existing [`Article`] identified by id and slug, setting the time it was published
+/// if it has never been published before.
+pub async fn publish_article(
+    cxn: &mut PgConnection,
+    id: &Uuid,
+    slug: &str,
+    user_ctx: &Uuid,
+) -> Result<ArticleView, sqlx::Error> {
+    let _ = sqlx::query(PUBLISH_ARTICLE_QUERY)
+        .bind(id)
+        .execute(&mut *cxn)
+        .await?;
+
+    query_article_view_by_slug(cxn, slug, Some(*user_ctx))
+        .await
+        .map(|av| av.expect("article should exist"))
+}
+
+/// Publishes a batch of at most `limit` scheduled articles whose publish time has passed and returns
+/// the [`ArticleView`]s of the newly published articles. The rows remain locked until the
+/// transaction that the connection is part of completes so that concurrent callers never publish
+/// the same article.
+pub async fn publish_due_articles(
+    cxn: &mut PgConnection,
+    limit: i64,
+) -> Result<Vec<ArticleView>, sqlx::Error> {
+    let slugs: Vec<String> = sqlx::query_scalar(PUBLISH_DUE_ARTICLES_QUERY)
+        .bind(limit)
+        .fetch_all(&mut *cxn)
+        .await?;
+
+    let mut articles = Vec::with_capacity(slugs.len());
+
+    for slug in slugs {
+        if let Some(view) = query_article_view_by_slug(cxn, &slug, None).await? {
+            articles.push(view);
+        }
+    }
+
+    Ok(articles)
+}
+
+/// Locks the batch of at most `limit` articles with the lowest ids greater than the given id and
+/// returns their ids. The rows remain locked until the transaction that the connection is part of
+/// completes.
+pub async fn lock_articles_batch(
+    cxn: &mut PgConnection,
+    after_id: &Uuid,
+    limit: i64,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar(LOCK_ARTICLES_BATCH_QUERY)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&mut *cxn)
+        .await
+}
+
+/// The [`UnrenderedArticle`] struct is used to let the `sqlx` library easily map the body of an
+/// article that has not been rendered to HTML to a struct value.
+#[derive(Debug, FromRow)]
+pub struct UnrenderedArticle {
+    /// Id of the article.
+    pub id: Uuid,
+    /// Markdown body of the article.
+    pub body: String,
+}
+
+/// Locks a batch of at most `limit` articles whose body has not been rendered to HTML and returns
+/// their bodies. The locks are held until the transaction ends so the rendered HTML should be
+/// stored within the same transaction.
+pub async fn lock_unrendered_articles(
+    cxn: &mut PgConnection,
+    limit: i64,
+) -> Result<Vec<UnrenderedArticle>, sqlx::Error> {
+    sqlx::query_as(LOCK_UNRENDERED_ARTICLES_QUERY)
+        .bind(limit)
+        .fetch_all(&mut *cxn)
+        .await
+}
+
+/// Stores the HTML rendered from the body of the article with the given id.
+pub async fn update_article_body_html(
+    cxn: &mut PgConnection,
+    id: &Uuid,
+    body_html: &str,
+) -> Result<(), sqlx::Error> {
+    let _ = sqlx::query(UPDATE_ARTICLE_BODY_HTML_QUERY)
+        .bind(body_html)
+        .bind(id)
+        .execute(&mut *cxn)
+        .await?;
+
+    Ok(())
+}
+
+/// Recomputes the favorites, comments and views counts of the articles with the given ids, which
+/// should be locked using [`lock_articles_batch`], and returns the number of articles that were
+/// corrected.
+pub async fn reconcile_article_counters(
+    cxn: &mut PgConnection,
+    article_ids: &[Uuid],
+) -> Result<u64, sqlx::Error> {
+    sqlx::query(RECONCILE_ARTICLE_COUNTERS_QUERY)
+        .bind(article_ids)
+        .execute(&mut *cxn)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// Recomputes the trending scores of the articles from their recent favorites so that the scores
+/// decay over time. Returns the number of articles whose scores were recomputed.
+pub async fn refresh_trending_scores(cxn: &mut PgConnection) -> Result<u64, sqlx::Error> {
+    sqlx::query(REFRESH_TRENDING_SCORES_QUERY)
+        .execute(&mut *cxn)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// Determines whether the user with the given id can edit the article with the given id, which is
+/// the case for its owner and the editors who have accepted the invitation to co-author it.
+pub async fn is_article_editor(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(IS_ARTICLE_EDITOR_QUERY)
+        .bind(article_id)
+        .bind(user_id)
+        .fetch_one(cxn)
+        .await
+}
+
+/// Determines whether the article can be seen by the user with the given id, if available. Drafts
+/// and scheduled articles can only be seen by their owner and the editors who have accepted the
+/// invitation to co-author them while published and unlisted articles can be seen by anyone who
+/// knows the slug.
+pub async fn is_article_visible(
+    cxn: &mut PgConnection,
+    article: &Article,
+    user_ctx: Option<Uuid>,
+) -> Result<bool, sqlx::Error> {
+    if article.is_public() || user_ctx == Some(article.user_id) {
+        return Ok(true);
+    }
+
+    match user_ctx {
+        Some(user_id) => is_article_editor(cxn, &article.id, &user_id).await,
+        None => Ok(false),
+    }
+}
+
 /// Retrieves an [`Article`] identified by the given slug, if it exists.
 pub async fn query_article_by_slug(
     cxn: &mut PgConnection,
@@ -456,6 +1431,34 @@ pub async fn query_article_by_slug(
         .await
 }
 
+/// Retrieves an [`Article`] identified by the given slug if it exists and can be seen by the user
+/// with the given id, if available, as determined by [`is_article_visible`].
+pub async fn query_visible_article_by_slug(
+    cxn: &mut PgConnection,
+    slug: &str,
+    user_ctx: Option<Uuid>,
+) -> Result<Option<Article>, sqlx::Error> {
+    let article = match query_article_by_slug(&mut *cxn, slug).await? {
+        Some(article) => article,
+        None => return Ok(None),
+    };
+
+    Ok(is_article_visible(cxn, &article, user_ctx)
+        .await?
+        .then_some(article))
+}
+
+/// Retrieves an [`Article`] in the trash identified by the given slug, if it exists.
+pub async fn query_trashed_article_by_slug(
+    cxn: &mut PgConnection,
+    slug: &str,
+) -> Result<Option<Article>, sqlx::Error> {
+    sqlx::query_as(GET_TRASHED_ARTICLE_BY_SLUG_QUERY)
+        .bind(slug)
+        .fetch_optional(&mut *cxn)
+        .await
+}
+
 /// Retrieves an [`ArticleView`] identified by the given slug, if it exsts, using the
 /// identifier of the authenticated user, if available, as the user context to determine
 /// if the article is favorited or not.
@@ -473,20 +1476,56 @@ pub async fn query_article_view_by_slug(
         .await
 }
 
+/// Retrieves the ids of at most `limit` published articles that are most similar to the article
+/// with the given id, most similar first.
+pub async fn query_related_article_ids(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    limit: i64,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar(LIST_RELATED_ARTICLE_IDS_QUERY)
+        .bind(article_id)
+        .bind(limit)
+        .fetch_all(cxn)
+        .await
+}
+
+/// Retrieves the [`ArticleView`]s of the articles with the given ids, in the same order, using the
+/// identifier of the authenticated user, if available, as the user context. Articles that no longer
+/// exist or are not published are left out.
+pub async fn query_published_article_views_by_ids(
+    cxn: &mut PgConnection,
+    ids: &[Uuid],
+    user_ctx: Option<Uuid>,
+) -> Result<Vec<ArticleView>, sqlx::Error> {
+    let user_context = user_ctx.unwrap_or_else(Uuid::nil);
+
+    sqlx::query_as(LIST_PUBLISHED_ARTICLE_VIEWS_BY_IDS_QUERY)
+        .bind(user_context)
+        .bind(ids)
+        .fetch_all(cxn)
+        .await
+}
+
 /// Retrives a [`Vec`] of [`ArticleView`]s that make up a page of articles in the feed of the
 /// specified user.
 pub async fn query_user_feed(
     cxn: &mut PgConnection,
     user_ctx: &Uuid,
-    limit: i32,
-    offset: i32,
+    page: &Page,
 ) -> Result<Vec<ArticleView>, sqlx::Error> {
-    sqlx::query_as(GET_USER_FEED_PAGE_QUERY)
+    let articles = sqlx::query_as(GET_USER_FEED_PAGE_QUERY)
         .bind(user_ctx)
-        .bind(limit)
-        .bind(offset)
+        .bind(page.limit())
+        .bind(page.offset())
+        .bind(page.after().map(|k| k.created))
+        .bind(page.after().map(|k| k.id))
+        .bind(page.before().map(|k| k.created))
+        .bind(page.before().map(|k| k.id))
         .fetch_all(&mut *cxn)
-        .await
+        .await?;
+
+    Ok(in_page_order(articles, page))
 }
 
 /// Counts the total number of articles in a user's feed.
@@ -497,6 +1536,62 @@ pub async fn count_user_feed(cxn: &mut PgConnection, user_ctx: &Uuid) -> Result<
         .await
 }
 
+/// Retrives a [`Vec`] of [`ArticleView`]s that make up a page of the draft articles of the
+/// specified user, including those that the user co-authors.
+pub async fn query_user_drafts(
+    cxn: &mut PgConnection,
+    user_ctx: &Uuid,
+    page: &Page,
+) -> Result<Vec<ArticleView>, sqlx::Error> {
+    let articles = sqlx::query_as(GET_USER_DRAFTS_PAGE_QUERY)
+        .bind(user_ctx)
+        .bind(page.limit())
+        .bind(page.offset())
+        .bind(page.after().map(|k| k.created))
+        .bind(page.after().map(|k| k.id))
+        .bind(page.before().map(|k| k.created))
+        .bind(page.before().map(|k| k.id))
+        .fetch_all(&mut *cxn)
+        .await?;
+
+    Ok(in_page_order(articles, page))
+}
+
+/// Counts the total number of draft articles of a user.
+pub async fn count_user_drafts(
+    cxn: &mut PgConnection,
+    user_ctx: &Uuid,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(COUNT_USER_DRAFTS_QUERY)
+        .bind(user_ctx)
+        .fetch_one(&mut *cxn)
+        .await
+}
+
+/// Moves the [`Article`] with the given identifier to the trash, which hides it from every listing
+/// and lookup by slug until it is restored or purged. Returns `false` if the article was already
+/// in the trash.
+pub async fn trash_article(cxn: &mut PgConnection, article_id: &Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query(TRASH_ARTICLE_QUERY)
+        .bind(article_id)
+        .execute(&mut *cxn)
+        .await
+        .map(|r| r.rows_affected() > 0)
+}
+
+/// Restores the [`Article`] with the given identifier from the trash. Returns `false` if the
+/// article was not in the trash.
+pub async fn restore_article(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query(RESTORE_ARTICLE_QUERY)
+        .bind(article_id)
+        .execute(&mut *cxn)
+        .await
+        .map(|r| r.rows_affected() > 0)
+}
+
 /// Deletes an [`Article`] and any existing relational data given the identifier.
 pub async fn delete_article_by_id(
     cxn: &mut PgConnection,
@@ -508,12 +1603,48 @@ pub async fn delete_article_by_id(
         .execute(&mut *cxn)
         .await?;
 
+    // delete any bookmarks
+    let _ = sqlx::query(DELETE_ARTICLE_BOOKMARKS_QUERY)
+        .bind(article_id)
+        .execute(&mut *cxn)
+        .await?;
+
+    // delete any collection membership
+    let _ = sqlx::query(DELETE_ARTICLE_COLLECTIONS_QUERY)
+        .bind(article_id)
+        .execute(&mut *cxn)
+        .await?;
+
+    // delete any authors
+    let _ = sqlx::query(DELETE_ARTICLE_AUTHORS_QUERY)
+        .bind(article_id)
+        .execute(&mut *cxn)
+        .await?;
+
+    // delete any daily views
+    let _ = sqlx::query(DELETE_ARTICLE_DAILY_VIEWS_QUERY)
+        .bind(article_id)
+        .execute(&mut *cxn)
+        .await?;
+
     // delete any tags associations
     let _ = sqlx::query(DELETE_ARTICLE_TAGS_QUERY)
         .bind(article_id)
         .execute(&mut *cxn)
         .await?;
 
+    // delete any comments
+    let _ = sqlx::query(DELETE_ARTICLE_COMMENTS_QUERY)
+        .bind(article_id)
+        .execute(&mut *cxn)
+        .await?;
+
+    // delete any revisions
+    let _ = sqlx::query(DELETE_ARTICLE_REVISIONS_QUERY)
+        .bind(article_id)
+        .execute(&mut *cxn)
+        .await?;
+
     // finally delete the article
     let _ = sqlx::query(DELETE_ARTICLE_QUERY)
         .bind(article_id)
@@ -538,36 +1669,67 @@ pub async fn add_article_comment(
         .await
 }
 
-/// Deletes an the entry from the article comments table that matches the comment and user
-/// identifiers.
-pub async fn remove_article_comment(
+/// Moves the comment on the article that matches the comment, article and user identifiers to the
+/// trash. Returns `false` if there is no such comment or it was already in the trash.
+pub async fn trash_article_comment(
     cxn: &mut PgConnection,
     comment_id: &Uuid,
+    article_id: &Uuid,
     user_id: &Uuid,
-) -> Result<(), sqlx::Error> {
-    sqlx::query(DELETE_ARTICLE_COMMENT_QUERY)
+) -> Result<bool, sqlx::Error> {
+    sqlx::query(TRASH_ARTICLE_COMMENT_QUERY)
         .bind(comment_id)
+        .bind(article_id)
         .bind(user_id)
         .execute(&mut *cxn)
         .await
-        .map(|_| ())
+        .map(|r| r.rows_affected() > 0)
 }
 
-/// Retrives a [`Vec`] that contains all of the [`CommentView`]s that are associated to an article.
-/// using the identifier of the authenticated user, if available, as the user context to determine
-/// if the author followed status.
+/// Restores the comment on the article that matches the comment, article and user identifiers from
+/// the trash. Returns the [`CommentView`] of the restored comment or `None` if there is no such
+/// comment in the trash.
+pub async fn restore_article_comment(
+    cxn: &mut PgConnection,
+    comment_id: &Uuid,
+    article_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<Option<CommentView>, sqlx::Error> {
+    sqlx::query_as(RESTORE_ARTICLE_COMMENT_QUERY)
+        .bind(comment_id)
+        .bind(article_id)
+        .bind(user_id)
+        .fetch_optional(&mut *cxn)
+        .await
+}
+
+/// Retrives a [`Vec`] that contains the [`CommentView`]s that are associated to an article, either
+/// all of them or only those in the given [`Page`], using the identifier of the authenticated user,
+/// if available, as the user context to determine if the author followed status.
 pub async fn query_article_comments_by_slug(
     cxn: &mut PgConnection,
     slug: &str,
     user_ctx: Option<Uuid>,
+    page: Option<&Page>,
 ) -> Result<Vec<CommentView>, sqlx::Error> {
     let user_context = user_ctx.unwrap_or_else(Uuid::nil);
 
-    sqlx::query_as(GET_ARTICLE_COMMENTS_BY_SLUG_QUERY)
+    let comments = sqlx::query_as(GET_ARTICLE_COMMENTS_BY_SLUG_QUERY)
         .bind(user_context)
         .bind(slug)
+        .bind(page.map(Page::limit))
+        .bind(page.map_or(0, Page::offset))
+        .bind(page.and_then(Page::after).map(|k| k.created))
+        .bind(page.and_then(Page::after).map(|k| k.id))
+        .bind(page.and_then(Page::before).map(|k| k.created))
+        .bind(page.and_then(Page::before).map(|k| k.id))
         .fetch_all(&mut *cxn)
-        .await
+        .await?;
+
+    Ok(match page {
+        Some(page) => in_page_order(comments, page),
+        None => comments,
+    })
 }
 
 /// The [`SlugW`] struct is a smaller wrapper around a String that makes it easy to deserialize a
@@ -0,0 +1,221 @@
+use crate::db::{article::ArticleView, in_page_order, Page};
+
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+/// SQL query used to bookmark an article for a user. Bookmarking an article that is already
+/// bookmarked leaves the existing bookmark and its read progress unchanged.
+const CREATE_BOOKMARK_QUERY: &str = r#"
+    INSERT INTO article_bookmarks (article_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING"#;
+
+/// SQL query used to remove the bookmark of an article for a user.
+const DELETE_BOOKMARK_QUERY: &str =
+    "DELETE FROM article_bookmarks WHERE article_id = $1 AND user_id = $2";
+
+/// SQL query used to set the read progress of an existing bookmark.
+const UPDATE_BOOKMARK_PROGRESS_QUERY: &str = r#"
+    UPDATE
+        article_bookmarks
+    SET
+        progress = $3,
+        updated = NOW()
+    WHERE
+        article_id = $1 AND user_id = $2"#;
+
+/// SQL query used to fetch a single page of the bookmarks of a user, most recently bookmarked first,
+/// along with a view of each bookmarked article. Articles that are no longer visible to the user are
+/// excluded. The page can be selected by offset or by the position of a bookmark, in which case the
+/// rows of a page that precedes a position are selected in ascending order and must be reversed by
+/// the caller.
+const GET_USER_BOOKMARKS_PAGE_QUERY: &str = concat!(
+    r#"
+    SELECT
+        a.*,
+        s.*,
+        co.coauthors,
+        (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id AND af.user_id = $1)::int::bool AS favorited,
+        true AS bookmarked,
+        ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC) AS tags,
+        u.id AS author_id,
+        u.name AS author_name,
+        u.bio AS author_bio,
+        u.image AS author_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS author_followed,
+        ab.progress AS bookmark_progress,
+        ab.created AS bookmark_created,
+        ab.updated AS bookmark_updated
+    FROM
+        article_bookmarks AS ab INNER JOIN articles AS a ON ab.article_id = a.id INNER JOIN users AS u ON a.user_id = u.id"#,
+    article_series_join!(),
+    article_coauthors_join!(),
+    r#"
+    WHERE
+        ab.user_id = $1 AND a.deleted_at IS NULL AND (a.status IN ('published', 'unlisted') OR "#,
+    article_editor!("a"),
+    r#")
+
+        AND
+
+        ($4::timestamptz IS NULL OR (ab.created, ab.article_id) < ($4, $5::uuid))
+
+        AND
+
+        ($6::timestamptz IS NULL OR (ab.created, ab.article_id) > ($6, $7::uuid))
+    ORDER BY
+        CASE WHEN $6::timestamptz IS NULL THEN NULL ELSE ab.created END ASC,
+        CASE WHEN $6::timestamptz IS NULL THEN NULL ELSE ab.article_id END ASC,
+        ab.created DESC,
+        ab.article_id DESC
+    LIMIT
+        $2
+    OFFSET
+        $3"#
+);
+
+/// SQL query used to get a total count of the bookmarks of a user.
+const COUNT_USER_BOOKMARKS_QUERY: &str = concat!(
+    r#"
+    SELECT
+        COUNT(ab.article_id)
+    FROM
+        article_bookmarks AS ab INNER JOIN articles AS a ON ab.article_id = a.id
+    WHERE
+        ab.user_id = $1 AND a.deleted_at IS NULL AND (a.status IN ('published', 'unlisted') OR "#,
+    article_editor!("a"),
+    ")"
+);
+
+/// SQL query used to fetch the bookmark of an article for a user along with a view of the article.
+const GET_BOOKMARK_VIEW_QUERY: &str = concat!(
+    r#"
+    SELECT
+        a.*,
+        s.*,
+        co.coauthors,
+        (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id AND af.user_id = $1)::int::bool AS favorited,
+        true AS bookmarked,
+        ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC) AS tags,
+        u.id AS author_id,
+        u.name AS author_name,
+        u.bio AS author_bio,
+        u.image AS author_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS author_followed,
+        ab.progress AS bookmark_progress,
+        ab.created AS bookmark_created,
+        ab.updated AS bookmark_updated
+    FROM
+        article_bookmarks AS ab INNER JOIN articles AS a ON ab.article_id = a.id INNER JOIN users AS u ON a.user_id = u.id"#,
+    article_series_join!(),
+    article_coauthors_join!(),
+    r#"
+    WHERE
+        ab.user_id = $1 AND ab.article_id = $2 AND a.deleted_at IS NULL"#
+);
+
+/// The [`BookmarkView`] struct is used to let the `sqlx` library easily map a view of the
+/// `article_bookmarks` table and the bookmarked article to a struct value.
+#[derive(Debug, FromRow)]
+pub struct BookmarkView {
+    /// View of the bookmarked article.
+    #[sqlx(flatten)]
+    pub article: ArticleView,
+    /// Percentage of the article that the user has read.
+    pub bookmark_progress: i16,
+    /// Time the article was bookmarked.
+    pub bookmark_created: DateTime<Utc>,
+    /// Time the read progress was last updated.
+    pub bookmark_updated: Option<DateTime<Utc>>,
+}
+
+/// Inserts an entry into the table that tracks the bookmarked articles of a user, unless the
+/// article is already bookmarked.
+pub async fn add_bookmark(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(CREATE_BOOKMARK_QUERY)
+        .bind(article_id)
+        .bind(user_id)
+        .execute(cxn)
+        .await
+        .map(|_| ())
+}
+
+/// Deletes the entry from the table that tracks the bookmarked articles of a user, if it exists.
+pub async fn remove_bookmark(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(DELETE_BOOKMARK_QUERY)
+        .bind(article_id)
+        .bind(user_id)
+        .execute(cxn)
+        .await
+        .map(|_| ())
+}
+
+/// Sets the read progress of the bookmark of an article for a user and returns the updated
+/// [`BookmarkView`], or [`None`] if the user has not bookmarked the article.
+pub async fn update_bookmark_progress(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_id: &Uuid,
+    progress: i16,
+) -> Result<Option<BookmarkView>, sqlx::Error> {
+    let _ = sqlx::query(UPDATE_BOOKMARK_PROGRESS_QUERY)
+        .bind(article_id)
+        .bind(user_id)
+        .bind(progress)
+        .execute(&mut *cxn)
+        .await?;
+
+    query_bookmark(cxn, article_id, user_id).await
+}
+
+/// Retrieves the [`BookmarkView`] of the article with the given id for a user, if it exists.
+pub async fn query_bookmark(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<Option<BookmarkView>, sqlx::Error> {
+    sqlx::query_as(GET_BOOKMARK_VIEW_QUERY)
+        .bind(user_id)
+        .bind(article_id)
+        .fetch_optional(cxn)
+        .await
+}
+
+/// Retrives a [`Vec`] of [`BookmarkView`]s that make up a page of the bookmarks of the specified
+/// user.
+pub async fn query_user_bookmarks(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+    page: &Page,
+) -> Result<Vec<BookmarkView>, sqlx::Error> {
+    let bookmarks = sqlx::query_as(GET_USER_BOOKMARKS_PAGE_QUERY)
+        .bind(user_id)
+        .bind(page.limit())
+        .bind(page.offset())
+        .bind(page.after().map(|k| k.created))
+        .bind(page.after().map(|k| k.id))
+        .bind(page.before().map(|k| k.created))
+        .bind(page.before().map(|k| k.id))
+        .fetch_all(&mut *cxn)
+        .await?;
+
+    Ok(in_page_order(bookmarks, page))
+}
+
+/// Counts the total number of bookmarks of a user.
+pub async fn count_user_bookmarks(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(COUNT_USER_BOOKMARKS_QUERY)
+        .bind(user_id)
+        .fetch_one(cxn)
+        .await
+}
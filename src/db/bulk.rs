@@ -0,0 +1,65 @@
+use crate::db::article::ArticleStatus;
+
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+/// SQL query used to fetch all of the articles that a user owns for export, oldest first, whatever
+/// their publication status. Articles in the trash are not exported.
+const LIST_EXPORTED_ARTICLES_QUERY: &str = r#"
+    SELECT
+        a.slug,
+        a.title,
+        a.description,
+        a.body,
+        ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC) AS tags,
+        a.status,
+        a.created,
+        a.updated,
+        a.published_at,
+        a.publish_at
+    FROM
+        articles AS a
+    WHERE
+        a.user_id = $1 AND a.deleted_at IS NULL
+    ORDER BY
+        a.created ASC,
+        a.id ASC"#;
+
+/// The [`ExportedArticle`] struct is used to let the `sqlx` library easily map the content of an
+/// article that is exported to a struct value.
+#[derive(Debug, FromRow)]
+pub struct ExportedArticle {
+    /// Slugified title of the article.
+    pub slug: String,
+    /// Title of the article.
+    pub title: String,
+    /// Description of the article.
+    pub description: String,
+    /// Body of the article.
+    pub body: String,
+    /// Names of the tags associated with the article.
+    pub tags: Vec<String>,
+    /// Publication status of the article.
+    pub status: ArticleStatus,
+    /// Time the article was created.
+    pub created: DateTime<Utc>,
+    /// Time the article was last modified.
+    pub updated: Option<DateTime<Utc>>,
+    /// Time the article was first published.
+    pub published_at: Option<DateTime<Utc>>,
+    /// Time the article is scheduled to be published.
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+/// Retrieves a [`Vec`] of the [`ExportedArticle`]s of all of the articles owned by the specified
+/// user that are not in the trash.
+pub async fn query_exported_articles(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+) -> Result<Vec<ExportedArticle>, sqlx::Error> {
+    sqlx::query_as(LIST_EXPORTED_ARTICLES_QUERY)
+        .bind(user_id)
+        .fetch_all(&mut *cxn)
+        .await
+}
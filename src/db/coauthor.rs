@@ -0,0 +1,228 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+/// SQL query used to invite a user to co-author an article as an editor. Inviting a user who is
+/// already an author of the article or who has a pending invitation has no effect.
+const CREATE_INVITATION_QUERY: &str = r#"
+    INSERT INTO
+        article_authors (article_id, user_id, role, status, invited_by)
+    VALUES
+        ($1, $2, 'editor', 'pending', $3)
+    ON CONFLICT DO NOTHING"#;
+
+/// SQL query used to accept a pending invitation to co-author an article.
+const ACCEPT_INVITATION_QUERY: &str = r#"
+    UPDATE
+        article_authors
+    SET
+        status = 'accepted',
+        responded = NOW()
+    WHERE
+        article_id = $1 AND user_id = $2 AND status = 'pending'"#;
+
+/// SQL query used to decline a pending invitation to co-author an article.
+const DECLINE_INVITATION_QUERY: &str =
+    "DELETE FROM article_authors WHERE article_id = $1 AND user_id = $2 AND status = 'pending'";
+
+/// SQL query used to remove an editor from an article, whether the invitation is pending or not.
+const DELETE_EDITOR_QUERY: &str =
+    "DELETE FROM article_authors WHERE article_id = $1 AND user_id = $2 AND role = 'editor'";
+
+/// SQL query used to fetch the authors of an article, including pending invitations, with the owner
+/// first followed by the editors in the order they were invited.
+const LIST_ARTICLE_AUTHOR_VIEWS_QUERY: &str = r#"
+    SELECT
+        aa.role,
+        aa.status,
+        aa.created,
+        aa.responded,
+        u.id AS user_id,
+        u.name AS user_name,
+        u.bio AS user_bio,
+        u.image AS user_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS user_followed
+    FROM
+        article_authors AS aa INNER JOIN users AS u ON aa.user_id = u.id
+    WHERE
+        aa.article_id = $2
+    ORDER BY
+        CASE WHEN aa.role = 'owner' THEN 0 ELSE 1 END ASC,
+        aa.created ASC"#;
+
+/// SQL query used to fetch the pending invitations of a user to co-author articles, most recent
+/// first.
+const LIST_INVITATION_VIEWS_QUERY: &str = r#"
+    SELECT
+        aa.created,
+        a.slug AS article_slug,
+        a.title AS article_title,
+        u.id AS inviter_id,
+        u.name AS inviter_name,
+        u.bio AS inviter_bio,
+        u.image AS inviter_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS inviter_followed
+    FROM
+        article_authors AS aa INNER JOIN articles AS a ON aa.article_id = a.id INNER JOIN users AS u ON aa.invited_by = u.id
+    WHERE
+        aa.user_id = $1 AND aa.status = 'pending' AND a.deleted_at IS NULL
+    ORDER BY
+        aa.created DESC"#;
+
+/// Enumerates the roles that the authors of an article can have.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum AuthorRole {
+    /// The primary author of the article, who can invite and remove editors and delete the article.
+    Owner,
+    /// A co-author of the article, who can edit the article.
+    Editor,
+}
+
+/// Enumerates the statuses of the invitation of a user to co-author an article.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum InvitationStatus {
+    /// The user has not yet responded to the invitation.
+    Pending,
+    /// The user has accepted the invitation and is an author of the article.
+    Accepted,
+}
+
+/// The [`ArticleAuthorView`] struct is used to let the `sqlx` library easily map a view of the
+/// `article_authors` table and the user who is an author of the article to a struct value.
+#[derive(Debug, FromRow)]
+pub struct ArticleAuthorView {
+    /// Role of the user.
+    pub role: AuthorRole,
+    /// Status of the invitation of the user.
+    pub status: InvitationStatus,
+    /// Time the user was invited, or the article was created in the case of the owner.
+    pub created: DateTime<Utc>,
+    /// Time the user accepted the invitation.
+    pub responded: Option<DateTime<Utc>>,
+    /// Id of the user.
+    pub user_id: Uuid,
+    /// Username of the user.
+    pub user_name: String,
+    /// Bio of the user.
+    pub user_bio: String,
+    /// URL to the image of the user.
+    pub user_image: Option<String>,
+    /// Flag indicating whether or not the user is being followed by the currently authenticated
+    /// user.
+    pub user_followed: bool,
+}
+
+/// The [`InvitationView`] struct is used to let the `sqlx` library easily map a view of a pending
+/// invitation to co-author an article to a struct value.
+#[derive(Debug, FromRow)]
+pub struct InvitationView {
+    /// Time the user was invited.
+    pub created: DateTime<Utc>,
+    /// Slug of the article.
+    pub article_slug: String,
+    /// Title of the article.
+    pub article_title: String,
+    /// Id of the user who sent the invitation.
+    pub inviter_id: Uuid,
+    /// Username of the user who sent the invitation.
+    pub inviter_name: String,
+    /// Bio of the user who sent the invitation.
+    pub inviter_bio: String,
+    /// URL to the image of the user who sent the invitation.
+    pub inviter_image: Option<String>,
+    /// Flag indicating whether or not the user who sent the invitation is being followed by the
+    /// invited user.
+    pub inviter_followed: bool,
+}
+
+/// Invites the user with the given id to co-author the article with the given id as an editor,
+/// unless the user is already an author of the article or has a pending invitation.
+pub async fn invite_coauthor(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_id: &Uuid,
+    invited_by: &Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(CREATE_INVITATION_QUERY)
+        .bind(article_id)
+        .bind(user_id)
+        .bind(invited_by)
+        .execute(cxn)
+        .await
+        .map(|_| ())
+}
+
+/// Accepts the pending invitation of the user to co-author the article. Returns `false` if the
+/// user does not have a pending invitation.
+pub async fn accept_invitation(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query(ACCEPT_INVITATION_QUERY)
+        .bind(article_id)
+        .bind(user_id)
+        .execute(cxn)
+        .await
+        .map(|r| r.rows_affected() > 0)
+}
+
+/// Declines the pending invitation of the user to co-author the article. Returns `false` if the
+/// user does not have a pending invitation.
+pub async fn decline_invitation(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query(DECLINE_INVITATION_QUERY)
+        .bind(article_id)
+        .bind(user_id)
+        .execute(cxn)
+        .await
+        .map(|r| r.rows_affected() > 0)
+}
+
+/// Removes the user as an editor of the article, along with any pending invitation. Returns
+/// `false` if the user is not an editor of the article.
+pub async fn remove_coauthor(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query(DELETE_EDITOR_QUERY)
+        .bind(article_id)
+        .bind(user_id)
+        .execute(cxn)
+        .await
+        .map(|r| r.rows_affected() > 0)
+}
+
+/// Retrieves the [`ArticleAuthorView`]s of the article with the given id, including pending
+/// invitations, using the identifier of the authenticated user as the user context.
+pub async fn query_article_authors(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_ctx: &Uuid,
+) -> Result<Vec<ArticleAuthorView>, sqlx::Error> {
+    sqlx::query_as(LIST_ARTICLE_AUTHOR_VIEWS_QUERY)
+        .bind(user_ctx)
+        .bind(article_id)
+        .fetch_all(cxn)
+        .await
+}
+
+/// Retrieves the [`InvitationView`]s of the pending invitations of the user with the given id.
+pub async fn query_user_invitations(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+) -> Result<Vec<InvitationView>, sqlx::Error> {
+    sqlx::query_as(LIST_INVITATION_VIEWS_QUERY)
+        .bind(user_id)
+        .fetch_all(cxn)
+        .await
+}
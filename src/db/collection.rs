@@ -0,0 +1,399 @@
+use crate::db::{article::ArticleView, in_page_order, Page};
+
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+/// SQL query used to create a new collection in the database.
+const CREATE_COLLECTION_QUERY: &str = r#"
+    INSERT INTO
+        collections (user_id, slug, title, description)
+    VALUES
+        ($1, $2, $3, $4)
+    RETURNING *"#;
+
+/// SQL query used to update an existing collection in the database.
+const UPDATE_COLLECTION_QUERY: &str = r#"
+    UPDATE
+        collections
+    SET
+        slug = $1,
+        title = $2,
+        description = $3,
+        updated = NOW()
+    WHERE
+        id = $4
+    RETURNING *"#;
+
+/// SQL query used to fetch a collection by slug.
+const GET_COLLECTION_BY_SLUG_QUERY: &str = "SELECT * FROM collections WHERE slug = $1";
+
+/// SQL query used to fetch a computed view of a collection by slug. Only the articles that are
+/// published or co-authored by the user bound to `$1` are counted.
+const GET_COLLECTION_VIEW_BY_SLUG_QUERY: &str = concat!(
+    r#"
+    SELECT
+        c.*,
+        (SELECT COUNT(*) FROM collection_articles AS ca INNER JOIN articles AS a ON ca.article_id = a.id WHERE ca.collection_id = c.id AND a.deleted_at IS NULL AND (a.status = 'published' OR "#,
+    article_editor!("a"),
+    r#")) AS articles_count,
+        u.id AS author_id,
+        u.name AS author_name,
+        u.bio AS author_bio,
+        u.image AS author_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS author_followed
+    FROM
+        collections AS c INNER JOIN users AS u ON c.user_id = u.id
+    WHERE
+        c.slug = $2"#
+);
+
+/// SQL query used to fetch a single page of collections, optionally only those of an author, most
+/// recently created first. The page can be selected by offset or by the position of a collection,
+/// in which case the rows of a page that precedes a position are selected in ascending order.
+const LIST_COLLECTION_VIEWS_QUERY: &str = concat!(
+    r#"
+    SELECT
+        c.*,
+        (SELECT COUNT(*) FROM collection_articles AS ca INNER JOIN articles AS a ON ca.article_id = a.id WHERE ca.collection_id = c.id AND a.deleted_at IS NULL AND (a.status = 'published' OR "#,
+    article_editor!("a"),
+    r#")) AS articles_count,
+        u.id AS author_id,
+        u.name AS author_name,
+        u.bio AS author_bio,
+        u.image AS author_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS author_followed
+    FROM
+        collections AS c INNER JOIN users AS u ON c.user_id = u.id
+    WHERE
+        ($2::text IS NULL OR u.name = $2)
+
+        AND
+
+        ($5::timestamptz IS NULL OR (c.created, c.id) < ($5, $6::uuid))
+
+        AND
+
+        ($7::timestamptz IS NULL OR (c.created, c.id) > ($7, $8::uuid))
+    ORDER BY
+        CASE WHEN $7::timestamptz IS NULL THEN NULL ELSE c.created END ASC,
+        CASE WHEN $7::timestamptz IS NULL THEN NULL ELSE c.id END ASC,
+        c.created DESC,
+        c.id DESC
+    LIMIT
+        $3
+    OFFSET
+        $4"#
+);
+
+/// SQL query used to get a total count of the collections, optionally only those of an author.
+const COUNT_COLLECTIONS_QUERY: &str = r#"
+    SELECT
+        COUNT(c.id)
+    FROM
+        collections AS c INNER JOIN users AS u ON c.user_id = u.id
+    WHERE
+        ($1::text IS NULL OR u.name = $1)"#;
+
+/// SQL query used to fetch the views of the articles of a collection in order. Only the articles
+/// that are published or co-authored by the user bound to `$1` are returned.
+const LIST_COLLECTION_ARTICLE_VIEWS_QUERY: &str = concat!(
+    r#"
+    SELECT
+        a.*,
+        s.*,
+        co.coauthors,
+        (SELECT COUNT(af.*) FROM article_favs AS af WHERE af.article_id = a.id AND af.user_id = $1)::int::bool AS favorited,
+        (SELECT COUNT(ab.*) FROM article_bookmarks AS ab WHERE ab.article_id = a.id AND ab.user_id = $1)::int::bool AS bookmarked,
+        ARRAY(SELECT t.name FROM tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id WHERE at.article_id = a.id ORDER BY t.name ASC) AS tags,
+        u.id AS author_id,
+        u.name AS author_name,
+        u.bio AS author_bio,
+        u.image AS author_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS author_followed
+    FROM
+        collection_articles AS cm INNER JOIN articles AS a ON cm.article_id = a.id INNER JOIN users AS u ON a.user_id = u.id"#,
+    article_series_join!(),
+    article_coauthors_join!(),
+    r#"
+    WHERE
+        cm.collection_id = $2 AND a.deleted_at IS NULL AND (a.status = 'published' OR "#,
+    article_editor!("a"),
+    r#")
+    ORDER BY
+        cm.position ASC"#
+);
+
+/// SQL query used to fetch the articles with the given slugs along with the collection that each
+/// article is part of, if any.
+const GET_COLLECTION_CANDIDATES_QUERY: &str = r#"
+    SELECT
+        a.id,
+        a.slug,
+        a.user_id,
+        ca.collection_id
+    FROM
+        articles AS a LEFT JOIN collection_articles AS ca ON a.id = ca.article_id
+    WHERE
+        a.slug = ANY($1) AND a.deleted_at IS NULL"#;
+
+/// SQL query used to remove all of the articles from a collection.
+const DELETE_COLLECTION_ARTICLES_QUERY: &str =
+    "DELETE FROM collection_articles WHERE collection_id = $1";
+
+/// SQL query used to add articles to a collection in the order of the given ids.
+const CREATE_COLLECTION_ARTICLES_QUERY: &str = r#"
+    INSERT INTO
+        collection_articles (collection_id, article_id, position)
+    SELECT
+        $1, ids.id, ids.position::int
+    FROM
+        UNNEST($2::uuid[]) WITH ORDINALITY AS ids(id, position)"#;
+
+/// SQL query used to delete a collection.
+const DELETE_COLLECTION_QUERY: &str = "DELETE FROM collections WHERE id = $1";
+
+/// The [`Collection`] struct is used to let the `sqlx` library easily map a row from the
+/// `collections` table in the database to a struct value. It is a one-to-one mapping from the
+/// database table.
+#[derive(Debug, FromRow)]
+pub struct Collection {
+    /// Id of the collection.
+    pub id: Uuid,
+    /// Id of the user who owns the collection.
+    pub user_id: Uuid,
+    /// Slugified title of the collection.
+    pub slug: String,
+    /// Title of the collection.
+    pub title: String,
+    /// Description of the collection.
+    pub description: String,
+    /// Time the collection was created.
+    #[allow(dead_code)]
+    pub created: DateTime<Utc>,
+    /// Time the collection was last modified.
+    #[allow(dead_code)]
+    pub updated: Option<DateTime<Utc>>,
+}
+
+/// The [`CollectionView`] struct is used to let the `sqlx` library easily map a view of the
+/// `collections` table and the user who owns the collection to a struct value.
+#[derive(Debug, FromRow)]
+pub struct CollectionView {
+    /// Id of the collection.
+    pub id: Uuid,
+    /// Slugified title of the collection.
+    pub slug: String,
+    /// Title of the collection.
+    pub title: String,
+    /// Description of the collection.
+    pub description: String,
+    /// Time the collection was created.
+    pub created: DateTime<Utc>,
+    /// Time the collection was last modified.
+    pub updated: Option<DateTime<Utc>>,
+    /// Count of the articles in the collection that are visible to the user.
+    pub articles_count: i64,
+    /// Id of the owner.
+    pub author_id: Uuid,
+    /// Username of the owner.
+    pub author_name: String,
+    /// Bio for the the owner.
+    pub author_bio: String,
+    /// URL to the image of the owner.
+    pub author_image: Option<String>,
+    /// Flag indicating whether or not the owner is being followed by the currently authenticated
+    /// user. If no user is curently logged in, then the value will be set to `false`.
+    pub author_followed: bool,
+}
+
+/// The [`CollectionCandidate`] struct contains the details of an article that are needed to
+/// determine whether it can be added to a collection.
+#[derive(Debug, FromRow)]
+pub struct CollectionCandidate {
+    /// Id of the article.
+    pub id: Uuid,
+    /// Slugified title of the article.
+    pub slug: String,
+    /// Id of the user who authored the article.
+    pub user_id: Uuid,
+    /// Id of the collection that the article is already part of, if any.
+    pub collection_id: Option<Uuid>,
+}
+
+/// The [`CreateCollection`] struct contains the data required to create a collection in the
+/// database.
+#[derive(Debug)]
+pub struct CreateCollection<'a> {
+    /// Title of the collection.
+    pub title: &'a str,
+    /// Description of the collection.
+    pub description: &'a str,
+}
+
+/// The [`UpdateCollection`] struct contains the data required to update a collection in the
+/// database.
+#[derive(Debug)]
+pub struct UpdateCollection<'a> {
+    /// Title of the collection.
+    pub title: &'a str,
+    /// Description of the collection.
+    pub description: &'a str,
+}
+
+/// Creates a new [`Collection`] row in the database owned by the given user using the details
+/// contained in the given [`CreateCollection`].
+pub async fn create_collection(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+    collection: CreateCollection<'_>,
+) -> Result<Collection, sqlx::Error> {
+    sqlx::query_as(CREATE_COLLECTION_QUERY)
+        .bind(user_id)
+        .bind(slug::slugify(collection.title))
+        .bind(collection.title)
+        .bind(collection.description)
+        .fetch_one(cxn)
+        .await
+}
+
+/// Updates an existing [`Collection`] row in the database identified by id using the details
+/// contained in the given [`UpdateCollection`].
+pub async fn update_collection(
+    cxn: &mut PgConnection,
+    id: &Uuid,
+    collection: UpdateCollection<'_>,
+) -> Result<Collection, sqlx::Error> {
+    sqlx::query_as(UPDATE_COLLECTION_QUERY)
+        .bind(slug::slugify(collection.title))
+        .bind(collection.title)
+        .bind(collection.description)
+        .bind(id)
+        .fetch_one(cxn)
+        .await
+}
+
+/// Replaces the articles of the collection with the given id with the articles with the given ids,
+/// in order.
+pub async fn set_collection_articles(
+    cxn: &mut PgConnection,
+    collection_id: &Uuid,
+    article_ids: &[Uuid],
+) -> Result<(), sqlx::Error> {
+    let _ = sqlx::query(DELETE_COLLECTION_ARTICLES_QUERY)
+        .bind(collection_id)
+        .execute(&mut *cxn)
+        .await?;
+
+    let _ = sqlx::query(CREATE_COLLECTION_ARTICLES_QUERY)
+        .bind(collection_id)
+        .bind(article_ids)
+        .execute(&mut *cxn)
+        .await?;
+
+    Ok(())
+}
+
+/// Retrieves a [`Collection`] identified by the given slug, if it exists.
+pub async fn query_collection_by_slug(
+    cxn: &mut PgConnection,
+    slug: &str,
+) -> Result<Option<Collection>, sqlx::Error> {
+    sqlx::query_as(GET_COLLECTION_BY_SLUG_QUERY)
+        .bind(slug)
+        .fetch_optional(cxn)
+        .await
+}
+
+/// Retrieves a [`CollectionView`] identified by the given slug, if it exists, using the identifier
+/// of the authenticated user, if available, as the user context.
+pub async fn query_collection_view_by_slug(
+    cxn: &mut PgConnection,
+    slug: &str,
+    user_ctx: Option<Uuid>,
+) -> Result<Option<CollectionView>, sqlx::Error> {
+    sqlx::query_as(GET_COLLECTION_VIEW_BY_SLUG_QUERY)
+        .bind(user_ctx.unwrap_or_else(Uuid::nil))
+        .bind(slug)
+        .fetch_optional(cxn)
+        .await
+}
+
+/// Retrives a [`Vec`] of [`CollectionView`]s that make up a page of collections, optionally only
+/// those owned by the user with the given username.
+pub async fn query_collections(
+    cxn: &mut PgConnection,
+    user_ctx: Option<Uuid>,
+    author: Option<&str>,
+    page: &Page,
+) -> Result<Vec<CollectionView>, sqlx::Error> {
+    let collections = sqlx::query_as(LIST_COLLECTION_VIEWS_QUERY)
+        .bind(user_ctx.unwrap_or_else(Uuid::nil))
+        .bind(author)
+        .bind(page.limit())
+        .bind(page.offset())
+        .bind(page.after().map(|k| k.created))
+        .bind(page.after().map(|k| k.id))
+        .bind(page.before().map(|k| k.created))
+        .bind(page.before().map(|k| k.id))
+        .fetch_all(&mut *cxn)
+        .await?;
+
+    Ok(in_page_order(collections, page))
+}
+
+/// Counts the total number of collections, optionally only those owned by the user with the given
+/// username.
+pub async fn count_collections(
+    cxn: &mut PgConnection,
+    author: Option<&str>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(COUNT_COLLECTIONS_QUERY)
+        .bind(author)
+        .fetch_one(cxn)
+        .await
+}
+
+/// Retrieves the [`ArticleView`]s of the articles of the collection with the given id in order,
+/// using the identifier of the authenticated user, if available, as the user context.
+pub async fn query_collection_articles(
+    cxn: &mut PgConnection,
+    collection_id: &Uuid,
+    user_ctx: Option<Uuid>,
+) -> Result<Vec<ArticleView>, sqlx::Error> {
+    sqlx::query_as(LIST_COLLECTION_ARTICLE_VIEWS_QUERY)
+        .bind(user_ctx.unwrap_or_else(Uuid::nil))
+        .bind(collection_id)
+        .fetch_all(cxn)
+        .await
+}
+
+/// Retrieves the [`CollectionCandidate`]s of the articles with the given slugs. Slugs that do not
+/// identify an article are ignored.
+pub async fn query_collection_candidates(
+    cxn: &mut PgConnection,
+    slugs: &[String],
+) -> Result<Vec<CollectionCandidate>, sqlx::Error> {
+    sqlx::query_as(GET_COLLECTION_CANDIDATES_QUERY)
+        .bind(slugs)
+        .fetch_all(cxn)
+        .await
+}
+
+/// Deletes a [`Collection`] given the identifier. The articles of the collection are not deleted.
+pub async fn delete_collection_by_id(
+    cxn: &mut PgConnection,
+    collection_id: &Uuid,
+) -> Result<(), sqlx::Error> {
+    let _ = sqlx::query(DELETE_COLLECTION_ARTICLES_QUERY)
+        .bind(collection_id)
+        .execute(&mut *cxn)
+        .await?;
+
+    let _ = sqlx::query(DELETE_COLLECTION_QUERY)
+        .bind(collection_id)
+        .execute(&mut *cxn)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+/// SQL query used to create the feed token of a user, replacing any existing token so that the
+/// previous URL of the private feed of the user stops working.
+const UPSERT_FEED_TOKEN_QUERY: &str = r#"
+    INSERT INTO
+        feed_tokens (user_id, token_hash)
+    VALUES
+        ($1, $2)
+    ON CONFLICT (user_id) DO UPDATE SET token_hash = EXCLUDED.token_hash, created = NOW()
+    RETURNING *"#;
+
+/// SQL query used to fetch the feed token of a user by the id of the user.
+const GET_FEED_TOKEN_BY_USER_ID_QUERY: &str = "SELECT * FROM feed_tokens WHERE user_id = $1";
+
+/// SQL query used to fetch a feed token by the hash of the token.
+const GET_FEED_TOKEN_QUERY: &str = "SELECT * FROM feed_tokens WHERE token_hash = $1";
+
+/// SQL query used to delete the feed token of a user.
+const DELETE_FEED_TOKEN_QUERY: &str = "DELETE FROM feed_tokens WHERE user_id = $1";
+
+/// The [`FeedToken`] struct is used to let the `sqlx` library easily map a row from the
+/// `feed_tokens` table in the database to a struct value. It is a one-to-one mapping from the
+/// database table.
+#[derive(Debug, FromRow)]
+pub struct FeedToken {
+    /// Id of the user whose private feed the token grants access to.
+    pub user_id: Uuid,
+    /// Hex encoded SHA-256 hash of the secret token contained in the URL of the private feed.
+    pub token_hash: String,
+    /// Time the token was created.
+    pub created: DateTime<Utc>,
+}
+
+/// Sets the hash of the feed token of the user with the given id, replacing any existing token, and
+/// returns the resulting [`FeedToken`].
+pub async fn upsert_feed_token(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+    token_hash: &str,
+) -> Result<FeedToken, sqlx::Error> {
+    sqlx::query_as(UPSERT_FEED_TOKEN_QUERY)
+        .bind(user_id)
+        .bind(token_hash)
+        .fetch_one(cxn)
+        .await
+}
+
+/// Retrieves the [`FeedToken`] of the user with the given id if one exists.
+pub async fn query_feed_token_by_user_id(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+) -> Result<Option<FeedToken>, sqlx::Error> {
+    sqlx::query_as(GET_FEED_TOKEN_BY_USER_ID_QUERY)
+        .bind(user_id)
+        .fetch_optional(cxn)
+        .await
+}
+
+/// Retrieves the [`FeedToken`] whose token has the given hash if one exists.
+pub async fn query_feed_token(
+    cxn: &mut PgConnection,
+    token_hash: &str,
+) -> Result<Option<FeedToken>, sqlx::Error> {
+    sqlx::query_as(GET_FEED_TOKEN_QUERY)
+        .bind(token_hash)
+        .fetch_optional(cxn)
+        .await
+}
+
+/// Deletes the feed token of the user with the given id. Returns `true` if the user had a token.
+pub async fn delete_feed_token(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query(DELETE_FEED_TOKEN_QUERY)
+        .bind(user_id)
+        .execute(cxn)
+        .await
+        .map(|r| r.rows_affected() > 0)
+}
@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+/// SQL query used to create a new invite.
+const CREATE_INVITE_QUERY: &str =
+    "INSERT INTO invites (code, user_id, max_uses, expires) VALUES ($1, $2, $3, $4) RETURNING *";
+
+/// SQL query used to fetch all invites ordered by the most recently created first.
+const LIST_INVITES_QUERY: &str = "SELECT * FROM invites ORDER BY created DESC";
+
+/// SQL query used to consume a use of an invite as long as it has remaining uses and has not yet
+/// expired. The row is locked by the update so concurrent redemptions cannot exceed the maximum.
+const REDEEM_INVITE_QUERY: &str = r#"
+    UPDATE
+        invites
+    SET
+        uses = uses + 1
+    WHERE
+        code = $1 AND uses < max_uses AND (expires IS NULL OR expires > NOW())
+    RETURNING *"#;
+
+/// SQL query used to record that a user registered using an invite.
+const CREATE_INVITE_REDEMPTION_QUERY: &str =
+    "INSERT INTO invite_redemptions (invite_id, user_id) VALUES ($1, $2)";
+
+/// The [`Invite`] struct is used to let the `sqlx` library easily map a row from the `invites`
+/// table in the database to a struct value. It is a one-to-one mapping from the database table.
+#[derive(Debug, FromRow)]
+pub struct Invite {
+    /// Id of the invite.
+    pub id: Uuid,
+    /// Code that must be provided to register using the invite.
+    pub code: String,
+    /// Id of the user who created the invite.
+    pub user_id: Uuid,
+    /// Maximum number of times the invite can be redeemed.
+    pub max_uses: i32,
+    /// Number of times the invite has been redeemed.
+    pub uses: i32,
+    /// Time after which the invite can no longer be redeemed, if any.
+    pub expires: Option<DateTime<Utc>>,
+    /// Time the invite was created.
+    pub created: DateTime<Utc>,
+}
+
+/// The [`CreateInvite`] struct contains the data required to create an invite in the database.
+#[derive(Debug)]
+pub struct CreateInvite<'a> {
+    /// Code that must be provided to register using the invite.
+    pub code: &'a String,
+    /// Maximum number of times the invite can be redeemed.
+    pub max_uses: i32,
+    /// Time after which the invite can no longer be redeemed, if any.
+    pub expires: Option<DateTime<Utc>>,
+}
+
+/// Creates a new [`Invite`] row in the database using the details contained in the given
+/// [`CreateInvite`].
+pub async fn create_invite(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+    invite: CreateInvite<'_>,
+) -> Result<Invite, sqlx::Error> {
+    sqlx::query_as(CREATE_INVITE_QUERY)
+        .bind(invite.code)
+        .bind(user_id)
+        .bind(invite.max_uses)
+        .bind(invite.expires)
+        .fetch_one(cxn)
+        .await
+}
+
+/// Retrieves all of the [`Invite`]s that exist in the database.
+pub async fn query_invites(cxn: &mut PgConnection) -> Result<Vec<Invite>, sqlx::Error> {
+    sqlx::query_as(LIST_INVITES_QUERY).fetch_all(cxn).await
+}
+
+/// Redeems the [`Invite`] identified by the code on behalf of the user with the given id. Returns
+/// the redeemed [`Invite`] if the code exists, has remaining uses and has not expired, otherwise
+/// [`None`] is returned and nothing is changed.
+pub async fn redeem_invite(
+    cxn: &mut PgConnection,
+    code: &str,
+    user_id: &Uuid,
+) -> Result<Option<Invite>, sqlx::Error> {
+    let invite: Option<Invite> = sqlx::query_as(REDEEM_INVITE_QUERY)
+        .bind(code)
+        .fetch_optional(&mut *cxn)
+        .await?;
+
+    if let Some(invite) = &invite {
+        let _ = sqlx::query(CREATE_INVITE_REDEMPTION_QUERY)
+            .bind(invite.id)
+            .bind(user_id)
+            .execute(&mut *cxn)
+            .await?;
+    }
+
+    Ok(invite)
+}
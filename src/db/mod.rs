@@ -1,4 +1,178 @@
+/// Expands to the condition that the user bound to `$1` is one of the authors of the article with
+/// the given alias, which is the case for its owner and the editors who have accepted the invitation
+/// to co-author it. This is the same check as [`article::is_article_editor`] so that the queries
+/// agree with it about who can see drafts and scheduled articles.
+macro_rules! article_editor {
+    ($a:literal) => {
+        concat!(
+            "(",
+            $a,
+            ".user_id = $1 OR EXISTS(SELECT 1 FROM article_authors AS aa WHERE aa.article_id = ",
+            $a,
+            ".id AND aa.user_id = $1 AND aa.status = 'accepted'))"
+        )
+    };
+}
+
+/// Expands to the lateral join that selects the navigation of the series that the article `a`
+/// belongs to, if any, as the `series_*` columns of `s`. Only the published articles of the series,
+/// those co-authored by the user bound to `$1` and the article itself are counted and navigated to,
+/// leaving out those in the trash.
+macro_rules! article_series_join {
+    () => {
+        concat!(
+            r#"
+        LEFT JOIN LATERAL (
+            SELECT
+                sw.series_slug,
+                sw.series_title,
+                sw.series_position,
+                sw.series_length,
+                sw.series_prev_slug,
+                sw.series_prev_title,
+                sw.series_next_slug,
+                sw.series_next_title
+            FROM (
+                SELECT
+                    sa.id,
+                    c.slug AS series_slug,
+                    c.title AS series_title,
+                    ROW_NUMBER() OVER w AS series_position,
+                    COUNT(*) OVER () AS series_length,
+                    LAG(sa.slug) OVER w AS series_prev_slug,
+                    LAG(sa.title) OVER w AS series_prev_title,
+                    LEAD(sa.slug) OVER w AS series_next_slug,
+                    LEAD(sa.title) OVER w AS series_next_title
+                FROM
+                    collection_articles AS ca
+                    INNER JOIN collections AS c ON ca.collection_id = c.id
+                    INNER JOIN collection_articles AS sca ON sca.collection_id = c.id
+                    INNER JOIN articles AS sa ON sca.article_id = sa.id
+                WHERE
+                    ca.article_id = a.id AND sa.deleted_at IS NULL AND (sa.status = 'published' OR "#,
+            article_editor!("sa"),
+            r#" OR sa.id = a.id)
+                WINDOW
+                    w AS (ORDER BY sca.position ASC)
+            ) AS sw
+            WHERE
+                sw.id = a.id
+        ) AS s ON true"#
+        )
+    };
+}
+
+/// Expands to the lateral join that selects the profiles of the editors who have accepted an
+/// invitation to co-author the article `a` as the `coauthors` JSON array of `co`, using the user
+/// bound to `$1` to determine whether each editor is followed.
+macro_rules! article_coauthors_join {
+    () => {
+        r#"
+        LEFT JOIN LATERAL (
+            SELECT
+                COALESCE(
+                    JSONB_AGG(
+                        JSONB_BUILD_OBJECT(
+                            'id', cu.id,
+                            'username', cu.name,
+                            'bio', cu.bio,
+                            'image', cu.image,
+                            'following', EXISTS(SELECT 1 FROM user_follows AS cf WHERE cf.user_id = cu.id AND cf.follower_id = $1)
+                        )
+                        ORDER BY aa.responded ASC, cu.name ASC
+                    ),
+                    '[]'::jsonb
+                ) AS coauthors
+            FROM
+                article_authors AS aa INNER JOIN users AS cu ON aa.user_id = cu.id
+            WHERE
+                aa.article_id = a.id AND aa.role = 'editor' AND aa.status = 'accepted'
+        ) AS co ON true"#
+    };
+}
+
 pub mod article;
+pub mod bookmark;
+pub mod bulk;
+pub mod coauthor;
+pub mod collection;
+pub mod feed;
+pub mod invite;
 pub mod outbox;
+pub mod revision;
+pub mod sitemap;
 pub mod tag;
+pub mod trash;
 pub mod user;
+pub mod view;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// The [`Keyset`] struct identifies the position of a row in a listing that is ordered by the time
+/// the row was created, using the id of the row to break ties between rows created at the same
+/// time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keyset {
+    /// Time the row was created.
+    pub created: DateTime<Utc>,
+    /// Id of the row.
+    pub id: Uuid,
+}
+
+/// Enumerates the ways in which a page of results can be selected from a listing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Page {
+    /// Selects the page starting at an offset into the entire set of results.
+    Offset { limit: i32, offset: i32 },
+    /// Selects the page of results that directly follow the row at the [`Keyset`] position.
+    After { limit: i32, key: Keyset },
+    /// Selects the page of results that directly precede the row at the [`Keyset`] position.
+    Before { limit: i32, key: Keyset },
+}
+
+impl Page {
+    /// Returns the maximum number of results in the page.
+    pub fn limit(&self) -> i32 {
+        match self {
+            Page::Offset { limit, .. } | Page::After { limit, .. } | Page::Before { limit, .. } => {
+                *limit
+            }
+        }
+    }
+
+    /// Returns the offset into the entire set of results, which is always zero when paging by
+    /// position.
+    pub fn offset(&self) -> i32 {
+        match self {
+            Page::Offset { offset, .. } => *offset,
+            _ => 0,
+        }
+    }
+
+    /// Returns the position that the results must follow, if any.
+    pub fn after(&self) -> Option<Keyset> {
+        match self {
+            Page::After { key, .. } => Some(*key),
+            _ => None,
+        }
+    }
+
+    /// Returns the position that the results must precede, if any.
+    pub fn before(&self) -> Option<Keyset> {
+        match self {
+            Page::Before { key, .. } => Some(*key),
+            _ => None,
+        }
+    }
+}
+
+/// Restores the order of the rows of a page that precedes a position, which are selected in the
+/// reverse order so that the rows closest to the position are the ones returned.
+fn in_page_order<T>(mut rows: Vec<T>, page: &Page) -> Vec<T> {
+    if let Page::Before { .. } = page {
+        rows.reverse();
+    }
+
+    rows
+}
@@ -0,0 +1,159 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+/// SQL query used to create a new revision of an article. The revision number is one greater than
+/// the latest revision of the article. Callers are expected to have already updated the article row
+/// in the same transaction, which serializes concurrent revisions of the same article.
+const CREATE_REVISION_QUERY: &str = r#"
+    INSERT INTO
+        article_revisions (article_id, user_id, revision, title, description, body)
+    SELECT
+        $1, $2, COALESCE(MAX(ar.revision), 0) + 1, $3, $4, $5
+    FROM
+        article_revisions AS ar
+    WHERE
+        ar.article_id = $1
+    RETURNING *"#;
+
+/// SQL query used to fetch all of the revisions of an article ordered by the latest first.
+const LIST_REVISION_VIEWS_QUERY: &str = r#"
+    SELECT
+        ar.*,
+        u.id AS editor_id,
+        u.name AS editor_name,
+        u.bio AS editor_bio,
+        u.image AS editor_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS editor_followed
+    FROM
+        article_revisions AS ar INNER JOIN users AS u ON ar.user_id = u.id
+    WHERE
+        ar.article_id = $2
+    ORDER BY
+        ar.revision DESC"#;
+
+/// SQL query used to fetch a single revision of an article by number.
+const GET_REVISION_VIEW_QUERY: &str = r#"
+    SELECT
+        ar.*,
+        u.id AS editor_id,
+        u.name AS editor_name,
+        u.bio AS editor_bio,
+        u.image AS editor_image,
+        (SELECT COUNT(*) FROM user_follows AS uf WHERE uf.user_id = u.id AND uf.follower_id = $1)::int::bool AS editor_followed
+    FROM
+        article_revisions AS ar INNER JOIN users AS u ON ar.user_id = u.id
+    WHERE
+        ar.article_id = $2 AND ar.revision = $3"#;
+
+/// The [`Revision`] struct is used to let the `sqlx` library easily map a row from the
+/// `article_revisions` table in the database to a struct value. It is a one-to-one mapping from the
+/// database table.
+#[derive(Debug, FromRow)]
+pub struct Revision {
+    /// Id of the revision.
+    pub id: Uuid,
+    /// Id of the article the revision belongs to.
+    pub article_id: Uuid,
+    /// Id of the user who made the revision.
+    pub user_id: Uuid,
+    /// Number of the revision, starting at one for the content the article was created with.
+    pub revision: i32,
+    /// Title of the article at the revision.
+    pub title: String,
+    /// Description of the article at the revision.
+    pub description: String,
+    /// Body of the article at the revision.
+    pub body: String,
+    /// Time the revision was created.
+    pub created: DateTime<Utc>,
+}
+
+/// The [`RevisionView`] struct is used to let the `sqlx` library easily map a view of the
+/// `article_revisions` table and the user who made the revision to a struct value.
+#[derive(Debug, FromRow)]
+pub struct RevisionView {
+    /// Number of the revision, starting at one for the content the article was created with.
+    pub revision: i32,
+    /// Title of the article at the revision.
+    pub title: String,
+    /// Description of the article at the revision.
+    pub description: String,
+    /// Body of the article at the revision.
+    pub body: String,
+    /// Time the revision was created.
+    pub created: DateTime<Utc>,
+    /// Id of the user who made the revision.
+    pub editor_id: Uuid,
+    /// Username of the user who made the revision.
+    pub editor_name: String,
+    /// Bio of the user who made the revision.
+    pub editor_bio: String,
+    /// URL to the image of the user who made the revision.
+    pub editor_image: Option<String>,
+    /// Flag indicating whether or not the editor is being followed by the currently authenticated
+    /// user. If no user is curently logged in, then the value will be set to `false`.
+    pub editor_followed: bool,
+}
+
+/// The [`CreateRevision`] struct contains the data required to create a revision of an article in
+/// the database.
+#[derive(Debug)]
+pub struct CreateRevision<'a> {
+    /// Id of the user who made the revision.
+    pub user_id: &'a Uuid,
+    /// Title of the article.
+    pub title: &'a str,
+    /// Description of the article.
+    pub description: &'a str,
+    /// Body of the article.
+    pub body: &'a str,
+}
+
+/// Creates a new [`Revision`] row in the database for the article with the given id using the
+/// details contained in the given [`CreateRevision`].
+pub async fn create_revision(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    revision: CreateRevision<'_>,
+) -> Result<Revision, sqlx::Error> {
+    sqlx::query_as(CREATE_REVISION_QUERY)
+        .bind(article_id)
+        .bind(revision.user_id)
+        .bind(revision.title)
+        .bind(revision.description)
+        .bind(revision.body)
+        .fetch_one(cxn)
+        .await
+}
+
+/// Retrieves all of the [`RevisionView`]s of the article with the given id using the identifier of
+/// the authenticated user as the user context to determine whether the editor is followed.
+pub async fn query_revisions(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    user_ctx: &Uuid,
+) -> Result<Vec<RevisionView>, sqlx::Error> {
+    sqlx::query_as(LIST_REVISION_VIEWS_QUERY)
+        .bind(user_ctx)
+        .bind(article_id)
+        .fetch_all(cxn)
+        .await
+}
+
+/// Retrieves the [`RevisionView`] of the article with the given id and revision number, if it
+/// exists, using the identifier of the authenticated user as the user context to determine whether
+/// the editor is followed.
+pub async fn query_revision(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    revision: i32,
+    user_ctx: &Uuid,
+) -> Result<Option<RevisionView>, sqlx::Error> {
+    sqlx::query_as(GET_REVISION_VIEW_QUERY)
+        .bind(user_ctx)
+        .bind(article_id)
+        .bind(revision)
+        .fetch_optional(cxn)
+        .await
+}
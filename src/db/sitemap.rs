@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use sqlx::{FromRow, PgConnection};
+
+/// SQL query used to fetch the pages of the sitemap along with the time that the most recently
+/// modified entry in each page was modified. The entries of each section are numbered in the same
+/// order as they are listed by the queries of the sections and split into pages of the given size.
+const LIST_SITEMAP_PAGES_QUERY: &str = r#"
+    WITH entries AS (
+        SELECT
+            'articles' AS section,
+            ROW_NUMBER() OVER (ORDER BY a.created ASC, a.id ASC) - 1 AS position,
+            COALESCE(a.updated, a.created) AS modified
+        FROM
+            articles AS a
+        WHERE
+            a.status = 'published' AND a.deleted_at IS NULL
+
+        UNION ALL
+
+        SELECT
+            'profiles' AS section,
+            ROW_NUMBER() OVER (ORDER BY u.name ASC) - 1 AS position,
+            MAX(COALESCE(a.updated, a.created)) AS modified
+        FROM
+            users AS u INNER JOIN articles AS a ON u.id = a.user_id
+        WHERE
+            a.status = 'published' AND a.deleted_at IS NULL
+        GROUP BY
+            u.id
+
+        UNION ALL
+
+        SELECT
+            'tags' AS section,
+            ROW_NUMBER() OVER (ORDER BY t.name ASC) - 1 AS position,
+            MAX(COALESCE(a.updated, a.created)) AS modified
+        FROM
+            tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id INNER JOIN articles AS a ON at.article_id = a.id
+        WHERE
+            a.status = 'published' AND a.deleted_at IS NULL
+        GROUP BY
+            t.id
+    )
+    SELECT
+        section,
+        position / $1 AS page,
+        MAX(modified) AS modified
+    FROM
+        entries
+    GROUP BY
+        section, page
+    ORDER BY
+        section ASC, page ASC"#;
+
+/// SQL query used to fetch a page of the published articles in the sitemap, oldest first.
+const LIST_SITEMAP_ARTICLES_QUERY: &str = r#"
+    SELECT
+        a.slug AS name,
+        COALESCE(a.updated, a.created) AS modified
+    FROM
+        articles AS a
+    WHERE
+        a.status = 'published' AND a.deleted_at IS NULL
+    ORDER BY
+        a.created ASC, a.id ASC
+    LIMIT
+        $1
+    OFFSET
+        $2"#;
+
+/// SQL query used to fetch a page of the profiles in the sitemap, which are the users who have
+/// authored at least one published article, in order of username.
+const LIST_SITEMAP_PROFILES_QUERY: &str = r#"
+    SELECT
+        u.name,
+        MAX(COALESCE(a.updated, a.created)) AS modified
+    FROM
+        users AS u INNER JOIN articles AS a ON u.id = a.user_id
+    WHERE
+        a.status = 'published' AND a.deleted_at IS NULL
+    GROUP BY
+        u.id
+    ORDER BY
+        u.name ASC
+    LIMIT
+        $1
+    OFFSET
+        $2"#;
+
+/// SQL query used to fetch a page of the tags in the sitemap, which are the tags of at least one
+/// published article, in order of name.
+const LIST_SITEMAP_TAGS_QUERY: &str = r#"
+    SELECT
+        t.name,
+        MAX(COALESCE(a.updated, a.created)) AS modified
+    FROM
+        tags AS t INNER JOIN article_tags AS at ON t.id = at.tag_id INNER JOIN articles AS a ON at.article_id = a.id
+    WHERE
+        a.status = 'published' AND a.deleted_at IS NULL
+    GROUP BY
+        t.id
+    ORDER BY
+        t.name ASC
+    LIMIT
+        $1
+    OFFSET
+        $2"#;
+
+/// Enumerates the sections of the sitemap, each of which is split into one or more pages.
+#[derive(Clone, Copy, Debug, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum SitemapSection {
+    /// Published articles.
+    Articles,
+    /// Profiles of the users who have authored published articles.
+    Profiles,
+    /// Tags of published articles.
+    Tags,
+}
+
+/// The [`SitemapPage`] struct is used to let the `sqlx` library easily map a page of the sitemap to
+/// a struct value.
+#[derive(Clone, Debug, FromRow)]
+pub struct SitemapPage {
+    /// Section of the sitemap that the page belongs to.
+    pub section: SitemapSection,
+    /// Number of the page within its section, starting at zero.
+    pub page: i64,
+    /// Time that the most recently modified entry in the page was modified.
+    pub modified: DateTime<Utc>,
+}
+
+/// The [`SitemapEntry`] struct is used to let the `sqlx` library easily map an entry in a page of
+/// the sitemap to a struct value.
+#[derive(Debug, FromRow)]
+pub struct SitemapEntry {
+    /// Slug of the article, username of the profile or name of the tag.
+    pub name: String,
+    /// Time that the entry, or the most recently modified article that it represents, was modified.
+    pub modified: DateTime<Utc>,
+}
+
+/// Retrieves all of the [`SitemapPage`]s when each section is split into pages of the given size.
+pub async fn query_sitemap_pages(
+    cxn: &mut PgConnection,
+    page_size: i64,
+) -> Result<Vec<SitemapPage>, sqlx::Error> {
+    sqlx::query_as(LIST_SITEMAP_PAGES_QUERY)
+        .bind(page_size)
+        .fetch_all(cxn)
+        .await
+}
+
+/// Streams the [`SitemapEntry`]s in the page of the section of the sitemap with the given number
+/// when the section is split into pages of the given size. The entries are streamed as they are
+/// read from the database so that a page never has to be held in memory in its entirety.
+pub fn stream_sitemap_entries(
+    cxn: &mut PgConnection,
+    section: SitemapSection,
+    page: i64,
+    page_size: i64,
+) -> BoxStream<'_, Result<SitemapEntry, sqlx::Error>> {
+    let query = match section {
+        SitemapSection::Articles => LIST_SITEMAP_ARTICLES_QUERY,
+        SitemapSection::Profiles => LIST_SITEMAP_PROFILES_QUERY,
+        SitemapSection::Tags => LIST_SITEMAP_TAGS_QUERY,
+    };
+
+    sqlx::query_as(query)
+        .bind(page_size)
+        .bind(page * page_size)
+        .fetch(cxn)
+}
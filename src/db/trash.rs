@@ -0,0 +1,262 @@
+use crate::db::{article::ArticleStatus, in_page_order, Page};
+
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+/// SQL query used to fetch a single page of the articles of a user that are in the trash, most
+/// recently deleted first. The page can be selected by offset or by the time an article was deleted
+/// and its id, in which case the rows of a page that precedes a position are selected in ascending
+/// order and must be reversed by the caller.
+const GET_TRASHED_ARTICLES_PAGE_QUERY: &str = r#"
+    SELECT
+        a.id,
+        a.slug,
+        a.title,
+        a.description,
+        a.status,
+        a.created,
+        a.deleted_at
+    FROM
+        articles AS a
+    WHERE
+        a.user_id = $1 AND a.deleted_at IS NOT NULL
+
+        AND
+
+        ($4::timestamptz IS NULL OR (a.deleted_at, a.id) < ($4, $5::uuid))
+
+        AND
+
+        ($6::timestamptz IS NULL OR (a.deleted_at, a.id) > ($6, $7::uuid))
+    ORDER BY
+        CASE WHEN $6::timestamptz IS NULL THEN NULL ELSE a.deleted_at END ASC,
+        CASE WHEN $6::timestamptz IS NULL THEN NULL ELSE a.id END ASC,
+        a.deleted_at DESC,
+        a.id DESC
+    LIMIT
+        $2
+    OFFSET
+        $3"#;
+
+/// SQL query used to get a total count of the articles of a user that are in the trash.
+const COUNT_TRASHED_ARTICLES_QUERY: &str =
+    "SELECT COUNT(id) FROM articles WHERE user_id = $1 AND deleted_at IS NOT NULL";
+
+/// SQL query used to fetch a single page of the comments of a user that are in the trash, most
+/// recently deleted first, along with the article each was made on. Comments on articles that are
+/// themselves in the trash are left out since they cannot be restored until the article is. The
+/// page is selected in the same way as for [`GET_TRASHED_ARTICLES_PAGE_QUERY`].
+const GET_TRASHED_COMMENTS_PAGE_QUERY: &str = r#"
+    SELECT
+        ac.id,
+        ac.body,
+        ac.created,
+        ac.deleted_at,
+        a.slug AS article_slug,
+        a.title AS article_title
+    FROM
+        article_comments AS ac INNER JOIN articles AS a ON ac.article_id = a.id
+    WHERE
+        ac.user_id = $1 AND ac.deleted_at IS NOT NULL AND a.deleted_at IS NULL
+
+        AND
+
+        ($4::timestamptz IS NULL OR (ac.deleted_at, ac.id) < ($4, $5::uuid))
+
+        AND
+
+        ($6::timestamptz IS NULL OR (ac.deleted_at, ac.id) > ($6, $7::uuid))
+    ORDER BY
+        CASE WHEN $6::timestamptz IS NULL THEN NULL ELSE ac.deleted_at END ASC,
+        CASE WHEN $6::timestamptz IS NULL THEN NULL ELSE ac.id END ASC,
+        ac.deleted_at DESC,
+        ac.id DESC
+    LIMIT
+        $2
+    OFFSET
+        $3"#;
+
+/// SQL query used to get a total count of the comments of a user that are in the trash, leaving
+/// out those on articles that are in the trash.
+const COUNT_TRASHED_COMMENTS_QUERY: &str = r#"
+    SELECT
+        COUNT(ac.id)
+    FROM
+        article_comments AS ac INNER JOIN articles AS a ON ac.article_id = a.id
+    WHERE
+        ac.user_id = $1 AND ac.deleted_at IS NOT NULL AND a.deleted_at IS NULL"#;
+
+/// SQL query used to lock a batch of the articles that have been in the trash for longer than the
+/// retention period, given in seconds, so that they can be purged. Rows that are locked by another
+/// instance of the application purging the same batch are skipped.
+const LOCK_EXPIRED_ARTICLES_QUERY: &str = r#"
+    SELECT
+        id
+    FROM
+        articles
+    WHERE
+        deleted_at <= NOW() - $1 * INTERVAL '1 second'
+    ORDER BY
+        deleted_at ASC
+    LIMIT
+        $2
+    FOR UPDATE SKIP LOCKED"#;
+
+/// SQL query used to delete a batch of the comments that have been in the trash for longer than
+/// the retention period, given in seconds. Rows that are locked by another instance of the
+/// application purging the same batch are skipped.
+const PURGE_EXPIRED_COMMENTS_QUERY: &str = r#"
+    WITH expired_comments AS (
+        SELECT
+            id
+        FROM
+            article_comments
+        WHERE
+            deleted_at <= NOW() - $1 * INTERVAL '1 second'
+        ORDER BY
+            deleted_at ASC
+        LIMIT
+            $2
+        FOR UPDATE SKIP LOCKED
+    )
+    DELETE FROM
+        article_comments AS ac
+    USING
+        expired_comments AS ec
+    WHERE
+        ac.id = ec.id
+    RETURNING
+        ac.article_id"#;
+
+/// The [`TrashedArticleView`] struct is used to let the `sqlx` library easily map the summary of an
+/// article in the trash to a struct value.
+#[derive(Debug, FromRow)]
+pub struct TrashedArticleView {
+    /// Id of the article.
+    pub id: Uuid,
+    /// Slugified title of the article.
+    pub slug: String,
+    /// Title of the article.
+    pub title: String,
+    /// Description of the article.
+    pub description: String,
+    /// Publication status of the article when it was deleted.
+    pub status: ArticleStatus,
+    /// Time the article was created.
+    pub created: DateTime<Utc>,
+    /// Time the article was moved to the trash.
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// The [`TrashedCommentView`] struct is used to let the `sqlx` library easily map a comment in the
+/// trash and the article it was made on to a struct value.
+#[derive(Debug, FromRow)]
+pub struct TrashedCommentView {
+    /// Id of the comment.
+    pub id: Uuid,
+    /// Body text of the comment.
+    pub body: String,
+    /// Time at which the comment was originally created.
+    pub created: DateTime<Utc>,
+    /// Time the comment was moved to the trash.
+    pub deleted_at: DateTime<Utc>,
+    /// Slug of the article the comment was made on.
+    pub article_slug: String,
+    /// Title of the article the comment was made on.
+    pub article_title: String,
+}
+
+/// Retrieves a [`Vec`] of [`TrashedArticleView`]s that make up a page of the articles of the
+/// specified user that are in the trash.
+pub async fn query_trashed_articles(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+    page: &Page,
+) -> Result<Vec<TrashedArticleView>, sqlx::Error> {
+    let articles = sqlx::query_as(GET_TRASHED_ARTICLES_PAGE_QUERY)
+        .bind(user_id)
+        .bind(page.limit())
+        .bind(page.offset())
+        .bind(page.after().map(|k| k.created))
+        .bind(page.after().map(|k| k.id))
+        .bind(page.before().map(|k| k.created))
+        .bind(page.before().map(|k| k.id))
+        .fetch_all(&mut *cxn)
+        .await?;
+
+    Ok(in_page_order(articles, page))
+}
+
+/// Counts the total number of articles of a user that are in the trash.
+pub async fn count_trashed_articles(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(COUNT_TRASHED_ARTICLES_QUERY)
+        .bind(user_id)
+        .fetch_one(&mut *cxn)
+        .await
+}
+
+/// Retrieves a [`Vec`] of [`TrashedCommentView`]s that make up a page of the comments of the
+/// specified user that are in the trash.
+pub async fn query_trashed_comments(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+    page: &Page,
+) -> Result<Vec<TrashedCommentView>, sqlx::Error> {
+    let comments = sqlx::query_as(GET_TRASHED_COMMENTS_PAGE_QUERY)
+        .bind(user_id)
+        .bind(page.limit())
+        .bind(page.offset())
+        .bind(page.after().map(|k| k.created))
+        .bind(page.after().map(|k| k.id))
+        .bind(page.before().map(|k| k.created))
+        .bind(page.before().map(|k| k.id))
+        .fetch_all(&mut *cxn)
+        .await?;
+
+    Ok(in_page_order(comments, page))
+}
+
+/// Counts the total number of comments of a user that are in the trash.
+pub async fn count_trashed_comments(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(COUNT_TRASHED_COMMENTS_QUERY)
+        .bind(user_id)
+        .fetch_one(&mut *cxn)
+        .await
+}
+
+/// Locks a batch of at most `limit` articles that have been in the trash for longer than the
+/// retention period in seconds and returns their ids. The locks are held until the transaction
+/// ends so the articles should be purged within the same transaction.
+pub async fn lock_expired_articles(
+    cxn: &mut PgConnection,
+    retention: u64,
+    limit: i64,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar(LOCK_EXPIRED_ARTICLES_QUERY)
+        .bind(retention as i64)
+        .bind(limit)
+        .fetch_all(&mut *cxn)
+        .await
+}
+
+/// Deletes a batch of at most `limit` comments that have been in the trash for longer than the
+/// retention period in seconds and returns the id of the article that each deleted comment was made
+/// on.
+pub async fn purge_expired_comments(
+    cxn: &mut PgConnection,
+    retention: u64,
+    limit: i64,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar(PURGE_EXPIRED_COMMENTS_QUERY)
+        .bind(retention as i64)
+        .bind(limit)
+        .fetch_all(&mut *cxn)
+        .await
+}
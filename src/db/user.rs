@@ -7,6 +7,28 @@ use uuid::Uuid;
 const CREATE_USER_QUERY: &str =
     "INSERT INTO users (name, email, password) VALUES ($1, $2, $3) RETURNING *";
 
+/// SQL query used to create the settings for a new user with the default values.
+const CREATE_USER_SETTINGS_QUERY: &str =
+    "INSERT INTO user_settings (user_id) VALUES ($1) ON CONFLICT DO NOTHING";
+
+/// SQL query used to fetch the settings of a user by the id of the user.
+const GET_USER_SETTINGS_QUERY: &str = "SELECT * FROM user_settings WHERE user_id = $1";
+
+/// SQL query used to update the settings of a user by the id of the user, creating the settings row
+/// if the user does not have one.
+const UPDATE_USER_SETTINGS_QUERY: &str = r#"
+    INSERT INTO
+        user_settings (email_on_comment, email_on_follow, digest_frequency, show_favorites_publicly, default_article_visibility, user_id)
+    VALUES
+        ($1, $2, $3, $4, $5, $6)
+    ON CONFLICT (user_id) DO UPDATE SET
+        email_on_comment = EXCLUDED.email_on_comment,
+        email_on_follow = EXCLUDED.email_on_follow,
+        digest_frequency = EXCLUDED.digest_frequency,
+        show_favorites_publicly = EXCLUDED.show_favorites_publicly,
+        default_article_visibility = EXCLUDED.default_article_visibility
+    RETURNING *"#;
+
 /// SQL query used to fetch a user by id.
 const GET_USER_BY_ID_QUERY: &str = "SELECT * FROM users WHERE id = $1";
 
@@ -17,6 +39,54 @@ const GET_USER_BY_EMAIL_QUERY: &str = "SELECT * FROM users WHERE email = $1";
 const UPDATE_USER_BY_ID_QUERY: &str =
     "UPDATE users SET name = $1, email = $2, password = $3, image = $4, bio = $5 WHERE id = $6 RETURNING *";
 
+/// SQL query used to update the image of a user by id.
+const UPDATE_USER_IMAGE_BY_ID_QUERY: &str = "UPDATE users SET image = $1 WHERE id = $2 RETURNING *";
+
+/// SQL query used to resolve a username to the current username of a user. A user's current
+/// username always takes precedence over a previous username that is still within the grace period.
+const RESOLVE_USERNAME_QUERY: &str = r#"
+    SELECT
+        r.name
+    FROM (
+        SELECT u.name, 0 AS priority, u.created FROM users AS u WHERE u.name = $1
+
+        UNION ALL
+
+        SELECT
+            u.name,
+            1 AS priority,
+            uh.created
+        FROM
+            username_history AS uh INNER JOIN users AS u ON uh.user_id = u.id
+        WHERE
+            uh.name = $1 AND uh.created > NOW() - $2 * INTERVAL '1 second'
+    ) AS r
+    ORDER BY
+        r.priority ASC,
+        r.created DESC
+    LIMIT
+        1"#;
+
+/// SQL query used to determine whether a username was previously used by a different user and is
+/// still reserved for them.
+const GET_USERNAME_RESERVED_QUERY: &str = r#"
+    SELECT EXISTS(
+        SELECT
+            1
+        FROM
+            username_history AS uh
+        WHERE
+            uh.name = $1 AND uh.user_id <> $2 AND uh.created > NOW() - $3 * INTERVAL '1 second'
+    )"#;
+
+/// SQL query used to fetch the time a user last changed their username.
+const GET_LAST_USERNAME_CHANGE_QUERY: &str =
+    "SELECT MAX(created) FROM username_history WHERE user_id = $1";
+
+/// SQL query used to record a previous username of a user.
+const CREATE_USERNAME_HISTORY_QUERY: &str =
+    "INSERT INTO username_history (user_id, name) VALUES ($1, $2)";
+
 /// SQL query used to fetch a profile by the name of the user.
 const GET_PROFILE_BY_USERNAME_QUERY: &str = r#"
     SELECT
@@ -51,6 +121,75 @@ const INSERT_FOLLOW_QUERY: &str =
 const DELETE_FOLLOW_QUERY: &str =
     "DELETE FROM user_follows AS uf WHERE uf.user_id = (SELECT u.id FROM users AS u WHERE u.name = $1) AND uf.follower_id = $2";
 
+/// SQL query which allows a user to block a profile. A user cannot block themselves and blocking a
+/// profile that is already blocked has no effect.
+const INSERT_BLOCK_QUERY: &str =
+    "INSERT INTO user_blocks (user_id, blocker_id) SELECT u.id, $2 FROM users AS u WHERE u.name = $1 AND u.id <> $2 ON CONFLICT DO NOTHING";
+
+/// SQL query which allows a user to unblock a profile.
+const DELETE_BLOCK_QUERY: &str =
+    "DELETE FROM user_blocks AS ub WHERE ub.user_id = (SELECT u.id FROM users AS u WHERE u.name = $1) AND ub.blocker_id = $2";
+
+/// SQL query used to fetch profiles the user does not yet follow ranked by how many of the
+/// profiles followed by the user also follow them and then by the number of tags shared between the
+/// articles each of them has favorited. Profiles that the user has blocked or that have blocked the
+/// user are never suggested.
+const GET_FOLLOW_SUGGESTIONS_QUERY: &str = r#"
+    WITH followed AS (
+        SELECT uf.user_id FROM user_follows AS uf WHERE uf.follower_id = $1
+    ), friends_of_friends AS (
+        SELECT
+            uf.user_id,
+            COUNT(*) AS score
+        FROM
+            user_follows AS uf INNER JOIN followed AS f ON uf.follower_id = f.user_id
+        GROUP BY
+            uf.user_id
+    ), favorited_tags AS (
+        SELECT DISTINCT
+            at.tag_id
+        FROM
+            article_favs AS af INNER JOIN article_tags AS at ON af.article_id = at.article_id
+        WHERE
+            af.user_id = $1
+    ), shared_tags AS (
+        SELECT
+            af.user_id,
+            COUNT(DISTINCT at.tag_id) AS score
+        FROM
+            article_favs AS af INNER JOIN article_tags AS at ON af.article_id = at.article_id INNER JOIN favorited_tags AS ft ON at.tag_id = ft.tag_id
+        GROUP BY
+            af.user_id
+    )
+    SELECT
+        u.id,
+        u.name,
+        u.bio,
+        u.image,
+        false AS following
+    FROM
+        users AS u LEFT JOIN friends_of_friends AS fof ON u.id = fof.user_id LEFT JOIN shared_tags AS st ON u.id = st.user_id
+    WHERE
+        u.id <> $1
+
+        AND
+
+        NOT EXISTS(SELECT 1 FROM followed AS f WHERE f.user_id = u.id)
+
+        AND
+
+        NOT EXISTS(SELECT 1 FROM user_blocks AS ub WHERE (ub.user_id = u.id AND ub.blocker_id = $1) OR (ub.user_id = $1 AND ub.blocker_id = u.id))
+
+        AND
+
+        (fof.score IS NOT NULL OR st.score IS NOT NULL)
+    ORDER BY
+        COALESCE(fof.score, 0) DESC,
+        COALESCE(st.score, 0) DESC,
+        u.name ASC
+    LIMIT
+        $2"#;
+
 /// The [`User`] struct is used to let the `sqlx` library easily map a row from the `users` table
 /// in the database to a struct value.
 #[derive(Debug, FromRow)]
@@ -67,6 +206,8 @@ pub struct User {
     pub bio: String,
     /// URL to the image of the user.
     pub image: Option<String>,
+    /// Flag indicating whether the user is allowed to perform administrative actions.
+    pub admin: bool,
     /// Time the user was created.
     #[allow(dead_code)]
     pub created: DateTime<Utc>,
@@ -75,6 +216,88 @@ pub struct User {
     pub updated: Option<DateTime<Utc>>,
 }
 
+/// Enumerates how often a user would like to receive an email digest of activity.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum DigestFrequency {
+    /// No digest emails are sent.
+    Never,
+    /// A digest email is sent once a day.
+    Daily,
+    /// A digest email is sent once a week.
+    Weekly,
+}
+
+/// Enumerates the visibility that articles created by a user have when none is specified.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum ArticleVisibility {
+    /// Articles are listed publicly.
+    Public,
+    /// Articles are only accessible to those who know the slug.
+    Unlisted,
+}
+
+/// The [`UserSettings`] struct is used to let the `sqlx` library easily map a row from the
+/// `user_settings` table in the database to a struct value. A settings row is created along with
+/// each user, but users that lack one are treated as having the default settings.
+#[derive(Clone, Debug, Deserialize, Serialize, FromRow)]
+pub struct UserSettings {
+    /// Id of the user the settings belong to.
+    #[serde(skip)]
+    pub user_id: Uuid,
+    /// Flag indicating whether the user is emailed when their article is commented on.
+    #[serde(rename = "emailOnComment")]
+    pub email_on_comment: bool,
+    /// Flag indicating whether the user is emailed when another user follows them.
+    #[serde(rename = "emailOnFollow")]
+    pub email_on_follow: bool,
+    /// How often the user receives an email digest of activity.
+    #[serde(rename = "digestFrequency")]
+    pub digest_frequency: DigestFrequency,
+    /// Flag indicating whether the articles favorited by the user are visible to others.
+    #[serde(rename = "showFavoritesPublicly")]
+    pub show_favorites_publicly: bool,
+    /// Visibility of articles created by the user when none is specified.
+    #[serde(rename = "defaultArticleVisibility")]
+    pub default_article_visibility: ArticleVisibility,
+}
+
+impl UserSettings {
+    /// Creates the [`UserSettings`] of a user that has no settings row, which match the defaults of
+    /// the columns of the `user_settings` table.
+    fn with_defaults(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            email_on_comment: true,
+            email_on_follow: true,
+            digest_frequency: DigestFrequency::Weekly,
+            show_favorites_publicly: true,
+            default_article_visibility: ArticleVisibility::Public,
+        }
+    }
+}
+
+/// The [`UpdateUserSettings`] struct contains the data to update the database row representing
+/// the settings of a user with.
+#[derive(Debug)]
+pub struct UpdateUserSettings {
+    /// Id of the user the settings belong to.
+    pub user_id: Uuid,
+    /// Flag indicating whether the user is emailed when their article is commented on.
+    pub email_on_comment: bool,
+    /// Flag indicating whether the user is emailed when another user follows them.
+    pub email_on_follow: bool,
+    /// How often the user receives an email digest of activity.
+    pub digest_frequency: DigestFrequency,
+    /// Flag indicating whether the articles favorited by the user are visible to others.
+    pub show_favorites_publicly: bool,
+    /// Visibility of articles created by the user when none is specified.
+    pub default_article_visibility: ArticleVisibility,
+}
+
 /// The [`CreateUser`] struct contains the data to used to create the database row representing a
 /// user.
 #[derive(Debug)]
@@ -107,7 +330,7 @@ pub struct UpdateUser<'a> {
 
 /// The [`Profile`] struct is used to let the `sqlx` library easily map the projection of a user
 /// profile to a struct value.
-#[derive(Debug, Deserialize, Serialize, FromRow)]
+#[derive(Clone, Debug, Deserialize, Serialize, FromRow)]
 pub struct Profile {
     /// Id of the user the profile represents.
     #[serde(skip_serializing)]
@@ -146,15 +369,55 @@ pub async fn query_user_by_email(
         .await
 }
 
-/// Creates a new [`User`] row in the database using the details contained in the given [`CreateUser`].
+/// Creates a new [`User`] row in the database using the details contained in the given [`CreateUser`]
+/// along with the [`UserSettings`] row for the user populated with the default values.
 pub async fn create_user(
     cxn: &mut PgConnection,
     data: CreateUser<'_>,
 ) -> Result<User, sqlx::Error> {
-    sqlx::query_as(CREATE_USER_QUERY)
+    let user: User = sqlx::query_as(CREATE_USER_QUERY)
         .bind(data.username)
         .bind(data.email)
         .bind(data.hashed_password)
+        .fetch_one(&mut *cxn)
+        .await?;
+
+    let _ = sqlx::query(CREATE_USER_SETTINGS_QUERY)
+        .bind(user.id)
+        .execute(&mut *cxn)
+        .await?;
+
+    Ok(user)
+}
+
+/// Retrieves the [`UserSettings`] from the database for the user with the given id. This allows
+/// any part of the application to take the preferences of a user into account, e.g. before sending
+/// a notification email. The default settings are returned if the user has no settings row.
+pub async fn query_user_settings(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+) -> Result<UserSettings, sqlx::Error> {
+    let settings: Option<UserSettings> = sqlx::query_as(GET_USER_SETTINGS_QUERY)
+        .bind(user_id)
+        .fetch_optional(cxn)
+        .await?;
+
+    Ok(settings.unwrap_or_else(|| UserSettings::with_defaults(*user_id)))
+}
+
+/// Updates the [`UserSettings`] row in the database using the details contained in the given
+/// [`UpdateUserSettings`], creating the row if the user does not have one.
+pub async fn update_user_settings(
+    cxn: &mut PgConnection,
+    data: UpdateUserSettings,
+) -> Result<UserSettings, sqlx::Error> {
+    sqlx::query_as(UPDATE_USER_SETTINGS_QUERY)
+        .bind(data.email_on_comment)
+        .bind(data.email_on_follow)
+        .bind(data.digest_frequency)
+        .bind(data.show_favorites_publicly)
+        .bind(data.default_article_visibility)
+        .bind(data.user_id)
         .fetch_one(cxn)
         .await
 }
@@ -175,6 +438,76 @@ pub async fn update_user(
         .await
 }
 
+/// Updates the image of a [`User`] row in the database identified by id. Returns the updated
+/// [`User`] if it exists.
+pub async fn update_user_image(
+    cxn: &mut PgConnection,
+    id: &Uuid,
+    image: &str,
+) -> Result<Option<User>, sqlx::Error> {
+    sqlx::query_as(UPDATE_USER_IMAGE_BY_ID_QUERY)
+        .bind(image)
+        .bind(id)
+        .fetch_optional(cxn)
+        .await
+}
+
+/// Resolves the given username to the current username of a user. The username resolves if it is
+/// the current username of a user or if it was the previous username of a user and was changed
+/// within the grace period specified in seconds.
+pub async fn resolve_username(
+    cxn: &mut PgConnection,
+    username: &str,
+    grace_period: u64,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar(RESOLVE_USERNAME_QUERY)
+        .bind(username)
+        .bind(grace_period as i64)
+        .fetch_optional(cxn)
+        .await
+}
+
+/// Determines whether the username was previously used by a user other than the one with the given
+/// id within the reservation period specified in seconds, in which case it cannot be taken.
+pub async fn is_username_reserved(
+    cxn: &mut PgConnection,
+    username: &str,
+    user_id: &Uuid,
+    reservation_period: u64,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(GET_USERNAME_RESERVED_QUERY)
+        .bind(username)
+        .bind(user_id)
+        .bind(reservation_period as i64)
+        .fetch_one(cxn)
+        .await
+}
+
+/// Retrieves the time at which the user with the given id last changed their username, if ever.
+pub async fn query_last_username_change(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar(GET_LAST_USERNAME_CHANGE_QUERY)
+        .bind(user_id)
+        .fetch_one(cxn)
+        .await
+}
+
+/// Inserts an entry into the table that tracks the previous usernames of a user.
+pub async fn add_username_history(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+    previous_username: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(CREATE_USERNAME_HISTORY_QUERY)
+        .bind(user_id)
+        .bind(previous_username)
+        .execute(cxn)
+        .await
+        .map(|_| ())
+}
+
 /// Retrieves a [`Profile`] from the database given the name of the user that the profile
 /// represents and the id of the authenticated user if available to determine the follower context.
 pub async fn query_profile_by_username(
@@ -222,17 +555,61 @@ pub async fn add_profile_follow(
     query_profile_by_username(cxn, username, Some(follower_id)).await
 }
 
-/// Deletes an entry from the table that tracks profile follows for a user.
+/// Deletes an entry from the table that tracks profile follows for a user. Returns whether the user
+/// was following the profile.
 pub async fn remove_profile_follow(
     cxn: &mut PgConnection,
     username: &str,
     follower_id: Uuid,
-) -> Result<Option<Profile>, sqlx::Error> {
-    let _ = sqlx::query(DELETE_FOLLOW_QUERY)
+) -> Result<bool, sqlx::Error> {
+    sqlx::query(DELETE_FOLLOW_QUERY)
         .bind(username)
         .bind(follower_id)
         .execute(&mut *cxn)
-        .await?;
+        .await
+        .map(|r| r.rows_affected() > 0)
+}
 
-    query_profile_by_username(cxn, username, Some(follower_id)).await
+/// Creates an entry in the table that tracks profile blocks for a user. Returns whether the profile
+/// was blocked by this call, i.e. it exists, is not the user and was not already blocked.
+pub async fn add_profile_block(
+    cxn: &mut PgConnection,
+    username: &str,
+    blocker_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query(INSERT_BLOCK_QUERY)
+        .bind(username)
+        .bind(blocker_id)
+        .execute(&mut *cxn)
+        .await
+        .map(|r| r.rows_affected() > 0)
+}
+
+/// Deletes an entry from the table that tracks profile blocks for a user. Returns whether the user
+/// had blocked the profile.
+pub async fn remove_profile_block(
+    cxn: &mut PgConnection,
+    username: &str,
+    blocker_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query(DELETE_BLOCK_QUERY)
+        .bind(username)
+        .bind(blocker_id)
+        .execute(&mut *cxn)
+        .await
+        .map(|r| r.rows_affected() > 0)
+}
+
+/// Retrieves a [`Vec`] of [`Profile`]s that the user is not yet following but may be interested
+/// in following based on their social graph and the tags of the articles they have favorited.
+pub async fn query_follow_suggestions(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+    limit: i64,
+) -> Result<Vec<Profile>, sqlx::Error> {
+    sqlx::query_as(GET_FOLLOW_SUGGESTIONS_QUERY)
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(cxn)
+        .await
 }
@@ -0,0 +1,105 @@
+use chrono::NaiveDate;
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+/// SQL query used to add aggregated view counts to the daily views and total views of articles. The
+/// counts are passed as parallel arrays of article ids, days and views. Counts for articles that have
+/// since been deleted are ignored.
+const ADD_ARTICLE_VIEWS_QUERY: &str = r#"
+    WITH counts AS (
+        SELECT
+            c.article_id,
+            c.day,
+            c.views
+        FROM
+            UNNEST($1::uuid[], $2::date[], $3::bigint[]) AS c(article_id, day, views)
+        WHERE
+            EXISTS(SELECT 1 FROM articles AS a WHERE a.id = c.article_id)
+    ),
+    daily AS (
+        INSERT INTO
+            article_daily_views (article_id, day, views)
+        SELECT
+            article_id, day, views
+        FROM
+            counts
+        ON CONFLICT (article_id, day) DO UPDATE SET views = article_daily_views.views + EXCLUDED.views
+    )
+    UPDATE
+        articles AS a
+    SET
+        views_count = a.views_count + t.views
+    FROM (
+        SELECT article_id, SUM(views)::bigint AS views FROM counts GROUP BY article_id
+    ) AS t
+    WHERE
+        a.id = t.article_id"#;
+
+/// SQL query used to fetch the number of views of an article on each day in a range of days,
+/// including the days on which the article was not viewed.
+const LIST_DAILY_VIEWS_QUERY: &str = r#"
+    SELECT
+        d.day::date AS day,
+        COALESCE(adv.views, 0) AS views
+    FROM
+        GENERATE_SERIES($2::date, $3::date, INTERVAL '1 day') AS d(day)
+        LEFT JOIN article_daily_views AS adv ON adv.article_id = $1 AND adv.day = d.day::date
+    ORDER BY
+        d.day ASC"#;
+
+/// The [`DailyViews`] struct is used to let the `sqlx` library easily map the number of views of an
+/// article on a single day to a struct value.
+#[derive(Debug, FromRow)]
+pub struct DailyViews {
+    /// Day in UTC.
+    pub day: NaiveDate,
+    /// Number of views of the article on the day.
+    pub views: i64,
+}
+
+/// The [`ViewCount`] struct contains the number of views of an article on a single day that were
+/// aggregated since the counts were last added to the database.
+#[derive(Debug)]
+pub struct ViewCount {
+    /// Id of the viewed article.
+    pub article_id: Uuid,
+    /// Day in UTC on which the article was viewed.
+    pub day: NaiveDate,
+    /// Number of views of the article on the day.
+    pub views: i64,
+}
+
+/// Adds the given [`ViewCount`]s to the daily views and total views of the articles. Returns the
+/// number of articles whose views were updated.
+pub async fn add_article_views(
+    cxn: &mut PgConnection,
+    counts: &[ViewCount],
+) -> Result<u64, sqlx::Error> {
+    let article_ids: Vec<Uuid> = counts.iter().map(|c| c.article_id).collect();
+    let days: Vec<NaiveDate> = counts.iter().map(|c| c.day).collect();
+    let views: Vec<i64> = counts.iter().map(|c| c.views).collect();
+
+    sqlx::query(ADD_ARTICLE_VIEWS_QUERY)
+        .bind(article_ids)
+        .bind(days)
+        .bind(views)
+        .execute(cxn)
+        .await
+        .map(|r| r.rows_affected())
+}
+
+/// Retrieves the [`DailyViews`] of the article with the given id for every day from `first` to
+/// `last` inclusive.
+pub async fn query_daily_views(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    first: NaiveDate,
+    last: NaiveDate,
+) -> Result<Vec<DailyViews>, sqlx::Error> {
+    sqlx::query_as(LIST_DAILY_VIEWS_QUERY)
+        .bind(article_id)
+        .bind(first)
+        .bind(last)
+        .fetch_all(cxn)
+        .await
+}
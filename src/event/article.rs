@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use crate::db::{
+    self,
+    article::{ArticleStatus, ArticleView},
+    outbox::CreateOutboxEntry,
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// The [`Author`] struct identifies the user who authored an article or comment in event data.
+#[derive(Debug, Serialize)]
+pub struct Author {
+    /// Id of the user.
+    pub id: Uuid,
+    /// Username of the user.
+    pub name: String,
+}
+
+/// The [`ArticleEvent`] struct contains event data related to an article that is published to Kafka
+/// when the article is created, updated or deleted.
+#[derive(Debug, Serialize)]
+pub struct ArticleEvent {
+    /// Id of the article.
+    pub id: Uuid,
+    /// Slugified title of the article.
+    pub slug: String,
+    /// Title of the article.
+    pub title: String,
+    /// Description of the article.
+    pub description: String,
+    /// Body of the article.
+    pub body: String,
+    /// Time the article was created.
+    pub created: DateTime<Utc>,
+    /// Time the article was last modified.
+    pub updated: Option<DateTime<Utc>>,
+    /// Publication status of the article.
+    pub status: ArticleStatus,
+    /// Time the article was first published.
+    pub published_at: Option<DateTime<Utc>>,
+    /// Time the article is scheduled to be published.
+    pub publish_at: Option<DateTime<Utc>>,
+    /// Any tags that have been set on the article.
+    #[serde(rename = "tagList")]
+    pub tags: Vec<String>,
+    /// Author of the article.
+    pub author: Author,
+}
+
+impl ArticleEvent {
+    /// Creates a new [`ArticleEvent`] from the data in the given [`ArticleView`].
+    pub fn with_db_view(view: &ArticleView) -> Self {
+        Self {
+            id: view.id,
+            slug: view.slug.clone(),
+            title: view.title.clone(),
+            description: view.description.clone(),
+            body: view.body.clone(),
+            created: view.created,
+            updated: view.updated,
+            status: view.status,
+            published_at: view.published_at,
+            publish_at: view.publish_at,
+            tags: view.tags.clone(),
+            author: Author {
+                id: view.author_id,
+                name: view.author_name.clone(),
+            },
+        }
+    }
+}
+
+/// Creates the outbox entry for an event of the given type, e.g. `ARTICLE_PUBLISHED`, whose payload
+/// is the given [`ArticleEvent`]. Note that the `ARTICLE_PUBLISHED` event is distinct from
+/// `ARTICLE_CREATED` since an article may be created as a draft and published at a later time.
+pub async fn create_article_outbox_entry(
+    cxn: &mut PgConnection,
+    article_event: ArticleEvent,
+    event_type: &str,
+) -> Result<(), sqlx::Error> {
+    let mut headers = HashMap::with_capacity(1);
+    headers.insert(String::from("type"), String::from(event_type));
+
+    let create_outbox_entry = db::outbox::CreateOutboxEntry {
+        topic: String::from("article"),
+        partition_key: Some(article_event.id.to_string()),
+        headers: Some(headers),
+        payload: Some(article_event),
+    };
+
+    let _ = db::outbox::create_outbox_entry(cxn, create_outbox_entry).await?;
+
+    Ok(())
+}
+
+/// Creates the outbox entry for the `ARTICLE_DELETED` event of the article with the given id, which
+/// is only published once the article has been purged from the trash and is gone for good.
+pub async fn create_article_deleted_outbox_entry(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+) -> Result<(), sqlx::Error> {
+    let mut headers = HashMap::with_capacity(1);
+    headers.insert(String::from("type"), String::from("ARTICLE_DELETED"));
+
+    let create_outbox_entry: CreateOutboxEntry<()> = db::outbox::CreateOutboxEntry {
+        topic: String::from("article"),
+        partition_key: Some(article_id.to_string()),
+        headers: Some(headers),
+        payload: None,
+    };
+
+    let _ = db::outbox::create_outbox_entry(cxn, create_outbox_entry).await?;
+
+    Ok(())
+}
+
+/// Creates the outbox entry for an event of the given type, e.g. `COMMENT_TRASHED`, that concerns a
+/// comment on the article with the given id but carries no payload.
+pub async fn create_comment_outbox_entry(
+    cxn: &mut PgConnection,
+    article_id: &Uuid,
+    event_type: &str,
+) -> Result<(), sqlx::Error> {
+    let mut headers = HashMap::with_capacity(1);
+    headers.insert(String::from("type"), String::from(event_type));
+
+    let create_outbox_entry: CreateOutboxEntry<()> = db::outbox::CreateOutboxEntry {
+        topic: String::from("article"),
+        partition_key: Some(article_id.to_string()),
+        headers: Some(headers),
+        payload: None,
+    };
+
+    let _ = db::outbox::create_outbox_entry(cxn, create_outbox_entry).await?;
+
+    Ok(())
+}
+
+/// Creates the outbox entries for the `ARTICLE_PUBLISHED` events of scheduled articles that were
+/// published by the background scheduler rather than in response to a request. The events are the
+/// same as those created when an article is published through the API.
+pub async fn create_scheduled_published_outbox_entries(
+    cxn: &mut PgConnection,
+    views: Vec<ArticleView>,
+) -> Result<(), sqlx::Error> {
+    for view in views {
+        create_article_outbox_entry(cxn, ArticleEvent::with_db_view(&view), "ARTICLE_PUBLISHED")
+            .await?;
+    }
+
+    Ok(())
+}
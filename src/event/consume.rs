@@ -1,13 +1,83 @@
-use crate::{config::Config, event::Error};
+use crate::{cache::Caches, config::Config, db, db::view::ViewCount, event::Error};
 
-use futures::TryStreamExt;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::StreamExt;
 use rdkafka::{
     consumer::{Consumer, ConsumerContext, Rebalance, StreamConsumer},
     error::KafkaResult,
     message::{BorrowedMessage, Headers},
-    ClientContext, Message, Statistics, TopicPartitionList,
+    ClientContext, Message, Offset, Statistics, TopicPartitionList,
 };
-use std::sync::Arc;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use uuid::Uuid;
+
+/// The [`FollowEvent`] struct contains the subset of the data published when a profile is
+/// followed or unfollowed that is needed by the consumer.
+#[derive(Debug, Deserialize)]
+struct FollowEvent {
+    /// Id of the user who followed or unfollowed the profile.
+    follower_id: Uuid,
+}
+
+/// The [`BlockEvent`] struct contains the data published when a profile is blocked or unblocked.
+#[derive(Debug, Deserialize)]
+struct BlockEvent {
+    /// Id of the user who was blocked or unblocked.
+    user_id: Uuid,
+    /// Id of the user who blocked or unblocked the profile.
+    blocker_id: Uuid,
+}
+
+/// The [`ViewEvent`] struct contains the data published when an article is viewed.
+#[derive(Debug, Deserialize)]
+struct ViewEvent {
+    /// Id of the article that was viewed.
+    article_id: Uuid,
+    /// Time the article was viewed.
+    viewed_at: DateTime<Utc>,
+}
+
+/// The [`ViewTally`] struct aggregates the views of articles received by the consumer per article
+/// and day so that they can be periodically added to the database in a single write rather than
+/// one write per view. The offsets of the events that the views were counted from are only stored
+/// for commit once the views have been written to the database.
+#[derive(Debug, Default)]
+pub struct ViewTally {
+    /// Number of views of each article on each day since the tally was last drained.
+    counts: Mutex<HashMap<(Uuid, NaiveDate), i64>>,
+}
+
+impl ViewTally {
+    /// Adds the given number of views of the article on the day to the tally.
+    pub fn add(&self, article_id: Uuid, day: NaiveDate, views: i64) {
+        *self
+            .counts
+            .lock()
+            .expect("view tally lock poisoned")
+            .entry((article_id, day))
+            .or_default() += views;
+    }
+
+    /// Removes all of the views from the tally and returns them as [`ViewCount`]s.
+    pub fn drain(&self) -> Vec<ViewCount> {
+        self.counts
+            .lock()
+            .expect("view tally lock poisoned")
+            .drain()
+            .map(|((article_id, day), views)| ViewCount {
+                article_id,
+                day,
+                views,
+            })
+            .collect()
+    }
+}
 
 /// The [`ConsumeContext`] is a struct that is used to implement a custom Kafka consumer context to
 /// hook into key events in the lifecycle of a Kafka consumer.
@@ -64,16 +134,161 @@ impl ConsumerContext for ConsumeContext {
     }
 }
 
-/// Starts the Kafka consumer configured with the application configuration.
-pub async fn start_kafka_consumer(config: Arc<Config>) -> Result<(), Error> {
+/// Invalidates any cached data that may have been made stale by the event of the given type that
+/// was received on the topic.
+fn invalidate_caches(caches: &Caches, topic: &str, event_type: &str, payload: &str) {
+    if topic == "user" && (event_type == "USER_FOLLOWED" || event_type == "USER_UNFOLLOWED") {
+        match serde_json::from_str::<FollowEvent>(payload) {
+            Ok(event) => caches.suggestions.invalidate(&event.follower_id),
+            Err(e) => tracing::error!("unable to deserialize {} payload: {}", event_type, e),
+        }
+    }
+
+    // blocks exclude suggestions in both directions so the suggestions of both users are stale
+    if topic == "user" && (event_type == "USER_BLOCKED" || event_type == "USER_UNBLOCKED") {
+        match serde_json::from_str::<BlockEvent>(payload) {
+            Ok(event) => {
+                caches.suggestions.invalidate(&event.blocker_id);
+                caches.suggestions.invalidate(&event.user_id);
+            }
+            Err(e) => tracing::error!("unable to deserialize {} payload: {}", event_type, e),
+        }
+    }
+}
+
+/// Invalidates the cached related articles of the article identified by the key of an
+/// `ARTICLE_UPDATED`, `ARTICLE_TRASHED`, `ARTICLE_RESTORED` or `ARTICLE_DELETED` event received on
+/// the topic, along with the cached related articles of any article that the article is related
+/// to. The key is used rather than the payload since `ARTICLE_DELETED` events have no payload.
+fn invalidate_related(caches: &Caches, topic: &str, event_type: &str, key: Option<&str>) {
+    let stale = matches!(
+        event_type,
+        "ARTICLE_UPDATED" | "ARTICLE_TRASHED" | "ARTICLE_RESTORED" | "ARTICLE_DELETED"
+    );
+
+    if topic == "article" && stale {
+        match key.map(Uuid::parse_str) {
+            Some(Ok(article_id)) => caches
+                .related
+                .invalidate_if(|id, related| *id == article_id || related.contains(&article_id)),
+            Some(Err(e)) => tracing::error!("unable to parse {} key: {}", event_type, e),
+            None => tracing::error!("received {} event without a key", event_type),
+        }
+    }
+}
+
+/// Invalidates the cached pages of the sitemap if the event of the given type that was received on
+/// the topic may have changed the published articles, their tags or the usernames of their authors.
+fn invalidate_sitemap(caches: &Caches, topic: &str, event_type: &str) {
+    let stale = match topic {
+        "article" => matches!(
+            event_type,
+            "ARTICLE_CREATED"
+                | "ARTICLE_PUBLISHED"
+                | "ARTICLE_UPDATED"
+                | "ARTICLE_TRASHED"
+                | "ARTICLE_RESTORED"
+                | "ARTICLE_DELETED"
+        ),
+        "user" => event_type == "USER_UPDATED",
+        _ => false,
+    };
+
+    if stale {
+        caches.sitemap.invalidate(&());
+    }
+}
+
+/// Adds the view of an article to the [`ViewTally`] if the event of the given type that was
+/// received on the topic is an `ARTICLE_VIEWED` event.
+fn tally_views(tally: &ViewTally, topic: &str, event_type: &str, payload: &str) {
+    if topic == "article" && event_type == "ARTICLE_VIEWED" {
+        match serde_json::from_str::<ViewEvent>(payload) {
+            Ok(event) => tally.add(event.article_id, event.viewed_at.date_naive(), 1),
+            Err(e) => tracing::error!("unable to deserialize {} payload: {}", event_type, e),
+        }
+    }
+}
+
+/// Writes the views that have been aggregated in the [`ViewTally`] to the database and only then
+/// stores the offsets of the events that have been received since the last write, so that the
+/// offset of a view is never committed before the view is written. If the write fails then the
+/// views are returned to the tally and the offsets are kept so that both are included in the next
+/// write.
+///
+/// Views are counted at least once. If the consumer stops before a write, or a partition is
+/// revoked after its views were written but before its offsets were committed, then the events are
+/// received again and their views are counted again.
+async fn flush_views(
+    db: &PgPool,
+    consumer: &StreamConsumer<ConsumeContext>,
+    views: &ViewTally,
+    offsets: &mut HashMap<(String, i32), i64>,
+) {
+    let counts = views.drain();
+
+    if !counts.is_empty() {
+        match add_article_views(db, &counts).await {
+            Ok(num_updated) => tracing::debug!("added views to {} articles", num_updated),
+            Err(e) => {
+                tracing::error!("error adding article views: {}", e);
+
+                for count in counts {
+                    views.add(count.article_id, count.day, count.views);
+                }
+
+                return;
+            }
+        }
+    }
+
+    if offsets.is_empty() {
+        return;
+    }
+
+    let mut tpl = TopicPartitionList::with_capacity(offsets.len());
+    for ((topic, partition), offset) in offsets.drain() {
+        // the stored offset is that of the next event to receive from the partition
+        if let Err(e) = tpl.add_partition_offset(&topic, partition, Offset::Offset(offset + 1)) {
+            tracing::error!(
+                "invalid offset for partition {} on {}: {}",
+                partition,
+                topic,
+                e
+            );
+        }
+    }
+
+    if let Err(e) = consumer.store_offsets(&tpl) {
+        tracing::warn!("failed to store Kafka consumer offsets: {}", e);
+    }
+}
+
+/// Adds the aggregated view counts to the articles. Returns the number of articles whose views were
+/// updated.
+async fn add_article_views(db: &PgPool, counts: &[ViewCount]) -> Result<u64, Error> {
+    let mut cxn = db.acquire().await?;
+
+    Ok(db::view::add_article_views(&mut cxn, counts).await?)
+}
+
+/// Starts the Kafka consumer configured with the application configuration. Events that are
+/// received are used to invalidate any stale entries in the given [`Caches`] and views of articles
+/// are aggregated in a [`ViewTally`] that is written to the database at the configured interval.
+pub async fn start_kafka_consumer(
+    config: Arc<Config>,
+    caches: Arc<Caches>,
+    db: PgPool,
+) -> Result<(), Error> {
     // Similar to the producer, in a real production application the configuration would need to
-    // be tuned to best meet the use case and performance requirements of the application. For
-    // instance, you would most likely want to manage the committing offsets yourself rather than
-    // having auto commit enabled.
+    // be tuned to best meet the use case and performance requirements of the application. Offsets
+    // are committed automatically but only once they have been stored, which the consumer does
+    // after the views that it has aggregated are written to the database.
     let mut consumer_config = rdkafka::ClientConfig::new();
     consumer_config.set("group.id", "realworld");
     consumer_config.set("bootstrap.servers", &config.kafka.servers);
     consumer_config.set("enable.auto.commit", "true");
+    consumer_config.set("enable.auto.offset.store", "false");
     consumer_config.set("statistics.interval.ms", "120000");
     consumer_config.set("auto.offset.reset", "latest");
 
@@ -86,56 +301,83 @@ pub async fn start_kafka_consumer(config: Arc<Config>) -> Result<(), Error> {
 
     consumer.subscribe(&["article", "user"])?;
 
-    let stream_processor = consumer
-        .stream()
-        .try_for_each(|msg: BorrowedMessage| async move {
-            // Here you could do any processing you need to on the messages that you recieve. This
-            // consumer will be subscribed to both the `article` and `user` topics and simply print
-            // out the payload that is received. A lot of this depends on how your topics and
-            // events are laid out but for this application how the event was processed would be
-            // determined by the topic the event was received on and `type` header value.
-
-            // Extract event type header value.
-            let mut event_type = "unknown";
-            if let Some(headers) = msg.headers() {
-                for (idx, header) in headers.iter().enumerate() {
-                    if header.key == "type" {
-                        event_type = headers
-                            .try_get_as(idx)
-                            .and_then(|h| h.ok())
-                            .and_then(|h| h.value)
-                            .unwrap_or(event_type);
-                    }
-                }
+    let views = ViewTally::default();
+    let mut offsets = HashMap::new();
+
+    let mut stream = consumer.stream();
+    let mut interval = tokio::time::interval(Duration::from_millis(config.views.flush_interval));
+
+    loop {
+        tokio::select! {
+            msg = stream.next() => {
+                let Some(msg) = msg else {
+                    break;
+                };
+                let msg = msg?;
+
+                process_message(&caches, &views, &msg);
+
+                offsets.insert((msg.topic().to_string(), msg.partition()), msg.offset());
             }
+            _ = interval.tick() => flush_views(&db, &consumer, &views, &mut offsets).await,
+        }
+    }
 
-            // Log appropriate message based on the message payload.
-            match msg.payload_view::<str>() {
-                Some(Ok(payload)) => {
-                    tracing::info!(
-                        "received event of type {} on {} with payload: {}",
-                        event_type,
-                        msg.topic(),
-                        payload
-                    );
-                }
-                Some(Err(err)) => {
-                    tracing::error!(
-                        "received event of type {} on {} with invalid string payload: {}",
-                        event_type,
-                        msg.topic(),
-                        err
-                    )
-                }
-                None => tracing::info!(
-                    "received event of type {} on {} with no payload",
-                    event_type,
-                    msg.topic()
-                ),
+    Ok(())
+}
+
+/// Processes an event received by the consumer, invalidating any cached data that it makes stale
+/// and adding any view of an article to the [`ViewTally`].
+fn process_message(caches: &Caches, views: &ViewTally, msg: &BorrowedMessage) {
+    // Here you could do any processing you need to on the messages that you recieve. This
+    // consumer will be subscribed to both the `article` and `user` topics and simply print
+    // out the payload that is received. A lot of this depends on how your topics and
+    // events are laid out but for this application how the event was processed would be
+    // determined by the topic the event was received on and `type` header value.
+
+    // Extract event type header value.
+    let mut event_type = "unknown";
+    if let Some(headers) = msg.headers() {
+        for (idx, header) in headers.iter().enumerate() {
+            if header.key == "type" {
+                event_type = headers
+                    .try_get_as(idx)
+                    .and_then(|h| h.ok())
+                    .and_then(|h| h.value)
+                    .unwrap_or(event_type);
             }
+        }
+    }
 
-            Ok(())
-        });
+    let key = msg.key_view::<str>().and_then(|k| k.ok());
+    invalidate_related(caches, msg.topic(), event_type, key);
+    invalidate_sitemap(caches, msg.topic(), event_type);
 
-    stream_processor.await.map_err(|e| e.into())
+    // Log appropriate message based on the message payload.
+    match msg.payload_view::<str>() {
+        Some(Ok(payload)) => {
+            tracing::info!(
+                "received event of type {} on {} with payload: {}",
+                event_type,
+                msg.topic(),
+                payload
+            );
+
+            invalidate_caches(caches, msg.topic(), event_type, payload);
+            tally_views(views, msg.topic(), event_type, payload);
+        }
+        Some(Err(err)) => {
+            tracing::error!(
+                "received event of type {} on {} with invalid string payload: {}",
+                event_type,
+                msg.topic(),
+                err
+            )
+        }
+        None => tracing::info!(
+            "received event of type {} on {} with no payload",
+            event_type,
+            msg.topic()
+        ),
+    }
 }
@@ -1,5 +1,7 @@
+pub mod article;
 pub mod consume;
 pub mod produce;
+pub mod schedule;
 
 /// Enumerates the errors generated by the event module.
 #[derive(Debug, thiserror::Error)]
@@ -16,6 +18,12 @@ pub enum Error {
         #[from]
         source: tokio::task::JoinError,
     },
+    /// Occurs when the Markdown body of an article cannot be rendered.
+    #[error("error rendering markdown")]
+    Markdown {
+        #[from]
+        source: crate::markdown::Error,
+    },
     /// Occurs when there is an error querying the database for outbox entries.
     #[error("error querying the database for outbox entries")]
     OutboxDatabase {
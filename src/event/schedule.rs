@@ -0,0 +1,300 @@
+use crate::{
+    config::Config,
+    db,
+    event::{self, Error},
+    markdown,
+};
+
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::mpsc::Sender;
+use uuid::Uuid;
+
+/// Schedules a periodic check for scheduled articles whose publish time has passed. Any that are
+/// found are published and an `ARTICLE_PUBLISHED` event is added to the outbox for each of them,
+/// after which the outbox processor is notified over the channel.
+///
+/// Multiple instances of the application may run the check at the same time since the due articles
+/// are locked using `FOR UPDATE SKIP LOCKED` which ensures that each article is only published
+/// once.
+pub async fn schedule_article_publishing(
+    config: Arc<Config>,
+    db: PgPool,
+    tx: Sender<()>,
+) -> Result<(), Error> {
+    let interval_ms = config.scheduler.interval;
+    let batch_size = config.scheduler.batch_size as i64;
+
+    tracing::info!("scheduling article publishing for every {}ms", interval_ms);
+
+    let scheduled_task = tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+
+        loop {
+            interval.tick().await;
+
+            match publish_due_articles(&db, batch_size).await {
+                Err(e) => tracing::error!("error publishing scheduled articles: {}", e),
+                Ok(0) => {}
+                Ok(num_published) => {
+                    tracing::info!("published {} scheduled articles", num_published);
+
+                    if let Err(e) = tx.send(()).await {
+                        tracing::warn!("failed to notify outbox processor of new entries: {}", e);
+                    }
+                }
+            }
+        }
+    });
+
+    // We should never really return here as we simply log when an error is encountered right now.
+    Err(scheduled_task.await?)
+}
+
+/// Publishes a batch of scheduled articles that are due and adds the corresponding events to the
+/// outbox within the same transaction. Returns the number of articles that were published.
+async fn publish_due_articles(db: &PgPool, batch_size: i64) -> Result<usize, Error> {
+    let mut tx = db.begin().await?;
+
+    let articles = db::article::publish_due_articles(&mut tx, batch_size).await?;
+    let num_published = articles.len();
+
+    if num_published > 0 {
+        event::article::create_scheduled_published_outbox_entries(&mut tx, articles).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(num_published)
+}
+
+/// Schedules a periodic reconciliation of the favorites, comments and views counts that are
+/// denormalized onto the articles. The counts are maintained as favorites and comments are added and
+/// removed and as views are aggregated so the reconciliation only corrects drift, e.g. from rows that
+/// were changed outside of the application.
+///
+/// The articles are reconciled in batches, each in its own transaction, so that only a small number
+/// of articles are locked at any one time.
+pub async fn schedule_counter_reconciliation(config: Arc<Config>, db: PgPool) -> Result<(), Error> {
+    let interval_ms = config.reconciliation.interval;
+    let batch_size = config.reconciliation.batch_size as i64;
+
+    tracing::info!(
+        "scheduling article counter reconciliation for every {}ms",
+        interval_ms
+    );
+
+    let scheduled_task = tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+
+        loop {
+            interval.tick().await;
+
+            match reconcile_article_counters(&db, batch_size).await {
+                Err(e) => tracing::error!("error reconciling article counters: {}", e),
+                Ok(0) => tracing::debug!("article counters are consistent"),
+                Ok(num_corrected) => {
+                    tracing::warn!("corrected the counters of {} articles", num_corrected)
+                }
+            }
+        }
+    });
+
+    // We should never really return here as we simply log when an error is encountered right now.
+    Err(scheduled_task.await?)
+}
+
+/// Reconciles the counters of all of the articles in batches of the given size. Returns the number
+/// of articles whose counters were corrected.
+async fn reconcile_article_counters(db: &PgPool, batch_size: i64) -> Result<u64, Error> {
+    let mut num_corrected = 0;
+    let mut after_id = Uuid::nil();
+
+    loop {
+        let mut tx = db.begin().await?;
+
+        let article_ids = db::article::lock_articles_batch(&mut tx, &after_id, batch_size).await?;
+
+        let Some(last_id) = article_ids.last() else {
+            break;
+        };
+        after_id = *last_id;
+
+        num_corrected += db::article::reconcile_article_counters(&mut tx, &article_ids).await?;
+
+        tx.commit().await?;
+
+        if (article_ids.len() as i64) < batch_size {
+            break;
+        }
+    }
+
+    Ok(num_corrected)
+}
+
+/// Schedules a periodic refresh of the trending scores that are stored on the articles. The scores
+/// are adjusted as favorites are added and removed, but each favorite is worth half as much for
+/// every day that passes so the scores are recomputed from the recent favorites to let them decay.
+pub async fn schedule_trending_refresh(config: Arc<Config>, db: PgPool) -> Result<(), Error> {
+    let interval_ms = config.trending.interval;
+
+    tracing::info!(
+        "scheduling article trending score refresh for every {}ms",
+        interval_ms
+    );
+
+    let scheduled_task = tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+
+        loop {
+            interval.tick().await;
+
+            match refresh_trending_scores(&db).await {
+                Ok(num_refreshed) => tracing::debug!("refreshed {} trending scores", num_refreshed),
+                Err(e) => tracing::error!("error refreshing article trending scores: {}", e),
+            }
+        }
+    });
+
+    // We should never really return here as we simply log when an error is encountered right now.
+    Err(scheduled_task.await?)
+}
+
+/// Recomputes the trending scores of the articles. Returns the number of articles whose scores were
+/// recomputed.
+async fn refresh_trending_scores(db: &PgPool) -> Result<u64, Error> {
+    let mut cxn = db.acquire().await?;
+
+    Ok(db::article::refresh_trending_scores(&mut cxn).await?)
+}
+
+/// Number of articles whose body is rendered to HTML in a single transaction of the backfill.
+const BACKFILL_BATCH_SIZE: i64 = 100;
+
+/// Renders the body of every article that was created before the rendered HTML was stored and
+/// stores the HTML so that it does not have to be rendered when the article is read. The task runs
+/// once when the application starts and returns the number of articles that were rendered.
+///
+/// The articles are rendered in batches, each in its own transaction, and are locked using
+/// `FOR UPDATE SKIP LOCKED` so that multiple instances of the application can run the backfill at
+/// the same time without rendering the same article twice.
+pub async fn backfill_article_bodies(db: PgPool) -> Result<usize, Error> {
+    let mut num_rendered = 0;
+
+    loop {
+        let mut tx = db.begin().await?;
+
+        let articles = db::article::lock_unrendered_articles(&mut tx, BACKFILL_BATCH_SIZE).await?;
+
+        for article in articles.iter() {
+            let rendered = markdown::render_html(article.body.clone()).await?;
+            db::article::update_article_body_html(&mut tx, &article.id, &rendered.html).await?;
+        }
+
+        tx.commit().await?;
+
+        num_rendered += articles.len();
+        if (articles.len() as i64) < BACKFILL_BATCH_SIZE {
+            break;
+        }
+    }
+
+    Ok(num_rendered)
+}
+
+/// Schedules a periodic purge of the articles and comments that have been in the trash for longer
+/// than the configured retention period. Purged articles are deleted along with all of their
+/// associated data and an `ARTICLE_DELETED` or `COMMENT_DELETED` event is added to the outbox for
+/// each purged article or comment, after which the outbox processor is notified over the channel.
+///
+/// The articles and comments are purged in batches, each in its own transaction, and are locked
+/// using `FOR UPDATE SKIP LOCKED` so that multiple instances of the application can purge at the
+/// same time without purging the same row twice.
+pub async fn schedule_trash_purge(
+    config: Arc<Config>,
+    db: PgPool,
+    tx: Sender<()>,
+) -> Result<(), Error> {
+    let interval_ms = config.trash.interval;
+    let retention = config.trash.retention;
+    let batch_size = config.trash.batch_size as i64;
+
+    tracing::info!("scheduling trash purge for every {}ms", interval_ms);
+
+    let scheduled_task = tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+
+        loop {
+            interval.tick().await;
+
+            match purge_trash(&db, retention, batch_size).await {
+                Err(e) => tracing::error!("error purging the trash: {}", e),
+                Ok((0, 0)) => {}
+                Ok((num_articles, num_comments)) => {
+                    tracing::info!(
+                        "purged {} articles and {} comments from the trash",
+                        num_articles,
+                        num_comments
+                    );
+
+                    if let Err(e) = tx.send(()).await {
+                        tracing::warn!("failed to notify outbox processor of new entries: {}", e);
+                    }
+                }
+            }
+        }
+    });
+
+    // We should never really return here as we simply log when an error is encountered right now.
+    Err(scheduled_task.await?)
+}
+
+/// Purges the articles and then the comments that are due to be purged in batches of the given
+/// size, adding the corresponding events to the outbox within the same transaction as each batch.
+/// Returns the number of articles and comments that were purged.
+async fn purge_trash(
+    db: &PgPool,
+    retention: u64,
+    batch_size: i64,
+) -> Result<(usize, usize), Error> {
+    let mut num_articles = 0;
+    let mut num_comments = 0;
+
+    loop {
+        let mut tx = db.begin().await?;
+
+        let article_ids = db::trash::lock_expired_articles(&mut tx, retention, batch_size).await?;
+
+        for article_id in article_ids.iter() {
+            db::article::delete_article_by_id(&mut tx, article_id).await?;
+            event::article::create_article_deleted_outbox_entry(&mut tx, article_id).await?;
+        }
+
+        tx.commit().await?;
+
+        num_articles += article_ids.len();
+        if (article_ids.len() as i64) < batch_size {
+            break;
+        }
+    }
+
+    loop {
+        let mut tx = db.begin().await?;
+
+        let article_ids = db::trash::purge_expired_comments(&mut tx, retention, batch_size).await?;
+
+        for article_id in article_ids.iter() {
+            event::article::create_comment_outbox_entry(&mut tx, article_id, "COMMENT_DELETED")
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        num_comments += article_ids.len();
+        if (article_ids.len() as i64) < batch_size {
+            break;
+        }
+    }
+
+    Ok((num_articles, num_comments))
+}
@@ -2,8 +2,20 @@ use std::collections::HashMap;
 
 use crate::{
     db,
-    db::{outbox::CreateOutboxEntry, user::Profile},
-    http::{auth::AuthContext, AppContext, Error, Pagination},
+    db::{
+        article::{ArticleSort, ArticleStatus, TagMode},
+        user::Profile,
+        Keyset,
+    },
+    event::{
+        self,
+        article::{ArticleEvent, Author},
+    },
+    http::{
+        auth::AuthContext,
+        cursor::{self, Cursors},
+        markdown, profile, tag, view, AppContext, Error, Pagination,
+    },
 };
 
 use axum::{
@@ -13,8 +25,9 @@ use axum::{
     Json, Router,
 };
 use chrono::{DateTime, Utc};
-use http::StatusCode;
+use http::{HeaderMap, StatusCode};
 use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
 use uuid::Uuid;
 
 /// Creates the [`Router`] for the HTTP endpoints that correspond to the `article` domain and requires
@@ -24,27 +37,37 @@ use uuid::Uuid;
 ///
 /// * `GET /api/articles` - List multiple articles with filters ordered by the most recent first.
 /// * `GET /api/articles/feed` - Authentication required, will return multiple articles created by followed
-/// users, ordered by most recent first.
+///   users, ordered by most recent first.
+/// * `GET /api/user/drafts` - Authentication required, will return the draft articles of the
+///   authenticated user, ordered by most recent first.
 /// * `GET /api/articles/:slug` - Returns a single article.
 /// * `POST /api/articles` - Authentication required, creates a new article.
 /// * `PUT /api/articles/:slug` - Authentication required, updates an existing article.
-/// * `DELETE /api/articles/:slug` - Authentication required, deletes an existing article.
+/// * `DELETE /api/articles/:slug` - Authentication required, moves an existing article to the trash.
+/// * `POST /api/articles/:slug/restore` - Authentication required, restores an article from the
+///   trash.
+/// * `POST /api/articles/:slug/publish` - Authentication required, publishes an existing article.
 /// * `POST /api/articles/:slug/comments` - Authentication required, creates a new comment on an
-/// article.
+///   article.
 /// * `GET /api/articles/:slug/comments` - Lists all comments for an article.
-/// * `DELETE /api/articles/:slug/comments/:id` - Authentication required, deletes a comment on an
-/// article.
+/// * `DELETE /api/articles/:slug/comments/:id` - Authentication required, moves a comment on an
+///   article to the trash.
+/// * `POST /api/articles/:slug/comments/:id/restore` - Authentication required, restores a comment
+///   on an article from the trash.
 /// * `POST /api/articles/:slug/favorite` - Authentication required, favorites an article.
 /// * `DELETE /api/articles/:slug/favorite` - Authentication required, removes an article from
-/// favorites.
+///   favorites.
 pub(super) fn router() -> Router<AppContext> {
     Router::new()
         .route("/api/articles/feed", get(user_feed))
+        .route("/api/user/drafts", get(user_drafts))
         .route("/api/articles", get(list_articles).post(create_article))
         .route(
             "/api/articles/:slug",
             get(get_article).put(update_article).delete(delete_article),
         )
+        .route("/api/articles/:slug/publish", post(publish_article))
+        .route("/api/articles/:slug/restore", post(restore_article))
         .route(
             "/api/articles/:slug/favorite",
             post(favorite_article).delete(unfavorite_article),
@@ -54,13 +77,17 @@ pub(super) fn router() -> Router<AppContext> {
             post(create_comment).get(get_comments),
         )
         .route("/api/articles/:slug/comments/:id", delete(delete_comment))
+        .route(
+            "/api/articles/:slug/comments/:id/restore",
+            post(restore_comment),
+        )
 }
 
 /// The [`Article`] struct contains data that repesents an article as returned from the API. It
 /// contains the relevant article data, tag data and properties relevant to the currently
 /// authenticted user if one exists.
 #[derive(Debug, Serialize)]
-struct Article {
+pub(super) struct Article {
     /// Id of the article.
     #[serde(skip_serializing)]
     id: Uuid,
@@ -72,34 +99,109 @@ struct Article {
     description: String,
     /// Body of the article.
     body: String,
+    /// Sanitized HTML rendered from the Markdown body of the article, if it has been rendered.
+    #[serde(rename = "bodyHtml", skip_serializing_if = "Option::is_none")]
+    body_html: Option<String>,
     /// List of tags associated with the article.
     #[serde(rename = "tagList")]
-    tags: Option<Vec<String>>,
+    tags: Vec<String>,
     /// Time the article was created.
     #[serde(rename = "createdAt")]
     created: DateTime<Utc>,
     /// Time the article was last modified.
     #[serde(rename = "updatedAt")]
     updated: Option<DateTime<Utc>>,
+    /// Publication status of the article.
+    status: ArticleStatus,
+    /// Time the article was first published.
+    #[serde(rename = "publishedAt")]
+    published_at: Option<DateTime<Utc>>,
+    /// Time the article is scheduled to be published.
+    #[serde(rename = "publishAt")]
+    publish_at: Option<DateTime<Utc>>,
+    /// HTML snippet of the body with the terms matching the search query highlighted. Only
+    /// included when listing articles with a search query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<String>,
     /// Flag indicating whether the logged in user, if available, has favorited the article.
     favorited: bool,
     /// Count of the total number of users who have favorited the article.
     #[serde(rename = "favoritesCount")]
     favorites_count: i64,
+    /// Count of the total number of comments on the article.
+    #[serde(rename = "commentsCount")]
+    comments_count: i64,
+    /// Count of the total number of views of the article.
+    #[serde(rename = "viewsCount")]
+    views_count: i64,
+    /// Number of words in the body of the article, excluding code.
+    #[serde(rename = "wordCount")]
+    word_count: i32,
+    /// Estimated time to read the article in minutes.
+    #[serde(rename = "readingTimeMinutes")]
+    reading_time_minutes: i32,
+    /// Flag indicating whether the logged in user, if available, has bookmarked the article.
+    bookmarked: bool,
+    /// Navigation of the collection that the article is part of. Only included when the article
+    /// is part of a collection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    series: Option<Series>,
     /// Public [`Profile`] of the user who authored the article.
     author: Profile,
+    /// Public [`Profile`]s of the users who accepted an invitation to co-author the article.
+    coauthors: Vec<Profile>,
+}
+
+/// The [`Series`] struct contains the navigation of the collection that an article is part of,
+/// which only takes into account the articles of the collection that are visible to the user.
+#[derive(Debug, Serialize)]
+pub(super) struct Series {
+    /// Slug of the collection.
+    slug: String,
+    /// Title of the collection.
+    title: String,
+    /// Position of the article in the collection, starting at one.
+    position: i64,
+    /// Number of articles in the collection.
+    length: i64,
+    /// Article that precedes the article in the collection, if any.
+    previous: Option<SeriesArticle>,
+    /// Article that follows the article in the collection, if any.
+    next: Option<SeriesArticle>,
+}
+
+/// The [`SeriesArticle`] struct identifies an adjacent article in a [`Series`].
+#[derive(Debug, Serialize)]
+pub(super) struct SeriesArticle {
+    /// Slugified title of the article.
+    slug: String,
+    /// Title of the article.
+    title: String,
 }
 
 impl Article {
     /// Creates a new [`Article`] populated from the given [`crate::db::article::ArticleView`].
-    fn with_db_view(view: db::article::ArticleView) -> Self {
-        // TODO: Consider storing articles tags in an array directly on the article row in the database.
-        // Right now we send back a CSV of tags with the query result and then they are transformed into a
-        // Vec<String> before the response is returned to the client. Having that tags in their own table
-        // allows for an easy implementation of the list tags API so that could be kept along side the
-        // text array property on the article.
-        let tags = match view.tags {
-            Some(csv) if !csv.is_empty() => Some(csv.split(',').map(ToOwned::to_owned).collect()),
+    pub(super) fn with_db_view(view: db::article::ArticleView) -> Self {
+        let series = match (
+            view.series_slug,
+            view.series_title,
+            view.series_position,
+            view.series_length,
+        ) {
+            (Some(slug), Some(title), Some(position), Some(length)) => Some(Series {
+                slug,
+                title,
+                position,
+                length,
+                previous: view
+                    .series_prev_slug
+                    .zip(view.series_prev_title)
+                    .map(|(slug, title)| SeriesArticle { slug, title }),
+                next: view
+                    .series_next_slug
+                    .zip(view.series_next_title)
+                    .map(|(slug, title)| SeriesArticle { slug, title }),
+            }),
             _ => None,
         };
 
@@ -109,11 +211,22 @@ impl Article {
             title: view.title,
             description: view.description,
             body: view.body,
+            body_html: view.body_html,
             created: view.created,
             updated: view.updated,
+            status: view.status,
+            published_at: view.published_at,
+            publish_at: view.publish_at,
+            snippet: view.snippet.as_deref().map(highlight_snippet),
             favorited: view.favorited,
             favorites_count: view.favorites_count,
-            tags,
+            comments_count: view.comments_count,
+            views_count: view.views_count,
+            word_count: view.word_count,
+            reading_time_minutes: view.reading_time_minutes,
+            bookmarked: view.bookmarked,
+            series,
+            tags: view.tags,
             author: Profile {
                 id: view.author_id,
                 name: view.author_name,
@@ -121,16 +234,56 @@ impl Article {
                 image: view.author_image,
                 following: view.author_followed,
             },
+            coauthors: view.coauthors.0,
         }
     }
 }
 
+/// Returns the position of the article in listings ordered by the time articles were created.
+fn article_key(view: &db::article::ArticleView) -> Keyset {
+    Keyset {
+        created: view.created,
+        id: view.id,
+    }
+}
+
+/// Converts a reading time filter in minutes to the type of the column it is compared with. Returns
+/// an [`Error::Validation`] if the value is too large to be a reading time.
+fn reading_time(minutes: Option<u32>) -> Result<Option<i32>, Error> {
+    minutes
+        .map(|m| i32::try_from(m).map_err(|_| Error::Validation))
+        .transpose()
+}
+
+/// Converts a snippet returned by the database, in which the terms matching the search query are
+/// delimited by the `\x02` and `\x03` control characters, to HTML. The text of the snippet is
+/// escaped since it comes from the raw body of the article and the delimiters are then replaced
+/// with `<mark>` elements.
+fn highlight_snippet(snippet: &str) -> String {
+    let mut html = String::with_capacity(snippet.len() + 32);
+
+    for c in snippet.chars() {
+        match c {
+            '\u{2}' => html.push_str("<mark>"),
+            '\u{3}' => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+
+    html
+}
+
 /// The [`ArticleBody`] struct is the envelope in which different data for an article is
 /// returned to the client or accepted from the client.
 #[derive(Debug, Deserialize, Serialize)]
-struct ArticleBody<T> {
+pub(super) struct ArticleBody<T> {
     /// Article related data.
-    article: T,
+    pub(super) article: T,
 }
 
 /// The [`ArticlesBody`] struct is the envelope in which multiple [`Article`]s are returned to the
@@ -142,6 +295,9 @@ struct ArticlesBody {
     /// Total count of the articles matching any filters.
     #[serde(rename = "articlesCount")]
     articles_count: i64,
+    /// Cursors that select the pages adjacent to the returned articles.
+    #[serde(flatten)]
+    cursors: Cursors,
 }
 
 /// The [`CreateArticle`] struct contains the data received from the HTTP request to create a new
@@ -157,6 +313,11 @@ struct CreateArticle {
     /// List of tags associated with the article.
     #[serde(rename = "tagList")]
     tags: Option<Vec<String>>,
+    /// Publication status of the article.
+    status: Option<ArticleStatus>,
+    /// Time in the future at which the article should be published.
+    #[serde(rename = "publishAt")]
+    publish_at: Option<DateTime<Utc>>,
 }
 
 /// The [`UpdateArticle`] struct contains the data received from the HTTP request to update an
@@ -169,6 +330,11 @@ struct UpdateArticle {
     description: Option<String>,
     /// Body of the article.
     body: Option<String>,
+    /// Publication status of the article.
+    status: Option<ArticleStatus>,
+    /// Time in the future at which the article should be published.
+    #[serde(rename = "publishAt")]
+    publish_at: Option<DateTime<Utc>>,
 }
 
 /// The [`CommentBody`] struct is the envelope in which data for a comment is returned to the
@@ -185,6 +351,22 @@ struct CommentBody<T> {
 struct CommentsBody {
     /// [`Vec`] of [`Comment`]s for an article.
     comments: Vec<Comment>,
+    /// Cursors that select the pages adjacent to the returned comments.
+    #[serde(flatten)]
+    cursors: Cursors,
+}
+
+/// The [`CommentPagination`] struct contains the optional paging parameters of the list comments
+/// API. All of the comments of an article are returned when none of them are given.
+#[derive(Debug, Deserialize)]
+struct CommentPagination {
+    /// Maximum number of results to return for a single request.
+    limit: Option<i32>,
+    /// Starting offset into the entire set of results.
+    #[serde(default)]
+    offset: i32,
+    /// Opaque cursor returned with a previous page of results that selects an adjacent page.
+    cursor: Option<String>,
 }
 
 /// The [`CreateComment`] struct contains the data received from the HTTP request to create a new
@@ -234,15 +416,40 @@ impl Comment {
 
 /// The [`ListFilters`] struct encapsulates all of the filters available to the list articles
 /// API. The axum framework can deserialize the query string parameters into an instance of
-/// the struct auto-magically for us.
+/// the struct auto-magically for us. The query extractor from `axum-extra` is required in order to
+/// collect parameters that are repeated into a [`Vec`].
 #[derive(Debug, Deserialize)]
 struct ListFilters {
-    /// Name of the tag that an article must have.
-    tag: Option<String>,
-    /// Name of the author of the article.
-    author: Option<String>,
+    /// Names of the tags that an article must have.
+    #[serde(default)]
+    tag: Vec<String>,
+    /// Determines whether an article must have all or any of the tags.
+    #[serde(rename = "tagMode", default)]
+    tag_mode: TagMode,
+    /// Names of the tags that an article must not have.
+    #[serde(rename = "excludeTag", default)]
+    exclude_tag: Vec<String>,
+    /// Names of the users, any of whom authored the article.
+    #[serde(default)]
+    author: Vec<String>,
+    /// Time at or after which the article must have been created.
+    #[serde(rename = "createdAfter")]
+    created_after: Option<DateTime<Utc>>,
+    /// Time before which the article must have been created.
+    #[serde(rename = "createdBefore")]
+    created_before: Option<DateTime<Utc>>,
+    /// Minimum estimated reading time of the article in minutes.
+    #[serde(rename = "minReadingTime")]
+    min_reading_time: Option<u32>,
+    /// Maximum estimated reading time of the article in minutes.
+    #[serde(rename = "maxReadingTime")]
+    max_reading_time: Option<u32>,
     /// Name of the user who favorited the article.
     favorited: Option<String>,
+    /// Full-text search query matched against the title, description and body of the article.
+    q: Option<String>,
+    /// Order in which the articles are sorted.
+    sort: Option<ArticleSort>,
     // TODO: this would be preferable but appears to be a limitation in serde when trying to do
     // this. A workaround is given here https://docs.rs/serde_qs/0.12.0/serde_qs/index.html#flatten-workaround
     // so perhaps look into it at some point. Until then just duplicate the fields.
@@ -254,56 +461,28 @@ struct ListFilters {
     /// Starting offset into the entire set of results.
     #[serde(default)]
     offset: i32,
+    /// Opaque cursor returned with a previous page of results that selects an adjacent page.
+    cursor: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-struct Author {
-    id: Uuid,
-    name: String,
-}
-
-/// The [`ArticleEvent`] struct contains event data related to an article that is published to Kafka
-/// when the article is created, updated or deleted.
-#[derive(Debug, Serialize)]
-struct ArticleEvent {
-    /// Id of the article.
-    id: Uuid,
-    /// Slugified title of the article.
-    slug: String,
-    /// Title of the article.
-    title: String,
-    /// Description of the article.
-    description: String,
-    /// Body of the article.
-    body: String,
-    /// Time the article was created.
-    created: DateTime<Utc>,
-    /// Time the article was last modified.
-    updated: Option<DateTime<Utc>>,
-    /// Any tags that have been set on the article.
-    #[serde(rename = "tagList")]
-    tags: Option<Vec<String>>,
-    /// Author of the article.
-    author: Author,
-}
-
-impl ArticleEvent {
-    /// Creates a new [`ArticleEvent`] from the data in the given [`Article`].
-    fn with_article(article: &Article) -> Self {
-        Self {
-            id: article.id,
-            slug: article.slug.clone(),
-            title: article.title.clone(),
-            description: article.description.clone(),
-            body: article.body.clone(),
-            created: article.created,
-            updated: article.updated,
-            tags: article.tags.clone(),
-            author: Author {
-                id: article.author.id,
-                name: article.author.name.clone(),
-            },
-        }
+/// Creates the [`ArticleEvent`] that is published to Kafka for the given [`Article`].
+fn article_event(article: &Article) -> ArticleEvent {
+    ArticleEvent {
+        id: article.id,
+        slug: article.slug.clone(),
+        title: article.title.clone(),
+        description: article.description.clone(),
+        body: article.body.clone(),
+        created: article.created,
+        updated: article.updated,
+        status: article.status,
+        published_at: article.published_at,
+        publish_at: article.publish_at,
+        tags: article.tags.clone(),
+        author: Author {
+            id: article.author.id,
+            name: article.author.name.clone(),
+        },
     }
 }
 
@@ -351,24 +530,58 @@ impl FavoriteEvent {
     fn with_user_and_article(user_id: Uuid, article: &Article) -> Self {
         Self {
             user_id,
-            article: ArticleEvent::with_article(article),
+            article: article_event(article),
         }
     }
 }
 
 /// Handles the list articles endpoint at `GET /api/articles` which returns articles ordered by
-/// created date in descending order.
+/// created date in descending order unless another sort order is requested. Only published articles
+/// are returned unless the authenticated user is the author of the article.
 ///
 /// # Query Parameters
 ///
 /// The following query parameters are supported which allow the client to filter the articles
 /// returned by the API.
 ///
-/// * `tag` - name of the tag associated with the article
-/// * `author` - name of the user who authored the article
+/// * `tag` - name of the tag associated with the article, may be repeated to filter by multiple tags
+/// * `tagMode` - `all` to require every one of the tags, the default, or `any` to require at least
+///   one of them
+/// * `excludeTag` - name of a tag that the article must not have, may be repeated
+/// * `author` - name of the user who authored the article, may be repeated to include the articles
+///   of any of the users
+/// * `createdAfter` - RFC 3339 time at or after which the article was created
+/// * `createdBefore` - RFC 3339 time before which the article was created
+/// * `minReadingTime` - minimum estimated reading time of the article in minutes
+/// * `maxReadingTime` - maximum estimated reading time of the article in minutes, e.g. `5` for
+///   articles that can be read in five minutes or less
 /// * `favorited` - name of the user who favorited the article
+/// * `q` - full-text search query matched against the title, description and body of the article.
+///   Supports quoted phrases, `or` and `-` to exclude terms. When given, the articles are ordered
+///   by relevance, with matches in the title ranked highest, unless another sort order is requested
+///   and each article includes a `snippet` of the body with the matching terms wrapped in `<mark>`
+///   elements.
+/// * `sort` - order in which the articles are sorted, one of:
+///   * `newest` - most recently created first, the default
+///   * `oldest` - least recently created first
+///   * `favorites` - most favorited first
+///   * `comments` - most commented on first
+///   * `trending` - most favorited recently first, where each favorite counts for half as much for
+///     every day that has passed since it was made, as of the last periodic refresh of the scores
+///   * `relevance` - best match for the full-text search query first, requires `q`
+///
+///   Articles that are equal in the sort order are ordered by the most recently created first.
 /// * `limit` - count of the articles that should be returned in the response
 /// * `offset` - offset into the total set of results to start the current result set
+/// * `cursor` - opaque cursor from the `nextCursor` or `prevCursor` of a previous response that
+///   selects the page adjacent to it, cannot be combined with `offset` and is only available when
+///   sorting by `newest` or `oldest`
+///
+/// Paging with cursors is stable when articles are created between requests, where paging by
+/// offset returns the same article twice, and does not slow down as the pages get deeper.
+///
+/// The `author` and `favorited` filters also match a previous username of a user that was changed
+/// within the configured grace period.
 ///
 /// # Response Body Format
 ///
@@ -379,59 +592,120 @@ impl FavoriteEvent {
 ///     "title": "How to train your dragon",
 ///     "description": "Ever wonder how?",
 ///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
 ///     "tagList": ["dragons", "training"],
 ///     "createdAt": "2016-02-18T03:22:56.637Z",
 ///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
 ///     "favorited": false,
 ///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
 ///     "author": {
 ///       "username": "jake",
 ///       "bio": "I work at statefarm",
 ///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
 ///       "following": false
-///     }
-///   }]
+///     },
+///     "coauthors": []
+///   }],
+///   "articlesCount": 1,
+///   "nextCursor": "bjoxNDU1NzY1Nzc2NjM3MDAwOjE1ZDJkYzQ4LTM0N2QtNDdhZi1iMjE4LWU0YjA0NTc3MzdiMA",
+///   "prevCursor": null
 /// }
 /// ```
 async fn list_articles(
     ctx: State<AppContext>,
     auth_ctx: Option<AuthContext>,
-    filters: Query<ListFilters>,
+    filters: axum_extra::extract::Query<ListFilters>,
 ) -> Result<Json<ArticlesBody>, Error> {
     let user_ctx = auth_ctx.map(|ac| ac.user_id);
 
     let mut cxn = ctx.db.acquire().await?;
 
-    let articles = db::article::query_articles(
-        &mut cxn,
-        user_ctx,
-        filters.tag.as_ref(),
-        filters.author.as_ref(),
-        filters.favorited.as_ref(),
-        filters.limit,
-        filters.offset,
-    )
-    .await?
-    .into_iter()
-    .map(Article::with_db_view)
-    .collect();
-
-    let articles_count = db::article::count_articles(
-        &mut cxn,
-        filters.tag.as_ref(),
-        filters.author.as_ref(),
-        filters.favorited.as_ref(),
-    )
-    .await?;
+    let mut authors = Vec::with_capacity(filters.author.len());
+    for author in filters.author.iter().cloned() {
+        authors.push(profile::resolve_username(&mut cxn, &ctx, author).await?);
+    }
+
+    // an article has each tag at most once so the tags must be distinct to be matched in all mode
+    let mut tags = tag::normalize_filter_tags(&ctx.config.tags, &filters.tag);
+    tags.sort();
+    tags.dedup();
+
+    let exclude_tags = tag::normalize_filter_tags(&ctx.config.tags, &filters.exclude_tag);
+
+    let favorited = match filters.favorited.clone() {
+        Some(favorited) => Some(profile::resolve_username(&mut cxn, &ctx, favorited).await?),
+        None => None,
+    };
+
+    let query = filters.q.as_ref().filter(|q| !q.trim().is_empty());
+
+    let sort = match (filters.sort, query) {
+        (Some(ArticleSort::Relevance), None) => return Err(Error::Validation),
+        (Some(sort), _) => sort,
+        (None, Some(_)) => ArticleSort::Relevance,
+        (None, None) => ArticleSort::Newest,
+    };
+
+    // only articles sorted by the time they were created are in the order of the cursor position
+    if !sort.is_keyset() && filters.cursor.is_some() {
+        return Err(Error::Validation);
+    }
+
+    let page = cursor::page(filters.limit, filters.offset, filters.cursor.as_deref())?;
+
+    let list_filters = db::article::ListFilters {
+        tags: &tags,
+        tag_mode: filters.tag_mode,
+        exclude_tags: &exclude_tags,
+        authors: &authors,
+        favorited: favorited.as_ref(),
+        query,
+        created_after: filters.created_after,
+        created_before: filters.created_before,
+        min_reading_time: reading_time(filters.min_reading_time)?,
+        max_reading_time: reading_time(filters.max_reading_time)?,
+        sort,
+    };
+
+    let views =
+        db::article::query_articles(&mut cxn, user_ctx, &list_filters, &cursor::lookahead(&page))
+            .await?;
+
+    let (views, cursors) = cursor::paginate(views, &page, article_key);
+    let cursors = if sort.is_keyset() {
+        cursors
+    } else {
+        Cursors::default()
+    };
+
+    let articles = views.into_iter().map(Article::with_db_view).collect();
+
+    let articles_count = db::article::count_articles(&mut cxn, user_ctx, &list_filters).await?;
 
     Ok(Json(ArticlesBody {
         articles,
         articles_count,
+        cursors,
     }))
 }
 
-/// Handles the get user feed endpoint at `GET /api/articles/feed` which returns articles authored
-/// by users who the currently authenticted user follows.
+/// Handles the get user feed endpoint at `GET /api/articles/feed` which returns published articles
+/// authored by users who the currently authenticted user follows.
+///
+/// # Query Parameters
+///
+/// * `limit` - count of the articles that should be returned in the response
+/// * `offset` - offset into the total set of results to start the current result set
+/// * `cursor` - opaque cursor from the `nextCursor` or `prevCursor` of a previous response that
+///   selects the page adjacent to it, cannot be combined with `offset`
 ///
 /// # Response Body Format
 ///
@@ -442,18 +716,31 @@ async fn list_articles(
 ///     "title": "How to train your dragon",
 ///     "description": "Ever wonder how?",
 ///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
 ///     "tagList": ["dragons", "training"],
 ///     "createdAt": "2016-02-18T03:22:56.637Z",
 ///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
 ///     "favorited": false,
 ///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
 ///     "author": {
 ///       "username": "jake",
 ///       "bio": "I work at statefarm",
 ///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
 ///       "following": false
-///     }
-///   }]
+///     },
+///     "coauthors": []
+///   }],
+///   "articlesCount": 1,
+///   "nextCursor": null,
+///   "prevCursor": null
 /// }
 /// ```
 async fn user_feed(
@@ -461,20 +748,87 @@ async fn user_feed(
     auth_ctx: AuthContext,
     page: Query<Pagination>,
 ) -> Result<Json<ArticlesBody>, Error> {
+    let page = page.page()?;
+
     let mut cxn = ctx.db.acquire().await?;
 
-    let articles =
-        db::article::query_user_feed(&mut cxn, &auth_ctx.user_id, page.0.limit, page.0.offset)
-            .await?
-            .into_iter()
-            .map(Article::with_db_view)
-            .collect();
+    let views =
+        db::article::query_user_feed(&mut cxn, &auth_ctx.user_id, &cursor::lookahead(&page))
+            .await?;
+
+    let (views, cursors) = cursor::paginate(views, &page, article_key);
+
+    let articles = views.into_iter().map(Article::with_db_view).collect();
 
     let articles_count = db::article::count_user_feed(&mut cxn, &auth_ctx.user_id).await?;
 
     Ok(Json(ArticlesBody {
         articles,
         articles_count,
+        cursors,
+    }))
+}
+
+/// Handles the get user drafts endpoint at `GET /api/user/drafts` which returns the draft articles
+/// of the currently authenticated user, including those they have accepted an invitation to
+/// co-author.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "articles": [{
+///     "slug": "how-to-train-your-dragon",
+///     "title": "How to train your dragon",
+///     "description": "Ever wonder how?",
+///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
+///     "tagList": ["dragons", "training"],
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "draft",
+///     "publishedAt": null,
+///     "publishAt": null,
+///     "favorited": false,
+///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
+///     "author": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     },
+///     "coauthors": []
+///   }]
+/// }
+/// ```
+async fn user_drafts(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    page: Query<Pagination>,
+) -> Result<Json<ArticlesBody>, Error> {
+    let page = page.page()?;
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let views =
+        db::article::query_user_drafts(&mut cxn, &auth_ctx.user_id, &cursor::lookahead(&page))
+            .await?;
+
+    let (views, cursors) = cursor::paginate(views, &page, article_key);
+
+    let articles = views.into_iter().map(Article::with_db_view).collect();
+
+    let articles_count = db::article::count_user_drafts(&mut cxn, &auth_ctx.user_id).await?;
+
+    Ok(Json(ArticlesBody {
+        articles,
+        articles_count,
+        cursors,
     }))
 }
 
@@ -488,7 +842,8 @@ async fn user_feed(
 ///     "title": "How to train your dragon",
 ///     "description": "Ever wonder how?",
 ///     "body": "You have to believe",
-///     "tagList": ["reactjs", "angularjs", "dragons"]
+///     "tagList": ["reactjs", "angularjs", "dragons"],
+///     "status": "draft"
 ///   }
 /// }
 /// ```
@@ -497,8 +852,20 @@ async fn user_feed(
 ///
 /// * `title` - required
 /// * `description` - required
-/// * `body` - required
-/// * `tagList` - optional
+/// * `body` - required, Markdown which is rendered to sanitized HTML and returned as `bodyHtml`
+/// * `tagList` - optional, names are trimmed and lowercased, must not be longer than the configured
+///   maximum length, and must not contain control characters or any of the configured forbidden
+///   characters. Duplicate names are removed.
+/// * `status` - optional, one of `draft`, `published`, `scheduled` or `unlisted`. When not
+///   specified the default article visibility from the settings of the authenticated user is used.
+/// * `publishAt` - optional, time at which the article should be published. When the time is in the
+///   future the article is `scheduled` and remains hidden until a background task publishes it,
+///   otherwise the article is published immediately. May only be combined with the `scheduled` or
+///   `published` status.
+///
+/// The content of the article is recorded as its first revision. An `ARTICLE_CREATED` event is
+/// always published and, if the article is created with the `published` status, an
+/// `ARTICLE_PUBLISHED` event is published as well.
 ///
 /// # Response Body Format
 ///
@@ -509,17 +876,27 @@ async fn user_feed(
 ///     "title": "How to train your dragon",
 ///     "description": "Ever wonder how?",
 ///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
 ///     "tagList": ["dragons", "training"],
 ///     "createdAt": "2016-02-18T03:22:56.637Z",
 ///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
 ///     "favorited": false,
 ///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
 ///     "author": {
 ///       "username": "jake",
 ///       "bio": "I work at statefarm",
 ///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
 ///       "following": false
-///     }
+///     },
+///     "coauthors": []
 ///   }
 /// }
 /// ```
@@ -528,32 +905,54 @@ async fn create_article(
     auth_ctx: AuthContext,
     Json(request): Json<ArticleBody<CreateArticle>>,
 ) -> Result<Response, Error> {
+    let tags = tag::normalize_tags(
+        &ctx.config.tags,
+        request.article.tags.as_deref().unwrap_or_default(),
+    )?;
+
+    let rendered = markdown::render_body(&ctx.config.reading, &request.article.body).await?;
+
+    let mut tx = ctx.db.begin().await?;
+
+    let settings = db::user::query_user_settings(&mut tx, &auth_ctx.user_id).await?;
+
+    let (status, publish_at) = resolve_new_status(
+        request.article.status,
+        request.article.publish_at,
+        settings.default_article_visibility.into(),
+        Utc::now(),
+    )?;
+
     let create_article = db::article::CreateArticle {
         title: &request.article.title,
         description: &request.article.description,
         body: &request.article.body,
-        tags: request.article.tags.as_ref(),
+        body_html: &rendered.html,
+        word_count: rendered.word_count,
+        reading_time_minutes: rendered.reading_time_minutes,
+        tags: &tags,
+        status,
+        publish_at,
     };
 
-    let mut tx = ctx.db.begin().await?;
-
     let article = db::article::create_article(&mut tx, &auth_ctx.user_id, create_article)
         .await
         .map(Article::with_db_view)?;
 
-    let article_event = ArticleEvent::with_article(&article);
+    let create_revision = db::revision::CreateRevision {
+        user_id: &auth_ctx.user_id,
+        title: &article.title,
+        description: &article.description,
+        body: &article.body,
+    };
 
-    let mut headers = HashMap::with_capacity(1);
-    headers.insert(String::from("type"), String::from("ARTICLE_CREATED"));
+    let _ = db::revision::create_revision(&mut tx, &article.id, create_revision).await?;
 
-    let create_outbox_entry = db::outbox::CreateOutboxEntry {
-        topic: String::from("article"),
-        partition_key: Some(article_event.id.to_string()),
-        headers: Some(headers),
-        payload: Some(article_event),
-    };
+    create_article_outbox_entry(&mut tx, &article, "ARTICLE_CREATED").await?;
 
-    let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
+    if article.status == ArticleStatus::Published {
+        create_article_outbox_entry(&mut tx, &article, "ARTICLE_PUBLISHED").await?;
+    }
 
     tx.commit().await?;
 
@@ -576,6 +975,26 @@ async fn create_article(
 /// If the request is made unauthenticated, then the favorited and following metadata will always
 /// be set to `false`.
 ///
+/// Draft articles are only returned to their author, a 404 response is returned to everyone else.
+///
+/// Retrieving an article records a view of it, which is counted at most once per configured window
+/// for each user or anonymous visitor and not at all for the authors of the article. Views are
+/// aggregated asynchronously so the `viewsCount` of the article is not updated immediately.
+///
+/// If the article is part of a collection, then the response includes a `series` property with the
+/// position of the article in the collection and the previous and next articles, if any:
+///
+/// ```json
+/// "series": {
+///   "slug": "dragon-training",
+///   "title": "Dragon training",
+///   "position": 2,
+///   "length": 3,
+///   "previous": { "slug": "choosing-a-dragon", "title": "Choosing a dragon" },
+///   "next": { "slug": "flying-your-dragon", "title": "Flying your dragon" }
+/// }
+/// ```
+///
 /// # Response Body Format
 ///
 /// ```json
@@ -585,17 +1004,27 @@ async fn create_article(
 ///     "title": "How to train your dragon",
 ///     "description": "Ever wonder how?",
 ///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
 ///     "tagList": ["dragons", "training"],
 ///     "createdAt": "2016-02-18T03:22:56.637Z",
 ///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
 ///     "favorited": false,
 ///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
 ///     "author": {
 ///       "username": "jake",
 ///       "bio": "I work at statefarm",
 ///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
 ///       "following": false
-///     }
+///     },
+///     "coauthors": []
 ///   }
 /// }
 /// ```
@@ -603,18 +1032,23 @@ async fn get_article(
     ctx: State<AppContext>,
     auth_ctx: Option<AuthContext>,
     Path(slug): Path<String>,
+    headers: HeaderMap,
 ) -> Result<Response, Error> {
     let user_ctx = auth_ctx.map(|ac| ac.user_id);
 
     let mut tx = ctx.db.acquire().await?;
 
     match db::article::query_article_view_by_slug(&mut tx, &slug, user_ctx).await? {
-        None => Ok(StatusCode::NOT_FOUND.into_response()),
-        Some(db_view) => {
+        Some(db_view) if db_view.is_visible_to(user_ctx) => {
+            if let Err(e) = view::record_view(&ctx, &mut tx, &db_view, user_ctx, &headers).await {
+                tracing::error!("unable to record view of article {}: {}", db_view.id, e);
+            }
+
             let article = Article::with_db_view(db_view);
 
             Ok(Json(ArticleBody { article }).into_response())
         }
+        _ => Ok(StatusCode::NOT_FOUND.into_response()),
     }
 }
 
@@ -622,7 +1056,7 @@ async fn get_article(
 /// read the `slug` path parameter value and update the data for the matching article if it exists,
 /// otherwise it will return a 404 response.
 ///
-/// If the authenticated user is not the author of the article, then a 403 response is returned.
+/// If the authenticated user is not an author of the article, then a 403 response is returned.
 ///
 /// # Request Body Format
 ///
@@ -639,6 +1073,16 @@ async fn get_article(
 /// * `title`
 /// * `description`
 /// * `body`
+/// * `status`
+/// * `publishAt`
+///
+/// The `bodyHtml` of the article is rendered again from the Markdown body on every update.
+/// Every update records a new revision of the article content along with the user who made it.
+/// If the `status` of the article is changed to `published`, then an `ARTICLE_PUBLISHED` event is
+/// published in addition to the `ARTICLE_UPDATED` event. The `status` and `publishAt` fields follow
+/// the same rules as when creating an article and a 422 response is returned if they conflict. A
+/// `publishAt` without a `status` is also rejected for an article that is already published, since
+/// rescheduling it would hide the live article until then.
 ///
 /// # Response Body Format
 ///
@@ -649,17 +1093,27 @@ async fn get_article(
 ///     "title": "How to train your dragon",
 ///     "description": "Ever wonder how?",
 ///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
 ///     "tagList": ["dragons", "training"],
 ///     "createdAt": "2016-02-18T03:22:56.637Z",
 ///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
 ///     "favorited": false,
 ///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
 ///     "author": {
 ///       "username": "jake",
 ///       "bio": "I work at statefarm",
 ///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
 ///       "following": false
-///     }
+///     },
+///     "coauthors": []
 ///   }
 /// }
 ///
@@ -674,7 +1128,7 @@ async fn update_article(
     match db::article::query_article_by_slug(&mut tx, &slug).await? {
         None => Ok(StatusCode::NOT_FOUND.into_response()),
         Some(row) => {
-            if auth_ctx.user_id != row.user_id {
+            if !db::article::is_article_editor(&mut tx, &row.id, &auth_ctx.user_id).await? {
                 Ok(StatusCode::FORBIDDEN.into_response())
             } else {
                 let title = request.article.title.as_ref().unwrap_or(&row.title);
@@ -686,11 +1140,23 @@ async fn update_article(
                     .unwrap_or(&row.description);
 
                 let body = request.article.body.as_ref().unwrap_or(&row.body);
+                let rendered = markdown::render_body(&ctx.config.reading, body).await?;
+                let (status, publish_at) = resolve_status(
+                    request.article.status,
+                    request.article.publish_at,
+                    (row.status, row.publish_at),
+                    Utc::now(),
+                )?;
 
                 let update_article = db::article::UpdateArticle {
                     title,
                     description,
                     body,
+                    body_html: &rendered.html,
+                    word_count: rendered.word_count,
+                    reading_time_minutes: rendered.reading_time_minutes,
+                    status,
+                    publish_at,
                 };
 
                 let db_view = db::article::update_article(
@@ -701,21 +1167,22 @@ async fn update_article(
                 )
                 .await?;
 
-                let article = Article::with_db_view(db_view);
+                let create_revision = db::revision::CreateRevision {
+                    user_id: &auth_ctx.user_id,
+                    title,
+                    description,
+                    body,
+                };
 
-                let article_event = ArticleEvent::with_article(&article);
+                let _ = db::revision::create_revision(&mut tx, &row.id, create_revision).await?;
 
-                let mut headers = HashMap::with_capacity(1);
-                headers.insert(String::from("type"), String::from("ARTICLE_UPDATED"));
+                let article = Article::with_db_view(db_view);
 
-                let create_outbox_entry = db::outbox::CreateOutboxEntry {
-                    topic: String::from("article"),
-                    partition_key: Some(article_event.id.to_string()),
-                    headers: Some(headers),
-                    payload: Some(article_event),
-                };
+                create_article_outbox_entry(&mut tx, &article, "ARTICLE_UPDATED").await?;
 
-                let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
+                if row.status != ArticleStatus::Published && status == ArticleStatus::Published {
+                    create_article_outbox_entry(&mut tx, &article, "ARTICLE_PUBLISHED").await?;
+                }
 
                 tx.commit().await?;
 
@@ -732,12 +1199,47 @@ async fn update_article(
     }
 }
 
-/// Handles the delete article by slug API endpoint at `DELETE /api/articles/:slug`. The handler
-/// will read the `slug` path parameter value and delete the article and all associated data for
-/// the matching article if it exists and the authenticated user is the author. If the article does
-/// not exist then a 404 will be returned. If the authenticated user is not the author of the
-/// article then a 403 response will be returned.
-async fn delete_article(
+/// Handles the publish article API endpoint at `POST /api/articles/:slug/publish`. The handler
+/// will read the `slug` path parameter value and publish the matching article if it exists,
+/// otherwise it will return a 404 response. Publishing an article that is already published has no
+/// effect.
+///
+/// If the authenticated user is not an author of the article, then a 403 response is returned.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "article": {
+///     "slug": "how-to-train-your-dragon",
+///     "title": "How to train your dragon",
+///     "description": "Ever wonder how?",
+///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
+///     "tagList": ["dragons", "training"],
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:48:35.824Z",
+///     "publishAt": null,
+///     "favorited": false,
+///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
+///     "author": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     },
+///     "coauthors": []
+///   }
+/// }
+/// ```
+async fn publish_article(
     ctx: State<AppContext>,
     auth_ctx: AuthContext,
     Path(slug): Path<String>,
@@ -746,24 +1248,127 @@ async fn delete_article(
 
     match db::article::query_article_by_slug(&mut tx, &slug).await? {
         None => Ok(StatusCode::NOT_FOUND.into_response()),
-        Some(article) => {
-            if auth_ctx.user_id != article.user_id {
+        Some(row) => {
+            if !db::article::is_article_editor(&mut tx, &row.id, &auth_ctx.user_id).await? {
                 return Ok(StatusCode::FORBIDDEN.into_response());
             }
 
-            db::article::delete_article_by_id(&mut tx, &article.id).await?;
+            let user_ctx = Some(auth_ctx.user_id);
 
-            let mut headers = HashMap::with_capacity(1);
-            headers.insert(String::from("type"), String::from("ARTICLE_DELETED"));
+            if row.status == ArticleStatus::Published {
+                let article = db::article::query_article_view_by_slug(&mut tx, &slug, user_ctx)
+                    .await?
+                    .map(Article::with_db_view)
+                    .expect("article should exist");
 
-            let create_outbox_entry: CreateOutboxEntry<()> = db::outbox::CreateOutboxEntry {
-                topic: String::from("article"),
-                partition_key: Some(article.id.to_string()),
-                headers: Some(headers),
-                payload: None,
-            };
+                return Ok(Json(ArticleBody { article }).into_response());
+            }
 
-            let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
+            let article =
+                db::article::publish_article(&mut tx, &row.id, &row.slug, &auth_ctx.user_id)
+                    .await
+                    .map(Article::with_db_view)?;
+
+            create_article_outbox_entry(&mut tx, &article, "ARTICLE_PUBLISHED").await?;
+
+            tx.commit().await?;
+
+            match ctx.outbox_tx.send(()).await {
+                Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+                Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+            }
+
+            Ok(Json(ArticleBody { article }).into_response())
+        }
+    }
+}
+
+/// Resolves the status and scheduled publish time of an article from the values given in a request,
+/// falling back to the `current` values when neither is given. A publish time in the future
+/// schedules the article while a publish time that has already passed publishes it immediately.
+///
+/// An [`Error::Validation`] is returned if a publish time is given along with a status other than
+/// `scheduled` or `published`, if the `scheduled` status is given without a publish time, or if a
+/// publish time is given without a status for an article that is already published, which would
+/// otherwise hide the live article until the publish time.
+fn resolve_status(
+    status: Option<ArticleStatus>,
+    publish_at: Option<DateTime<Utc>>,
+    current: (ArticleStatus, Option<DateTime<Utc>>),
+    now: DateTime<Utc>,
+) -> Result<(ArticleStatus, Option<DateTime<Utc>>), Error> {
+    match (status, publish_at) {
+        (None, None) => Ok(current),
+        (Some(ArticleStatus::Scheduled), None) => Err(Error::Validation),
+        (Some(status), None) => Ok((status, None)),
+        (None, Some(_)) if current.0 == ArticleStatus::Published => Err(Error::Validation),
+        (None | Some(ArticleStatus::Scheduled | ArticleStatus::Published), Some(at)) => {
+            if at > now {
+                Ok((ArticleStatus::Scheduled, Some(at)))
+            } else {
+                Ok((ArticleStatus::Published, None))
+            }
+        }
+        (Some(_), Some(_)) => Err(Error::Validation),
+    }
+}
+
+/// Resolves the status and scheduled publish time of a new article from the values given when it is
+/// created, falling back to the `default` status when neither is given. Since a new article is not
+/// live yet, a publish time given without a status schedules it whatever the default status is.
+pub(super) fn resolve_new_status(
+    status: Option<ArticleStatus>,
+    publish_at: Option<DateTime<Utc>>,
+    default: ArticleStatus,
+    now: DateTime<Utc>,
+) -> Result<(ArticleStatus, Option<DateTime<Utc>>), Error> {
+    let status = status.or(publish_at.map(|_| ArticleStatus::Scheduled));
+
+    resolve_status(status, publish_at, (default, None), now)
+}
+
+/// Creates the outbox entry for an event of the given type, e.g. `ARTICLE_PUBLISHED`, whose payload
+/// is the [`ArticleEvent`] of the given [`Article`].
+pub(super) async fn create_article_outbox_entry(
+    cxn: &mut PgConnection,
+    article: &Article,
+    event_type: &str,
+) -> Result<(), sqlx::Error> {
+    event::article::create_article_outbox_entry(cxn, article_event(article), event_type).await
+}
+
+/// Handles the delete article by slug API endpoint at `DELETE /api/articles/:slug`. The handler
+/// will read the `slug` path parameter value and move the matching article to the trash if it
+/// exists and the authenticated user is the author. If the article does not exist then a 404 will
+/// be returned. If the authenticated user is not the author of the article then a 403 response will
+/// be returned.
+///
+/// An article in the trash is hidden everywhere but the trash of its author, who can restore it
+/// until it is purged along with all associated data once the configured retention period has
+/// passed. Its slug stays reserved until then. An `ARTICLE_TRASHED` event is published when the
+/// article is moved to the trash and the `ARTICLE_DELETED` event is only published once it is
+/// purged.
+async fn delete_article(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+) -> Result<Response, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    let user_ctx = Some(auth_ctx.user_id);
+
+    match db::article::query_article_view_by_slug(&mut tx, &slug, user_ctx).await? {
+        None => Ok(StatusCode::NOT_FOUND.into_response()),
+        Some(view) => {
+            if auth_ctx.user_id != view.author_id {
+                return Ok(StatusCode::FORBIDDEN.into_response());
+            }
+
+            db::article::trash_article(&mut tx, &view.id).await?;
+
+            let article = Article::with_db_view(view);
+
+            create_article_outbox_entry(&mut tx, &article, "ARTICLE_TRASHED").await?;
 
             tx.commit().await?;
 
@@ -777,6 +1382,83 @@ async fn delete_article(
     }
 }
 
+/// Handles the restore article API endpoint at `POST /api/articles/:slug/restore`. The handler will
+/// read the `slug` path parameter value and restore the matching article from the trash, returning
+/// it as it was before it was deleted. If there is no such article in the trash, either because it
+/// was never deleted or because it has been purged, then a 404 response is returned. If the
+/// authenticated user is not the author of the article then a 403 response is returned.
+///
+/// An `ARTICLE_RESTORED` event is published when the article is restored.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "article": {
+///     "slug": "how-to-train-your-dragon",
+///     "title": "How to train your dragon",
+///     "description": "Ever wonder how?",
+///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
+///     "tagList": ["dragons", "training"],
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
+///     "favorited": false,
+///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
+///     "author": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     },
+///     "coauthors": []
+///   }
+/// }
+/// ```
+async fn restore_article(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+) -> Result<Response, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    match db::article::query_trashed_article_by_slug(&mut tx, &slug).await? {
+        None => Ok(StatusCode::NOT_FOUND.into_response()),
+        Some(row) => {
+            if auth_ctx.user_id != row.user_id {
+                return Ok(StatusCode::FORBIDDEN.into_response());
+            }
+
+            db::article::restore_article(&mut tx, &row.id).await?;
+
+            let article =
+                db::article::query_article_view_by_slug(&mut tx, &slug, Some(auth_ctx.user_id))
+                    .await?
+                    .map(Article::with_db_view)
+                    .expect("article should exist");
+
+            create_article_outbox_entry(&mut tx, &article, "ARTICLE_RESTORED").await?;
+
+            tx.commit().await?;
+
+            match ctx.outbox_tx.send(()).await {
+                Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+                Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+            }
+
+            Ok(Json(ArticleBody { article }).into_response())
+        }
+    }
+}
+
 /// Handles the create article comment API endpoint at `POST /api/articles/:slug/comments`.
 ///
 /// # Request Body Format
@@ -818,8 +1500,8 @@ async fn create_comment(
 ) -> Result<Response, Error> {
     let mut tx = ctx.db.begin().await?;
 
-    match db::article::query_article_by_slug(&mut tx, &slug).await? {
-        None => Ok(StatusCode::NOT_FOUND.into_response()),
+    match db::article::query_visible_article_by_slug(&mut tx, &slug, Some(auth_ctx.user_id)).await?
+    {
         Some(article) => {
             let data = db::article::CreateComment {
                 user_id: &auth_ctx.user_id,
@@ -853,12 +1535,24 @@ async fn create_comment(
 
             Ok(Json(CommentBody { comment }).into_response())
         }
+        _ => Ok(StatusCode::NOT_FOUND.into_response()),
     }
 }
 
 /// Handles the get article comments API endpoint at `GET /api/articles/:slug/comments`. If there
 /// is an authentication context associated with the request then the comment author's profile will
-/// be populated based on the authenticated user.
+/// be populated based on the authenticated user. If the article does not exist or is a draft that
+/// the authenticated user did not author, then a 404 response is returned.
+///
+/// # Query Parameters
+///
+/// All of the comments are returned, oldest first, unless one of the following query parameters is
+/// given to select a single page of them.
+///
+/// * `limit` - count of the comments that should be returned in the response
+/// * `offset` - offset into the total set of comments to start the current result set
+/// * `cursor` - opaque cursor from the `nextCursor` or `prevCursor` of a previous response that
+///   selects the page adjacent to it, cannot be combined with `offset`
 ///
 /// # Response Body Format
 ///
@@ -874,28 +1568,71 @@ async fn create_comment(
 ///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
 ///       "following": false
 ///     }
-///   }]
+///   }],
+///   "nextCursor": null,
+///   "prevCursor": null
 /// }
 /// ```
 async fn get_comments(
     ctx: State<AppContext>,
     auth_ctx: Option<AuthContext>,
     Path(slug): Path<String>,
-) -> Result<Json<CommentsBody>, Error> {
+    Query(paging): Query<CommentPagination>,
+) -> Result<Response, Error> {
     let user_ctx = auth_ctx.map(|ac| ac.user_id);
 
+    let page = match paging {
+        CommentPagination {
+            limit: None,
+            offset: 0,
+            cursor: None,
+        } => None,
+        CommentPagination {
+            limit,
+            offset,
+            cursor,
+        } => Some(cursor::page(
+            limit.unwrap_or_else(crate::http::default_limit),
+            offset,
+            cursor.as_deref(),
+        )?),
+    };
+
     let mut cxn = ctx.db.acquire().await?;
 
-    let comments = db::article::query_article_comments_by_slug(&mut cxn, &slug, user_ctx)
-        .await?
-        .into_iter()
-        .map(Comment::with_db_view)
-        .collect();
+    match db::article::query_visible_article_by_slug(&mut cxn, &slug, user_ctx).await? {
+        Some(_) => {
+            let views = db::article::query_article_comments_by_slug(
+                &mut cxn,
+                &slug,
+                user_ctx,
+                page.as_ref().map(cursor::lookahead).as_ref(),
+            )
+            .await?;
+
+            let (views, cursors) = match page {
+                Some(page) => cursor::paginate(views, &page, |c| Keyset {
+                    created: c.created,
+                    id: c.id,
+                }),
+                None => (views, Cursors::default()),
+            };
+
+            let comments = views.into_iter().map(Comment::with_db_view).collect();
 
-    Ok(Json(CommentsBody { comments }))
+            Ok(Json(CommentsBody { comments, cursors }).into_response())
+        }
+        _ => Ok(StatusCode::NOT_FOUND.into_response()),
+    }
 }
 
 /// Handles the delete article comment API endpoint at `DELETE /api/articles/:slug/comments/:id`.
+/// The handler moves the comment to the trash of the authenticated user, who can restore it until
+/// it is purged once the configured retention period has passed. If the article does not exist or
+/// the authenticated user has no such comment on it, then a 404 response is returned.
+///
+/// A `COMMENT_TRASHED` event is published when the comment is moved to the trash and the
+/// `COMMENT_DELETED` event is only published once it is purged.
 async fn delete_comment(
     ctx: State<AppContext>,
     auth_ctx: AuthContext,
@@ -906,20 +1643,14 @@ async fn delete_comment(
     match db::article::query_article_by_slug(&mut tx, &slug).await? {
         None => Ok(StatusCode::NOT_FOUND.into_response()),
         Some(article) => {
-            // TODO: we could do better here by checking affected rows affected and returning 404 if zero
-            db::article::remove_article_comment(&mut tx, &id, &auth_ctx.user_id).await?;
-
-            let mut headers = HashMap::with_capacity(1);
-            headers.insert(String::from("type"), String::from("COMMENT_DELETED"));
-
-            let create_outbox_entry: CreateOutboxEntry<()> = db::outbox::CreateOutboxEntry {
-                topic: String::from("article"),
-                partition_key: Some(article.id.to_string()),
-                headers: Some(headers),
-                payload: None,
-            };
+            if !db::article::trash_article_comment(&mut tx, &id, &article.id, &auth_ctx.user_id)
+                .await?
+            {
+                return Ok(StatusCode::NOT_FOUND.into_response());
+            }
 
-            let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
+            event::article::create_comment_outbox_entry(&mut tx, &article.id, "COMMENT_TRASHED")
+                .await?;
 
             tx.commit().await?;
 
@@ -933,6 +1664,64 @@ async fn delete_comment(
     }
 }
 
+/// Handles the restore article comment API endpoint at
+/// `POST /api/articles/:slug/comments/:id/restore`. The handler restores the comment of the
+/// authenticated user from the trash and returns it. If the article does not exist or is itself in
+/// the trash, or the authenticated user has no such comment in the trash, then a 404 response is
+/// returned.
+///
+/// A `COMMENT_RESTORED` event is published when the comment is restored.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "comment": {
+///     "id": "f5a2a8b8-0f4c-4bd4-8b5e-1f6f0c8d7f0e",
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": "2016-02-18T03:22:56.637Z",
+///     "body": "It takes a Jacobian",
+///     "author": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     }
+///   }
+/// }
+/// ```
+async fn restore_comment(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path((slug, id)): Path<(String, Uuid)>,
+) -> Result<Response, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    let article = match db::article::query_article_by_slug(&mut tx, &slug).await? {
+        Some(article) => article,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    let comment =
+        match db::article::restore_article_comment(&mut tx, &id, &article.id, &auth_ctx.user_id)
+            .await?
+        {
+            Some(view) => Comment::with_db_view(view),
+            None => return Ok(StatusCode::NOT_FOUND.into_response()),
+        };
+
+    event::article::create_comment_outbox_entry(&mut tx, &article.id, "COMMENT_RESTORED").await?;
+
+    tx.commit().await?;
+
+    match ctx.outbox_tx.send(()).await {
+        Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+        Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+    }
+
+    Ok(Json(CommentBody { comment }).into_response())
+}
+
 /// Handles the favorite article API endpoint at `POST /api/articles/:slug/favorite`. The handler
 /// will read the `slug` path parameter value, favorite the article using the currently authenticated
 /// user and return the data for the matching article if it exists, otherwise it will return a 404
@@ -947,17 +1736,27 @@ async fn delete_comment(
 ///     "title": "How to train your dragon",
 ///     "description": "Ever wonder how?",
 ///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
 ///     "tagList": ["dragons", "training"],
 ///     "createdAt": "2016-02-18T03:22:56.637Z",
 ///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
 ///     "favorited": false,
 ///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
 ///     "author": {
 ///       "username": "jake",
 ///       "bio": "I work at statefarm",
 ///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
 ///       "following": false
-///     }
+///     },
+///     "coauthors": []
 ///   }
 /// }
 /// ```
@@ -971,8 +1770,8 @@ async fn favorite_article(
     // TODO: Handle case where favorite entry already exists with regard to publishing the event.
     // Currently an event will always be published whether the favorite already exists or not which
     // may be acceptable dependin on the use case.
-    match db::article::query_article_by_slug(&mut tx, &slug).await? {
-        None => Ok(StatusCode::NOT_FOUND.into_response()),
+    match db::article::query_visible_article_by_slug(&mut tx, &slug, Some(auth_ctx.user_id)).await?
+    {
         Some(article) => {
             let article =
                 db::article::add_article_favorite(&mut tx, &article.id, &auth_ctx.user_id)
@@ -1002,6 +1801,7 @@ async fn favorite_article(
 
             Ok(Json(ArticleBody { article }).into_response())
         }
+        _ => Ok(StatusCode::NOT_FOUND.into_response()),
     }
 }
 
@@ -1019,17 +1819,27 @@ async fn favorite_article(
 ///     "title": "How to train your dragon",
 ///     "description": "Ever wonder how?",
 ///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
 ///     "tagList": ["dragons", "training"],
 ///     "createdAt": "2016-02-18T03:22:56.637Z",
 ///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
 ///     "favorited": false,
 ///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
 ///     "author": {
 ///       "username": "jake",
 ///       "bio": "I work at statefarm",
 ///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
 ///       "following": false
-///     }
+///     },
+///     "coauthors": []
 ///   }
 /// }
 /// ```
@@ -1040,8 +1850,8 @@ async fn unfavorite_article(
 ) -> Result<Response, Error> {
     let mut tx = ctx.db.begin().await?;
 
-    match db::article::query_article_by_slug(&mut tx, &slug).await? {
-        None => Ok(StatusCode::NOT_FOUND.into_response()),
+    match db::article::query_visible_article_by_slug(&mut tx, &slug, Some(auth_ctx.user_id)).await?
+    {
         Some(article) => {
             let article =
                 db::article::remove_article_favorite(&mut tx, &article.id, &auth_ctx.user_id)
@@ -1071,5 +1881,96 @@ async fn unfavorite_article(
 
             Ok(Json(ArticleBody { article }).into_response())
         }
+        _ => Ok(StatusCode::NOT_FOUND.into_response()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    /// Verifies that the list filters that can be repeated are collected from the query string and
+    /// that a single value is still accepted.
+    #[tokio::test]
+    async fn verify_list_filters_repeated() {
+        let (mut parts, _) = http::Request::builder()
+            .uri("/api/articles?tag=rust&tag=axum&tagMode=any&excludeTag=draft&author=jake")
+            .body(())
+            .unwrap()
+            .into_parts();
+
+        let axum_extra::extract::Query(filters): axum_extra::extract::Query<ListFilters> =
+            axum::extract::FromRequestParts::from_request_parts(&mut parts, &())
+                .await
+                .unwrap();
+
+        assert_eq!(vec!["rust", "axum"], filters.tag);
+        assert_eq!(TagMode::Any, filters.tag_mode);
+        assert_eq!(vec!["draft"], filters.exclude_tag);
+        assert_eq!(vec!["jake"], filters.author);
+        assert_eq!(20, filters.limit);
+    }
+
+    /// Verifies that the text of a search snippet is escaped and the matching terms are marked.
+    #[test]
+    fn verify_highlight_snippet() {
+        assert_eq!(
+            "train your <mark>dragon</mark> &lt;b&gt;&amp;",
+            highlight_snippet("train your \u{2}dragon\u{3} <b>&")
+        );
+    }
+
+    /// Verifies that the status and publish time of an article are resolved from the request
+    /// values and that conflicting values are rejected.
+    #[test]
+    fn verify_resolve_status() {
+        let now = Utc::now();
+        let future = now + Duration::hours(1);
+        let past = now - Duration::hours(1);
+        let current = (ArticleStatus::Draft, None);
+
+        assert_eq!(
+            (ArticleStatus::Draft, None),
+            resolve_status(None, None, current, now).unwrap()
+        );
+        assert_eq!(
+            (ArticleStatus::Unlisted, None),
+            resolve_status(Some(ArticleStatus::Unlisted), None, current, now).unwrap()
+        );
+        assert_eq!(
+            (ArticleStatus::Scheduled, Some(future)),
+            resolve_status(None, Some(future), current, now).unwrap()
+        );
+        assert_eq!(
+            (ArticleStatus::Published, None),
+            resolve_status(Some(ArticleStatus::Scheduled), Some(past), current, now).unwrap()
+        );
+
+        assert!(resolve_status(Some(ArticleStatus::Scheduled), None, current, now).is_err());
+        assert!(resolve_status(Some(ArticleStatus::Draft), Some(future), current, now).is_err());
+    }
+
+    /// Verifies that a publish time alone cannot schedule an article that is already published,
+    /// while it does schedule a new article whatever the default status is.
+    #[test]
+    fn verify_resolve_status_of_published_article() {
+        let now = Utc::now();
+        let future = now + Duration::hours(1);
+        let current = (ArticleStatus::Published, None);
+
+        assert!(resolve_status(None, Some(future), current, now).is_err());
+        assert_eq!(
+            (ArticleStatus::Scheduled, Some(future)),
+            resolve_status(Some(ArticleStatus::Scheduled), Some(future), current, now).unwrap()
+        );
+        assert_eq!(
+            (ArticleStatus::Scheduled, Some(future)),
+            resolve_new_status(None, Some(future), ArticleStatus::Published, now).unwrap()
+        );
+        assert_eq!(
+            (ArticleStatus::Published, None),
+            resolve_new_status(None, None, ArticleStatus::Published, now).unwrap()
+        );
     }
 }
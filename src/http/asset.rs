@@ -0,0 +1,47 @@
+use crate::{
+    http::{AppContext, Error},
+    storage,
+};
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use http::{header, StatusCode};
+
+/// Creates the [`Router`] for the HTTP endpoints that serve the objects uploaded to the
+/// application and requires the [`AppContext`] to be the state type.
+///
+/// The following list enumerates the endpoints which are exposed by the `asset` API.
+///
+/// * `GET /assets/*key` - Returns the stored object identified by `key`.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new().route("/assets/*key", get(get_asset))
+}
+
+/// Handles the get asset endpoint at `GET /assets/*key`. The handler will read the object from the
+/// configured object store and return the raw data with the appropriate content type. If no object
+/// exists for the key then a 404 response is returned.
+///
+/// The keys of stored objects are unique per upload so the response can be cached indefinitely by
+/// clients and any intermediate caches.
+async fn get_asset(ctx: State<AppContext>, Path(key): Path<String>) -> Result<Response, Error> {
+    match ctx.store.get(&key).await {
+        Err(storage::Error::InvalidKey) => Ok(StatusCode::NOT_FOUND.into_response()),
+        Err(e) => Err(e.into()),
+        Ok(None) => Ok(StatusCode::NOT_FOUND.into_response()),
+        Ok(Some(object)) => Ok((
+            [
+                (header::CONTENT_TYPE, object.content_type),
+                (
+                    header::CACHE_CONTROL,
+                    String::from("public, max-age=31536000, immutable"),
+                ),
+            ],
+            object.data,
+        )
+            .into_response()),
+    }
+}
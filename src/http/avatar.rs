@@ -0,0 +1,175 @@
+use image::{imageops::FilterType, ImageError, ImageFormat, ImageReader, Limits};
+use std::io::Cursor;
+
+/// Names and square dimensions, in pixels, of the resized variants generated for an avatar.
+const VARIANTS: [(&str, u32); 3] = [("small", 64), ("medium", 128), ("large", 256)];
+
+/// Name of the variant that the `image` property of a user points to.
+pub const DEFAULT_VARIANT: &str = "large";
+
+/// Maximum width and height, in pixels, of an uploaded image. A small compressed upload can decode
+/// to a huge image so the dimensions are checked before any pixels are decoded.
+const MAX_DIMENSION: u32 = 4096;
+
+/// Maximum number of bytes the decoder may allocate while decoding an uploaded image, which leaves
+/// room for a full size image of [`MAX_DIMENSION`] with an alpha channel.
+const MAX_DECODE_ALLOC: u64 = 128 * 1024 * 1024;
+
+/// Enumerates the possible error states for the `avatar` module.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Occurs when the uploaded data is not an image in one of the supported formats.
+    #[error("unsupported image format")]
+    UnsupportedFormat,
+    /// Occurs when the uploaded data cannot be decoded as an image.
+    #[error("error decoding image")]
+    Decode,
+    /// Occurs when the uploaded image exceeds the dimensions or memory allowed for decoding it.
+    #[error("image too large")]
+    TooLarge,
+    /// Occurs when a resized variant of the image cannot be encoded.
+    #[error("error encoding image")]
+    Encode,
+}
+
+/// The [`Variant`] struct contains the encoded data of a resized variant of an avatar.
+#[derive(Debug)]
+pub struct Variant {
+    /// Name of the variant, e.g. `small`.
+    pub name: &'static str,
+    /// File extension for the format the variant is encoded in.
+    pub extension: &'static str,
+    /// MIME type for the format the variant is encoded in.
+    pub content_type: &'static str,
+    /// Encoded image data.
+    pub data: Vec<u8>,
+}
+
+/// Detects the format of the image data and returns it if it is one of the formats that is
+/// accepted for avatars.
+fn detect_format(data: &[u8]) -> Result<ImageFormat, Error> {
+    match image::guess_format(data) {
+        Ok(
+            format @ (ImageFormat::Gif | ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP),
+        ) => Ok(format),
+        _ => Err(Error::UnsupportedFormat),
+    }
+}
+
+/// Decodes the image data and generates a square, center cropped variant for each of the supported
+/// sizes encoded in the same format as the original image.
+fn resize(data: &[u8]) -> Result<Vec<Variant>, Error> {
+    let format = detect_format(data)?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+
+    let mut reader = ImageReader::with_format(Cursor::new(data), format);
+    reader.limits(limits);
+
+    let img = reader.decode().map_err(|e| match e {
+        ImageError::Limits(e) => {
+            tracing::debug!("image exceeds decoding limits: {}", e);
+            Error::TooLarge
+        }
+        e => {
+            tracing::debug!("error decoding image: {}", e);
+            Error::Decode
+        }
+    })?;
+
+    let extension = format.extensions_str().first().copied().unwrap_or("bin");
+
+    VARIANTS
+        .iter()
+        .map(|(name, size)| {
+            let mut encoded = Cursor::new(Vec::new());
+
+            img.resize_to_fill(*size, *size, FilterType::Lanczos3)
+                .write_to(&mut encoded, format)
+                .map_err(|e| {
+                    tracing::debug!("error encoding {} image variant: {}", name, e);
+                    Error::Encode
+                })?;
+
+            Ok(Variant {
+                name,
+                extension,
+                content_type: format.to_mime_type(),
+                data: encoded.into_inner(),
+            })
+        })
+        .collect()
+}
+
+/// Validates the uploaded avatar image data and generates the resized [`Variant`]s of it.
+///
+/// Decoding and resizing images is very CPU intensive so spawn a task to be run in the rayon thread
+/// pool which is good for that kind of work.
+pub async fn generate_variants(data: Vec<u8>) -> Result<Vec<Variant>, Error> {
+    // Validate the format before handing the work off so that obviously invalid uploads are
+    // rejected without touching the thread pool.
+    let _ = detect_format(&data)?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    rayon::spawn(move || {
+        if tx.send(resize(&data)).is_err() {
+            tracing::error!("failed to send avatar resize result over channel");
+        }
+    });
+
+    rx.await.map_err(|e| {
+        tracing::debug!("error resizing avatar: {}", e);
+        Error::Encode
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, GrayImage, RgbImage};
+
+    /// Verifies that a variant of each size is generated in the format of the uploaded image.
+    #[test]
+    fn verify_resize_generates_variants() {
+        let mut png = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(RgbImage::new(300, 200))
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+
+        let variants = resize(png.get_ref()).unwrap();
+        assert_eq!(3, variants.len());
+
+        for (variant, (name, size)) in variants.iter().zip(VARIANTS) {
+            assert_eq!(name, variant.name);
+            assert_eq!("png", variant.extension);
+            assert_eq!("image/png", variant.content_type);
+
+            let img = image::load_from_memory(&variant.data).unwrap();
+            assert_eq!((size, size), (img.width(), img.height()));
+        }
+    }
+
+    /// Verifies that an image whose dimensions exceed the limits is rejected before it is decoded.
+    #[test]
+    fn verify_oversized_image_rejected() {
+        let mut png = Cursor::new(Vec::new());
+        DynamicImage::ImageLuma8(GrayImage::new(MAX_DIMENSION + 1, 1))
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+
+        assert!(matches!(resize(png.get_ref()), Err(Error::TooLarge)));
+    }
+
+    /// Verifies that data which is not an image in a supported format is rejected.
+    #[test]
+    fn verify_unsupported_format_rejected() {
+        assert!(matches!(
+            detect_format(b"<svg></svg>"),
+            Err(Error::UnsupportedFormat)
+        ));
+    }
+}
@@ -0,0 +1,397 @@
+use crate::{
+    db,
+    db::Keyset,
+    http::{
+        article::{Article, ArticleBody},
+        auth::AuthContext,
+        cursor::{self, Cursors},
+        AppContext, Error, Pagination,
+    },
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+/// Maximum read progress of a bookmark, as a percentage of the article.
+const MAX_PROGRESS: i16 = 100;
+
+/// Creates the [`Router`] for the HTTP endpoints that correspond to the bookmarks of articles and
+/// requires the [`AppContext`] to be the state type. Unlike favorites, bookmarks are private to the
+/// user who made them. All of the endpoints require authentication.
+///
+/// The following list enumerates the endpoints which are exposed by the `bookmark` API.
+///
+/// * `GET /api/user/bookmarks` - Lists the bookmarks of the authenticated user, most recently
+///   bookmarked first.
+/// * `POST /api/articles/:slug/bookmark` - Bookmarks an article.
+/// * `PUT /api/articles/:slug/bookmark` - Sets the read progress of a bookmarked article.
+/// * `DELETE /api/articles/:slug/bookmark` - Removes an article from bookmarks.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new()
+        .route("/api/user/bookmarks", get(list_bookmarks))
+        .route(
+            "/api/articles/:slug/bookmark",
+            post(bookmark_article)
+                .put(update_bookmark)
+                .delete(unbookmark_article),
+        )
+}
+
+/// The [`Bookmark`] struct contains data that represents the bookmark of an article as returned
+/// from the API.
+#[derive(Debug, Serialize)]
+struct Bookmark {
+    /// Percentage of the article that the user has read.
+    progress: i16,
+    /// Time the article was bookmarked.
+    #[serde(rename = "createdAt")]
+    created: DateTime<Utc>,
+    /// Time the read progress was last updated.
+    #[serde(rename = "updatedAt")]
+    updated: Option<DateTime<Utc>>,
+    /// Bookmarked [`Article`].
+    article: Article,
+}
+
+impl Bookmark {
+    /// Creates a new [`Bookmark`] populated from the given [`crate::db::bookmark::BookmarkView`].
+    fn with_db_view(view: db::bookmark::BookmarkView) -> Self {
+        Self {
+            progress: view.bookmark_progress,
+            created: view.bookmark_created,
+            updated: view.bookmark_updated,
+            article: Article::with_db_view(view.article),
+        }
+    }
+}
+
+/// The [`BookmarkBody`] struct is the envelope in which data for a bookmark is returned to the
+/// client or accepted from the client.
+#[derive(Debug, Deserialize, Serialize)]
+struct BookmarkBody<T> {
+    /// Bookmark related data.
+    bookmark: T,
+}
+
+/// The [`BookmarksBody`] struct is the envelope in which multiple [`Bookmark`]s are returned to the
+/// client.
+#[derive(Debug, Serialize)]
+struct BookmarksBody {
+    /// Bookmarks that make up the response body.
+    bookmarks: Vec<Bookmark>,
+    /// Total count of the bookmarks of the user.
+    #[serde(rename = "bookmarksCount")]
+    bookmarks_count: i64,
+    /// Cursors that select the pages adjacent to the returned bookmarks.
+    #[serde(flatten)]
+    cursors: Cursors,
+}
+
+/// The [`UpdateBookmark`] struct contains the data received from the client to set the read
+/// progress of a bookmark.
+#[derive(Debug, Deserialize)]
+struct UpdateBookmark {
+    /// Percentage of the article that the user has read.
+    progress: i16,
+}
+
+/// Returns the position of the bookmark in listings ordered by the time articles were bookmarked.
+fn bookmark_key(view: &db::bookmark::BookmarkView) -> Keyset {
+    Keyset {
+        created: view.bookmark_created,
+        id: view.article.id,
+    }
+}
+
+/// Handles the list bookmarks API endpoint at `GET /api/user/bookmarks` which returns the
+/// bookmarks of the currently authenticated user, most recently bookmarked first. Bookmarks of
+/// articles that are no longer visible to the user are not included.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "bookmarks": [{
+///     "progress": 42,
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "article": {
+///       "slug": "how-to-train-your-dragon",
+///       "title": "How to train your dragon",
+///       "description": "Ever wonder how?",
+///       "body": "It takes a Jacobian",
+///       "bodyHtml": "<p>It takes a Jacobian</p>\n",
+///       "tagList": ["dragons", "training"],
+///       "createdAt": "2016-02-18T03:22:56.637Z",
+///       "updatedAt": "2016-02-18T03:48:35.824Z",
+///       "status": "published",
+///       "publishedAt": "2016-02-18T03:22:56.637Z",
+///       "publishAt": null,
+///       "favorited": false,
+///       "favoritesCount": 0,
+///       "commentsCount": 0,
+///       "viewsCount": 0,
+///       "wordCount": 4,
+///       "readingTimeMinutes": 1,
+///       "bookmarked": true,
+///       "author": {
+///         "username": "jake",
+///         "bio": "I work at statefarm",
+///         "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///         "following": false
+///       },
+///       "coauthors": []
+///     }
+///   }],
+///   "bookmarksCount": 1,
+///   "nextCursor": null,
+///   "prevCursor": null
+/// }
+/// ```
+async fn list_bookmarks(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    page: Query<Pagination>,
+) -> Result<Json<BookmarksBody>, Error> {
+    let page = page.page()?;
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let views =
+        db::bookmark::query_user_bookmarks(&mut cxn, &auth_ctx.user_id, &cursor::lookahead(&page))
+            .await?;
+
+    let (views, cursors) = cursor::paginate(views, &page, bookmark_key);
+
+    let bookmarks = views.into_iter().map(Bookmark::with_db_view).collect();
+
+    let bookmarks_count = db::bookmark::count_user_bookmarks(&mut cxn, &auth_ctx.user_id).await?;
+
+    Ok(Json(BookmarksBody {
+        bookmarks,
+        bookmarks_count,
+        cursors,
+    }))
+}
+
+/// Handles the bookmark article API endpoint at `POST /api/articles/:slug/bookmark`. The handler
+/// will read the `slug` path parameter value, bookmark the article for the currently authenticated
+/// user and return the data for the matching article if it exists, otherwise it will return a 404
+/// response. Bookmarking an article that is already bookmarked keeps its read progress.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "article": {
+///     "slug": "how-to-train-your-dragon",
+///     "title": "How to train your dragon",
+///     "description": "Ever wonder how?",
+///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
+///     "tagList": ["dragons", "training"],
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
+///     "favorited": false,
+///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": true,
+///     "author": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     },
+///     "coauthors": []
+///   }
+/// }
+/// ```
+async fn bookmark_article(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    match db::article::query_visible_article_by_slug(&mut cxn, &slug, Some(auth_ctx.user_id))
+        .await?
+    {
+        Some(article) => {
+            db::bookmark::add_bookmark(&mut cxn, &article.id, &auth_ctx.user_id).await?;
+
+            let article =
+                db::article::query_article_view_by_slug(&mut cxn, &slug, Some(auth_ctx.user_id))
+                    .await?
+                    .map(Article::with_db_view)
+                    .ok_or(Error::Internal)?;
+
+            Ok(Json(ArticleBody { article }).into_response())
+        }
+        _ => Ok(StatusCode::NOT_FOUND.into_response()),
+    }
+}
+
+/// Handles the update bookmark API endpoint at `PUT /api/articles/:slug/bookmark` which sets the
+/// read progress of an article that the currently authenticated user has bookmarked. If the article
+/// does not exist or has not been bookmarked, then a 404 response is returned.
+///
+/// # Request Body Format
+///
+/// ```json
+/// {
+///   "bookmark": {
+///     "progress": 42
+///   }
+/// }
+/// ```
+///
+/// # Field Validation
+///
+/// * `progress` - required, percentage of the article that has been read from `0` to `100`
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "bookmark": {
+///     "progress": 42,
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "article": {
+///       "slug": "how-to-train-your-dragon",
+///       "title": "How to train your dragon",
+///       "description": "Ever wonder how?",
+///       "body": "It takes a Jacobian",
+///       "bodyHtml": "<p>It takes a Jacobian</p>\n",
+///       "tagList": ["dragons", "training"],
+///       "createdAt": "2016-02-18T03:22:56.637Z",
+///       "updatedAt": "2016-02-18T03:48:35.824Z",
+///       "status": "published",
+///       "publishedAt": "2016-02-18T03:22:56.637Z",
+///       "publishAt": null,
+///       "favorited": false,
+///       "favoritesCount": 0,
+///       "commentsCount": 0,
+///       "viewsCount": 0,
+///       "wordCount": 4,
+///       "readingTimeMinutes": 1,
+///       "bookmarked": true,
+///       "author": {
+///         "username": "jake",
+///         "bio": "I work at statefarm",
+///         "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///         "following": false
+///       },
+///       "coauthors": []
+///     }
+///   }
+/// }
+/// ```
+async fn update_bookmark(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+    Json(request): Json<BookmarkBody<UpdateBookmark>>,
+) -> Result<Response, Error> {
+    let progress = request.bookmark.progress;
+    if !(0..=MAX_PROGRESS).contains(&progress) {
+        return Err(Error::Validation);
+    }
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let article =
+        match db::article::query_visible_article_by_slug(&mut cxn, &slug, Some(auth_ctx.user_id))
+            .await?
+        {
+            Some(article) => article,
+            _ => return Ok(StatusCode::NOT_FOUND.into_response()),
+        };
+
+    match db::bookmark::update_bookmark_progress(&mut cxn, &article.id, &auth_ctx.user_id, progress)
+        .await?
+    {
+        Some(view) => {
+            let bookmark = Bookmark::with_db_view(view);
+
+            Ok(Json(BookmarkBody { bookmark }).into_response())
+        }
+        None => Ok(StatusCode::NOT_FOUND.into_response()),
+    }
+}
+
+/// Handles the unbookmark article API endpoint at `DELETE /api/articles/:slug/bookmark`. The
+/// handler will read the `slug` path parameter value, remove the bookmark of the article for the
+/// currently authenticated user, along with its read progress, and return the data for the matching
+/// article if it exists, otherwise it will return a 404 response.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "article": {
+///     "slug": "how-to-train-your-dragon",
+///     "title": "How to train your dragon",
+///     "description": "Ever wonder how?",
+///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
+///     "tagList": ["dragons", "training"],
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
+///     "favorited": false,
+///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
+///     "author": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     },
+///     "coauthors": []
+///   }
+/// }
+/// ```
+async fn unbookmark_article(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    match db::article::query_visible_article_by_slug(&mut cxn, &slug, Some(auth_ctx.user_id))
+        .await?
+    {
+        Some(article) => {
+            db::bookmark::remove_bookmark(&mut cxn, &article.id, &auth_ctx.user_id).await?;
+
+            let article =
+                db::article::query_article_view_by_slug(&mut cxn, &slug, Some(auth_ctx.user_id))
+                    .await?
+                    .map(Article::with_db_view)
+                    .ok_or(Error::Internal)?;
+
+            Ok(Json(ArticleBody { article }).into_response())
+        }
+        _ => Ok(StatusCode::NOT_FOUND.into_response()),
+    }
+}
@@ -0,0 +1,658 @@
+use std::io::{Cursor, Read, Write};
+
+use crate::{
+    config::Bulk,
+    db::{self, article::ArticleStatus, bulk::ExportedArticle},
+    http::{
+        article::{self, Article},
+        auth::AuthContext,
+        markdown, tag, AppContext, Error,
+    },
+};
+
+use axum::{
+    body::Body,
+    extract::{DefaultBodyLimit, Query, State},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use futures::TryStreamExt;
+use http::{header, StatusCode};
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use uuid::Uuid;
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+/// Content type of the archive that articles are exported to and imported from.
+const ARCHIVE_CONTENT_TYPE: &str = "application/zip";
+
+/// Value of the `Content-Disposition` header of the exported archive.
+const ARCHIVE_CONTENT_DISPOSITION: &str = "attachment; filename=\"articles.zip\"";
+
+/// Extension of the files in an archive that contain articles.
+const ARTICLE_FILE_EXTENSION: &str = ".md";
+
+/// Line that opens and closes the front matter of an article file.
+const FRONT_MATTER_DELIMITER: &str = "---";
+
+/// Creates the [`Router`] for the HTTP endpoints that import and export the articles of the
+/// authenticated user in bulk and requires the [`AppContext`] to be the state type. The articles
+/// are exchanged as a zip archive containing a Markdown file for each article, which starts with
+/// YAML front matter holding the metadata of the article followed by its body. All of the
+/// endpoints require authentication.
+///
+/// The following list enumerates the endpoints which are exposed by the `bulk` API.
+///
+/// * `GET /api/user/articles/export` - Exports all of the articles of the user as a zip archive.
+/// * `POST /api/user/articles/import` - Imports the articles in a zip archive in the same format.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new()
+        .route("/api/user/articles/export", get(export_articles))
+        .route(
+            "/api/user/articles/import",
+            post(import_articles).layer(DefaultBodyLimit::disable()),
+        )
+}
+
+/// The [`FrontMatter`] struct contains the metadata of an article that precedes its body in an
+/// article file. Only the title is required when importing, any other fields that are not known,
+/// e.g. those written by other blogging tools, are ignored.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct FrontMatter {
+    /// Title of the article.
+    title: String,
+    /// Description of the article.
+    #[serde(default)]
+    description: String,
+    /// Slug of the article, which is derived from the title if not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    slug: Option<String>,
+    /// Names of the tags associated with the article.
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Publication status of the article, the default visibility of the user is used if not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<ArticleStatus>,
+    /// Time the article was created.
+    #[serde(rename = "createdAt", default, skip_serializing_if = "Option::is_none")]
+    created: Option<DateTime<Utc>>,
+    /// Time the article was last modified, which is informational only and not imported.
+    #[serde(rename = "updatedAt", default, skip_serializing_if = "Option::is_none")]
+    updated: Option<DateTime<Utc>>,
+    /// Time the article was first published.
+    #[serde(
+        rename = "publishedAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    published_at: Option<DateTime<Utc>>,
+    /// Time the article is scheduled to be published.
+    #[serde(rename = "publishAt", default, skip_serializing_if = "Option::is_none")]
+    publish_at: Option<DateTime<Utc>>,
+}
+
+/// The [`ArchiveFile`] struct contains an article file read from an imported archive.
+#[derive(Debug)]
+struct ArchiveFile {
+    /// Name of the file in the archive.
+    name: String,
+    /// Content of the file, or the reason that it could not be read.
+    content: Result<String, String>,
+}
+
+/// The [`ImportQuery`] struct contains the query parameters of an import.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ImportQuery {
+    /// Flag indicating whether the import should only report what would be imported without
+    /// importing anything.
+    #[serde(rename = "dryRun")]
+    dry_run: bool,
+}
+
+/// Enumerates the results of importing a single article file.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ImportResult {
+    /// The article was imported.
+    Created,
+    /// An article of the user already exists with the slug, so nothing was imported.
+    Skipped,
+    /// An article of another user, or one in the trash, already exists with the slug.
+    Conflict,
+    /// The file is not a valid article file.
+    Invalid,
+}
+
+/// The [`ImportedArticle`] struct contains the result of importing a single article file as
+/// returned from the API.
+#[derive(Debug, Serialize)]
+struct ImportedArticle {
+    /// Name of the file in the archive.
+    file: String,
+    /// Slug of the article, if the file was valid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slug: Option<String>,
+    /// Result of importing the file.
+    result: ImportResult,
+    /// Reason that the article was not imported, if it was a conflict or invalid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ImportedArticle {
+    /// Creates a new [`ImportedArticle`] for a file that is not a valid article file.
+    fn invalid(file: String, error: impl Into<String>) -> Self {
+        Self {
+            file,
+            slug: None,
+            result: ImportResult::Invalid,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// The [`ImportBody`] struct is the envelope in which the [`ImportedArticle`]s are returned to the
+/// client.
+#[derive(Debug, Serialize)]
+struct ImportBody {
+    /// Flag indicating whether the import was a dry run and nothing was imported.
+    #[serde(rename = "dryRun")]
+    dry_run: bool,
+    /// Results of importing each of the article files in the archive.
+    articles: Vec<ImportedArticle>,
+}
+
+/// Renders the article as an article file, i.e. its front matter followed by its body.
+fn render_article_file(article: &ExportedArticle) -> Result<String, serde_yaml::Error> {
+    let front_matter = FrontMatter {
+        title: article.title.clone(),
+        description: article.description.clone(),
+        slug: Some(article.slug.clone()),
+        tags: article.tags.clone(),
+        status: Some(article.status),
+        created: Some(article.created),
+        updated: article.updated,
+        published_at: article.published_at,
+        publish_at: article.publish_at,
+    };
+
+    let yaml = serde_yaml::to_string(&front_matter)?;
+
+    Ok(format!(
+        "{}\n{}{}\n\n{}",
+        FRONT_MATTER_DELIMITER, yaml, FRONT_MATTER_DELIMITER, article.body
+    ))
+}
+
+/// Parses an article file into its front matter and body. Any blank lines between the front matter
+/// and the body are not part of the body.
+fn parse_article_file(content: &str) -> Result<(FrontMatter, &str), String> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    let mut lines = content.split_inclusive('\n');
+    let start = match lines.next() {
+        Some(line) if line.trim_end() == FRONT_MATTER_DELIMITER => line.len(),
+        _ => return Err(String::from("file does not start with front matter")),
+    };
+
+    let mut end = start;
+    for line in lines {
+        if line.trim_end() == FRONT_MATTER_DELIMITER {
+            let front_matter = serde_yaml::from_str(&content[start..end])
+                .map_err(|e| format!("invalid front matter: {}", e))?;
+            let body = content[end + line.len()..].trim_start_matches(['\r', '\n']);
+
+            return Ok((front_matter, body));
+        }
+        end += line.len();
+    }
+
+    Err(String::from("front matter is not closed"))
+}
+
+/// Returns the time in the format stored in zip archives, if it can be represented.
+fn zip_datetime(time: DateTime<Utc>) -> Option<zip::DateTime> {
+    zip::DateTime::from_date_and_time(
+        u16::try_from(time.year()).ok()?,
+        time.month() as u8,
+        time.day() as u8,
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+    )
+    .ok()
+}
+
+/// Writes the articles to a zip archive with an article file for each, named after its slug.
+fn write_archive(articles: &[ExportedArticle]) -> Result<Vec<u8>, Error> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+    for article in articles {
+        let content = render_article_file(article).map_err(|e| {
+            tracing::error!("error rendering front matter of {}: {}", article.slug, e);
+            Error::Internal
+        })?;
+
+        let mut options = FileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .unix_permissions(0o644);
+        if let Some(modified) = zip_datetime(article.updated.unwrap_or(article.created)) {
+            options = options.last_modified_time(modified);
+        }
+
+        zip.start_file(
+            format!("{}{}", article.slug, ARTICLE_FILE_EXTENSION),
+            options,
+        )
+        .and_then(|_| zip.write_all(content.as_bytes()).map_err(Into::into))
+        .map_err(|e| {
+            tracing::error!("error writing {} to archive: {}", article.slug, e);
+            Error::Internal
+        })?;
+    }
+
+    let cursor = zip.finish().map_err(|e| {
+        tracing::error!("error finishing archive: {}", e);
+        Error::Internal
+    })?;
+
+    Ok(cursor.into_inner())
+}
+
+/// Reads the article files from a zip archive. Files in the archive that are not article files are
+/// ignored.
+///
+/// An [`Error::Validation`] is returned if the data is not a zip archive, if it contains more than
+/// the configured maximum number of article files, or if the article files are larger than the
+/// configured maximum import size combined.
+fn read_archive(config: &Bulk, data: Vec<u8>) -> Result<Vec<ArchiveFile>, Error> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).map_err(|e| {
+        tracing::debug!("error reading archive: {}", e);
+        Error::Validation
+    })?;
+
+    let mut files = Vec::new();
+    let mut remaining = config.max_import_size as u64;
+
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).map_err(|e| {
+            tracing::debug!("error reading file {} of archive: {}", i, e);
+            Error::Validation
+        })?;
+
+        // skip directories along with the resource forks that macOS adds to archives it creates
+        let name = file.name().to_owned();
+        if file.is_dir() || !name.ends_with(ARTICLE_FILE_EXTENSION) || name.starts_with("__MACOSX/")
+        {
+            continue;
+        }
+
+        if files.len() == config.max_import_articles {
+            tracing::debug!("archive contains more than {} articles", files.len());
+            return Err(Error::Validation);
+        }
+
+        // the size recorded in the archive cannot be trusted so the read is limited instead
+        let mut data = Vec::new();
+        let read = file
+            .take(remaining + 1)
+            .read_to_end(&mut data)
+            .map_err(|e| {
+                tracing::debug!("error reading {} from archive: {}", name, e);
+                Error::Validation
+            })? as u64;
+
+        if read > remaining {
+            tracing::debug!("articles in archive are larger than {} bytes", remaining);
+            return Err(Error::Validation);
+        }
+        remaining -= read;
+
+        let content = String::from_utf8(data).map_err(|_| String::from("file is not valid UTF-8"));
+
+        files.push(ArchiveFile { name, content });
+    }
+
+    Ok(files)
+}
+
+/// Runs the CPU heavy work of compressing or decompressing an archive on the `rayon` thread pool.
+async fn spawn_archive_task<T, F>(task: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Error> + Send + 'static,
+{
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    rayon::spawn(move || {
+        if tx.send(task()).is_err() {
+            tracing::error!("failed to send archive result over channel");
+        }
+    });
+
+    rx.await.map_err(|e| {
+        tracing::error!("error processing archive: {}", e);
+        Error::Internal
+    })?
+}
+
+/// Handles the export articles API endpoint at `GET /api/user/articles/export`. The handler returns
+/// a zip archive containing all of the articles that the currently authenticated user owns,
+/// including drafts and scheduled articles but not the articles in the trash. Each article is a
+/// Markdown file named after its slug which starts with YAML front matter holding the metadata of
+/// the article, followed by its body.
+///
+/// # Article File Format
+///
+/// ```markdown
+/// ---
+/// title: How to train your dragon
+/// description: Ever wonder how?
+/// slug: how-to-train-your-dragon
+/// tags:
+/// - dragons
+/// - training
+/// status: published
+/// createdAt: 2016-02-18T03:22:56.637Z
+/// updatedAt: 2016-02-18T03:48:35.824Z
+/// publishedAt: 2016-02-18T03:22:56.637Z
+/// ---
+///
+/// It takes a Jacobian
+/// ```
+async fn export_articles(ctx: State<AppContext>, auth_ctx: AuthContext) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    let articles = db::bulk::query_exported_articles(&mut cxn, &auth_ctx.user_id).await?;
+
+    drop(cxn);
+
+    let data = spawn_archive_task(move || write_archive(&articles)).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, ARCHIVE_CONTENT_TYPE),
+            (header::CONTENT_DISPOSITION, ARCHIVE_CONTENT_DISPOSITION),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+/// Handles the import articles API endpoint at `POST /api/user/articles/import`. The handler reads
+/// a zip archive of article files in the same format as the export from the request body and
+/// imports the articles as articles of the currently authenticated user. Files in the archive that
+/// do not have the `.md` extension are ignored.
+///
+/// The import is idempotent on the slug of each article, which is derived from the title if the
+/// front matter does not contain one. An article is only imported if no article exists with its
+/// slug, so importing the same archive again imports nothing. The times that an article was created
+/// and published are kept from the front matter, while its status defaults to the default visibility
+/// of the user if not given. Any tags that do not exist are created. Each imported article has its
+/// content recorded as its first revision and an `ARTICLE_CREATED` event is published, along with
+/// an `ARTICLE_PUBLISHED` event if it is imported with the `published` status.
+///
+/// All of the articles are imported in a single transaction. If the `dryRun` query parameter is
+/// `true`, then the transaction is rolled back so that the response reports what would have been
+/// imported without importing anything.
+///
+/// A 413 response is returned if the request body is larger than the configured maximum import
+/// size and a 422 response if it is not a zip archive, contains more article files than the
+/// configured maximum or the article files in it are too large combined. Invalid article files do
+/// not fail the import and are reported in the response instead.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "dryRun": false,
+///   "articles": [{
+///     "file": "how-to-train-your-dragon.md",
+///     "slug": "how-to-train-your-dragon",
+///     "result": "created"
+///   }, {
+///     "file": "drafts/welcome.md",
+///     "slug": "welcome",
+///     "result": "conflict",
+///     "error": "slug is used by another article"
+///   }, {
+///     "file": "notes.md",
+///     "result": "invalid",
+///     "error": "file does not start with front matter"
+///   }]
+/// }
+/// ```
+async fn import_articles(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Query(query): Query<ImportQuery>,
+    body: Body,
+) -> Result<Response, Error> {
+    let max_size = ctx.config.bulk.max_import_size;
+
+    let mut data = Vec::new();
+    let mut stream = body.into_data_stream();
+    while let Some(chunk) = stream.try_next().await.map_err(|e| {
+        tracing::debug!("error reading import request body: {}", e);
+        Error::Validation
+    })? {
+        if data.len() + chunk.len() > max_size {
+            return Ok(StatusCode::PAYLOAD_TOO_LARGE.into_response());
+        }
+
+        data.extend_from_slice(&chunk);
+    }
+
+    let config = ctx.config.clone();
+    let files = spawn_archive_task(move || read_archive(&config.bulk, data)).await?;
+
+    let mut tx = ctx.db.begin().await?;
+
+    let settings = db::user::query_user_settings(&mut tx, &auth_ctx.user_id).await?;
+
+    let now = Utc::now();
+    let mut articles = Vec::with_capacity(files.len());
+    for file in files {
+        let imported = import_article_file(
+            &ctx,
+            &mut tx,
+            &auth_ctx.user_id,
+            settings.default_article_visibility.into(),
+            now,
+            file,
+        )
+        .await?;
+
+        articles.push(imported);
+    }
+
+    if query.dry_run {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+
+        if articles.iter().any(|a| a.result == ImportResult::Created) {
+            match ctx.outbox_tx.send(()).await {
+                Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+                Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+            }
+        }
+    }
+
+    Ok(Json(ImportBody {
+        dry_run: query.dry_run,
+        articles,
+    })
+    .into_response())
+}
+
+/// Imports the article in an article file as an article of the user, unless an article with its
+/// slug already exists.
+async fn import_article_file(
+    ctx: &AppContext,
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+    default_status: ArticleStatus,
+    now: DateTime<Utc>,
+    file: ArchiveFile,
+) -> Result<ImportedArticle, Error> {
+    let content = match file.content {
+        Ok(content) => content,
+        Err(e) => return Ok(ImportedArticle::invalid(file.name, e)),
+    };
+
+    let (front_matter, body) = match parse_article_file(&content) {
+        Ok(parsed) => parsed,
+        Err(e) => return Ok(ImportedArticle::invalid(file.name, e)),
+    };
+
+    if front_matter.title.trim().is_empty() {
+        return Ok(ImportedArticle::invalid(
+            file.name,
+            "title must not be empty",
+        ));
+    }
+
+    let slug = match front_matter.slug {
+        Some(slug) if !slug.is_empty() && slug::slugify(&slug) == slug => slug,
+        Some(_) => return Ok(ImportedArticle::invalid(file.name, "slug is not valid")),
+        None => slug::slugify(&front_matter.title),
+    };
+
+    let tags = match tag::normalize_tags(&ctx.config.tags, &front_matter.tags) {
+        Ok(tags) => tags,
+        Err(_) => return Ok(ImportedArticle::invalid(file.name, "tags are not valid")),
+    };
+
+    let (status, publish_at) = match article::resolve_new_status(
+        front_matter.status,
+        front_matter.publish_at,
+        default_status,
+        now,
+    ) {
+        Ok(resolved) => resolved,
+        Err(_) => {
+            return Ok(ImportedArticle::invalid(
+                file.name,
+                "status does not match the publish time",
+            ))
+        }
+    };
+
+    let rendered = markdown::render_body(&ctx.config.reading, body).await?;
+
+    let import_article = db::article::ImportArticle {
+        slug: &slug,
+        title: &front_matter.title,
+        description: &front_matter.description,
+        body,
+        body_html: &rendered.html,
+        word_count: rendered.word_count,
+        reading_time_minutes: rendered.reading_time_minutes,
+        tags: &tags,
+        status,
+        created: front_matter.created,
+        published_at: front_matter.published_at,
+        publish_at,
+    };
+
+    let (result, error) = match db::article::import_article(cxn, user_id, import_article).await? {
+        Some(view) => {
+            let create_revision = db::revision::CreateRevision {
+                user_id,
+                title: &front_matter.title,
+                description: &front_matter.description,
+                body,
+            };
+
+            let _ = db::revision::create_revision(cxn, &view.id, create_revision).await?;
+
+            let article = Article::with_db_view(view);
+
+            article::create_article_outbox_entry(cxn, &article, "ARTICLE_CREATED").await?;
+
+            if status == ArticleStatus::Published {
+                article::create_article_outbox_entry(cxn, &article, "ARTICLE_PUBLISHED").await?;
+            }
+
+            (ImportResult::Created, None)
+        }
+        None => match db::article::query_article_by_slug(cxn, &slug).await? {
+            Some(existing) if existing.user_id == *user_id => (ImportResult::Skipped, None),
+            _ => (
+                ImportResult::Conflict,
+                Some(String::from("slug is used by another article")),
+            ),
+        },
+    };
+
+    Ok(ImportedArticle {
+        file: file.name,
+        slug: Some(slug),
+        result,
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that an exported article file is parsed back into the same front matter and body.
+    #[test]
+    fn verify_article_file_round_trip() {
+        let created = "2016-02-18T03:22:56.637Z".parse().unwrap();
+        let article = ExportedArticle {
+            slug: String::from("how-to-train-your-dragon"),
+            title: String::from("How to train your dragon: part 1"),
+            description: String::from("Ever wonder how?"),
+            body: String::from("It takes a Jacobian\n\n---\n\nOr two\n"),
+            tags: vec![String::from("dragons"), String::from("training")],
+            status: ArticleStatus::Published,
+            created,
+            updated: None,
+            published_at: Some(created),
+            publish_at: None,
+        };
+
+        let content = render_article_file(&article).unwrap();
+        let (front_matter, body) = parse_article_file(&content).unwrap();
+
+        assert_eq!(article.body, body);
+        assert_eq!(
+            FrontMatter {
+                title: article.title,
+                description: article.description,
+                slug: Some(article.slug),
+                tags: article.tags,
+                status: Some(ArticleStatus::Published),
+                created: Some(created),
+                updated: None,
+                published_at: Some(created),
+                publish_at: None,
+            },
+            front_matter
+        );
+    }
+
+    /// Verifies that only the title is required in the front matter, that unknown fields are
+    /// ignored and that files without closed front matter are rejected.
+    #[test]
+    fn verify_parse_article_file() {
+        let (front_matter, body) =
+            parse_article_file("---\r\ntitle: Hello\r\nlayout: post\r\n---\r\n# Hello\r\n")
+                .unwrap();
+        assert_eq!("Hello", front_matter.title);
+        assert_eq!("", front_matter.description);
+        assert!(front_matter.tags.is_empty());
+        assert_eq!(None, front_matter.status);
+        assert_eq!("# Hello\r\n", body);
+
+        assert!(parse_article_file("# Hello\n").is_err());
+        assert!(parse_article_file("---\ntitle: Hello\n").is_err());
+        assert!(parse_article_file("---\ndescription: Hi\n---\n").is_err());
+    }
+}
@@ -0,0 +1,440 @@
+use crate::{
+    db,
+    db::{
+        coauthor::{AuthorRole, InvitationStatus},
+        user::Profile,
+    },
+    http::{auth::AuthContext, profile, AppContext, Error},
+};
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+/// Creates the [`Router`] for the HTTP endpoints that correspond to the co-authors of articles and
+/// requires the [`AppContext`] to be the state type. The owner of an article invites other users to
+/// co-author it as editors, who are able to update and publish the article once they accept. The
+/// owner remains the primary author of the article and is the only one who can delete it. All of
+/// the endpoints require authentication.
+///
+/// The following list enumerates the endpoints which are exposed by the `coauthor` API.
+///
+/// * `GET /api/articles/:slug/coauthors` - Lists the authors of an article and pending invitations.
+/// * `POST /api/articles/:slug/coauthors` - Invites a user to co-author an article.
+/// * `DELETE /api/articles/:slug/coauthors/:username` - Removes a co-author from an article.
+/// * `POST /api/articles/:slug/invitation/accept` - Accepts an invitation to co-author an article.
+/// * `POST /api/articles/:slug/invitation/decline` - Declines an invitation to co-author an article.
+/// * `GET /api/user/coauthor-invitations` - Lists the pending invitations of the authenticated user.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new()
+        .route(
+            "/api/articles/:slug/coauthors",
+            get(list_coauthors).post(invite_coauthor),
+        )
+        .route(
+            "/api/articles/:slug/coauthors/:username",
+            delete(remove_coauthor),
+        )
+        .route(
+            "/api/articles/:slug/invitation/accept",
+            post(accept_invitation),
+        )
+        .route(
+            "/api/articles/:slug/invitation/decline",
+            post(decline_invitation),
+        )
+        .route("/api/user/coauthor-invitations", get(list_invitations))
+}
+
+/// The [`Coauthor`] struct contains data that represents an author of an article, or a user who
+/// has been invited to co-author it, as returned from the API.
+#[derive(Debug, Serialize)]
+struct Coauthor {
+    /// Public [`Profile`] of the user.
+    #[serde(flatten)]
+    profile: Profile,
+    /// Role of the user.
+    role: AuthorRole,
+    /// Status of the invitation of the user.
+    status: InvitationStatus,
+    /// Time the user was invited, or the article was created in the case of the owner.
+    #[serde(rename = "invitedAt")]
+    invited: DateTime<Utc>,
+    /// Time the user accepted the invitation.
+    #[serde(rename = "respondedAt")]
+    responded: Option<DateTime<Utc>>,
+}
+
+impl Coauthor {
+    /// Creates a new [`Coauthor`] populated from the given
+    /// [`crate::db::coauthor::ArticleAuthorView`].
+    fn with_db_view(view: db::coauthor::ArticleAuthorView) -> Self {
+        Self {
+            profile: Profile {
+                id: view.user_id,
+                name: view.user_name,
+                bio: view.user_bio,
+                image: view.user_image,
+                following: view.user_followed,
+            },
+            role: view.role,
+            status: view.status,
+            invited: view.created,
+            responded: view.responded,
+        }
+    }
+}
+
+/// The [`Invitation`] struct contains data that represents a pending invitation to co-author an
+/// article as returned from the API.
+#[derive(Debug, Serialize)]
+struct Invitation {
+    /// Slugified title of the article.
+    slug: String,
+    /// Title of the article.
+    title: String,
+    /// Public [`Profile`] of the user who sent the invitation.
+    inviter: Profile,
+    /// Time the invitation was sent.
+    #[serde(rename = "invitedAt")]
+    invited: DateTime<Utc>,
+}
+
+impl Invitation {
+    /// Creates a new [`Invitation`] populated from the given
+    /// [`crate::db::coauthor::InvitationView`].
+    fn with_db_view(view: db::coauthor::InvitationView) -> Self {
+        Self {
+            slug: view.article_slug,
+            title: view.article_title,
+            inviter: Profile {
+                id: view.inviter_id,
+                name: view.inviter_name,
+                bio: view.inviter_bio,
+                image: view.inviter_image,
+                following: view.inviter_followed,
+            },
+            invited: view.created,
+        }
+    }
+}
+
+/// The [`CoauthorBody`] struct is the envelope in which data for a co-author is returned to the
+/// client or accepted from the client.
+#[derive(Debug, Deserialize, Serialize)]
+struct CoauthorBody<T> {
+    /// Co-author related data.
+    coauthor: T,
+}
+
+/// The [`CoauthorsBody`] struct is the envelope in which multiple [`Coauthor`]s are returned to the
+/// client.
+#[derive(Debug, Serialize)]
+struct CoauthorsBody {
+    /// Authors of the article and users who have been invited to co-author it.
+    coauthors: Vec<Coauthor>,
+}
+
+/// The [`InvitationsBody`] struct is the envelope in which multiple [`Invitation`]s are returned to
+/// the client.
+#[derive(Debug, Serialize)]
+struct InvitationsBody {
+    /// Pending invitations of the user.
+    invitations: Vec<Invitation>,
+}
+
+/// The [`InviteCoauthor`] struct contains the data received from the client to invite a user to
+/// co-author an article.
+#[derive(Debug, Deserialize)]
+struct InviteCoauthor {
+    /// Username of the user to invite.
+    username: String,
+}
+
+/// Handles the list co-authors API endpoint at `GET /api/articles/:slug/coauthors` which returns
+/// the owner of the article followed by the editors and pending invitations in the order they were
+/// sent. Only the authors of the article can list its co-authors, otherwise a 403 response is
+/// returned if the article is visible to the user and a 404 response if it is not.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "coauthors": [{
+///     "username": "jake",
+///     "bio": "I work at statefarm",
+///     "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///     "following": false,
+///     "role": "owner",
+///     "status": "accepted",
+///     "invitedAt": "2016-02-18T03:22:56.637Z",
+///     "respondedAt": null
+///   }, {
+///     "username": "jane",
+///     "bio": "I work at allstate",
+///     "image": null,
+///     "following": false,
+///     "role": "editor",
+///     "status": "pending",
+///     "invitedAt": "2016-02-18T03:48:35.824Z",
+///     "respondedAt": null
+///   }]
+/// }
+/// ```
+async fn list_coauthors(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    let article = match db::article::query_article_by_slug(&mut cxn, &slug).await? {
+        Some(article) => article,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    if !db::article::is_article_editor(&mut cxn, &article.id, &auth_ctx.user_id).await? {
+        return match article.is_public() {
+            true => Ok(StatusCode::FORBIDDEN.into_response()),
+            false => Ok(StatusCode::NOT_FOUND.into_response()),
+        };
+    }
+
+    let coauthors = db::coauthor::query_article_authors(&mut cxn, &article.id, &auth_ctx.user_id)
+        .await?
+        .into_iter()
+        .map(Coauthor::with_db_view)
+        .collect();
+
+    Ok(Json(CoauthorsBody { coauthors }).into_response())
+}
+
+/// Handles the invite co-author API endpoint at `POST /api/articles/:slug/coauthors`. Only the
+/// owner of the article can invite users to co-author it, otherwise a 403 response is returned.
+/// Inviting a user who is already an author of the article or who has a pending invitation has no
+/// effect. The invited user must accept the invitation before they are able to edit the article.
+///
+/// # Request Body Format
+///
+/// ```json
+/// {
+///   "coauthor": {
+///     "username": "jane"
+///   }
+/// }
+/// ```
+///
+/// # Field Validation
+///
+/// * `username` - required, username of an existing user other than the owner of the article
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "coauthor": {
+///     "username": "jane",
+///     "bio": "I work at allstate",
+///     "image": null,
+///     "following": false,
+///     "role": "editor",
+///     "status": "pending",
+///     "invitedAt": "2016-02-18T03:48:35.824Z",
+///     "respondedAt": null
+///   }
+/// }
+/// ```
+async fn invite_coauthor(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+    Json(request): Json<CoauthorBody<InviteCoauthor>>,
+) -> Result<Response, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    let article = match db::article::query_article_by_slug(&mut tx, &slug).await? {
+        Some(article) => article,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    if article.user_id != auth_ctx.user_id {
+        let user_ctx = Some(auth_ctx.user_id);
+        return match db::article::is_article_visible(&mut tx, &article, user_ctx).await? {
+            true => Ok(StatusCode::FORBIDDEN.into_response()),
+            false => Ok(StatusCode::NOT_FOUND.into_response()),
+        };
+    }
+
+    let username = profile::resolve_username(&mut tx, &ctx, request.coauthor.username).await?;
+
+    let user = match db::user::query_profile_by_username(&mut tx, &username, None).await? {
+        Some(user) if user.id != article.user_id => user,
+        _ => return Err(Error::Validation),
+    };
+
+    db::coauthor::invite_coauthor(&mut tx, &article.id, &user.id, &auth_ctx.user_id).await?;
+
+    let coauthor = db::coauthor::query_article_authors(&mut tx, &article.id, &auth_ctx.user_id)
+        .await?
+        .into_iter()
+        .find(|view| view.user_id == user.id)
+        .map(Coauthor::with_db_view)
+        .ok_or(Error::Internal)?;
+
+    tx.commit().await?;
+
+    Ok(Json(CoauthorBody { coauthor }).into_response())
+}
+
+/// Handles the remove co-author API endpoint at `DELETE /api/articles/:slug/coauthors/:username`.
+/// The owner of the article can remove any editor or withdraw a pending invitation, while an editor
+/// can only remove themselves. The owner of the article cannot be removed. If the user is not an
+/// editor of the article then a 404 response is returned.
+async fn remove_coauthor(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path((slug, username)): Path<(String, String)>,
+) -> Result<Response, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    let article = match db::article::query_article_by_slug(&mut tx, &slug).await? {
+        Some(article) => article,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    let username = profile::resolve_username(&mut tx, &ctx, username).await?;
+
+    let user = match db::user::query_profile_by_username(&mut tx, &username, None).await? {
+        Some(user) => user,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    if article.user_id != auth_ctx.user_id && user.id != auth_ctx.user_id {
+        let user_ctx = Some(auth_ctx.user_id);
+        return match db::article::is_article_visible(&mut tx, &article, user_ctx).await? {
+            true => Ok(StatusCode::FORBIDDEN.into_response()),
+            false => Ok(StatusCode::NOT_FOUND.into_response()),
+        };
+    }
+
+    if !db::coauthor::remove_coauthor(&mut tx, &article.id, &user.id).await? {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// Handles the accept invitation API endpoint at `POST /api/articles/:slug/invitation/accept`.
+/// Once accepted, the authenticated user is listed as a co-author of the article and is able to
+/// edit it. If the user does not have a pending invitation to co-author the article then a 404
+/// response is returned.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "coauthor": {
+///     "username": "jane",
+///     "bio": "I work at allstate",
+///     "image": null,
+///     "following": false,
+///     "role": "editor",
+///     "status": "accepted",
+///     "invitedAt": "2016-02-18T03:48:35.824Z",
+///     "respondedAt": "2016-02-18T04:12:08.213Z"
+///   }
+/// }
+/// ```
+async fn accept_invitation(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+) -> Result<Response, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    let article = match db::article::query_article_by_slug(&mut tx, &slug).await? {
+        Some(article) => article,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    if !db::coauthor::accept_invitation(&mut tx, &article.id, &auth_ctx.user_id).await? {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    let coauthor = db::coauthor::query_article_authors(&mut tx, &article.id, &auth_ctx.user_id)
+        .await?
+        .into_iter()
+        .find(|view| view.user_id == auth_ctx.user_id)
+        .map(Coauthor::with_db_view)
+        .ok_or(Error::Internal)?;
+
+    tx.commit().await?;
+
+    Ok(Json(CoauthorBody { coauthor }).into_response())
+}
+
+/// Handles the decline invitation API endpoint at `POST /api/articles/:slug/invitation/decline`.
+/// The pending invitation of the authenticated user to co-author the article is removed. If the
+/// user does not have a pending invitation to co-author the article then a 404 response is
+/// returned.
+async fn decline_invitation(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    let article = match db::article::query_article_by_slug(&mut cxn, &slug).await? {
+        Some(article) => article,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    match db::coauthor::decline_invitation(&mut cxn, &article.id, &auth_ctx.user_id).await? {
+        true => Ok(StatusCode::NO_CONTENT.into_response()),
+        false => Ok(StatusCode::NOT_FOUND.into_response()),
+    }
+}
+
+/// Handles the list invitations API endpoint at `GET /api/user/coauthor-invitations` which returns
+/// the pending invitations of the currently authenticated user to co-author articles, most recent
+/// first.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "invitations": [{
+///     "slug": "how-to-train-your-dragon",
+///     "title": "How to train your dragon",
+///     "inviter": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     },
+///     "invitedAt": "2016-02-18T03:48:35.824Z"
+///   }]
+/// }
+/// ```
+async fn list_invitations(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+) -> Result<Json<InvitationsBody>, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    let invitations = db::coauthor::query_user_invitations(&mut cxn, &auth_ctx.user_id)
+        .await?
+        .into_iter()
+        .map(Invitation::with_db_view)
+        .collect();
+
+    Ok(Json(InvitationsBody { invitations }))
+}
@@ -0,0 +1,572 @@
+use std::collections::HashMap;
+
+use crate::{
+    db,
+    db::{user::Profile, Keyset},
+    http::{
+        article::Article,
+        auth::AuthContext,
+        cursor::{self, Cursors},
+        profile, AppContext, Error,
+    },
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Creates the [`Router`] for the HTTP endpoints that correspond to the `collection` domain and
+/// requires the [`AppContext`] to be the state type. A collection is an ordered series of articles
+/// put together by their author, such as a multi-part tutorial.
+///
+/// The following list enumerates the endpoints which are exposed by the `collection` API.
+///
+/// * `GET /api/collections` - Lists collections, optionally only those of an author, ordered by the
+///   most recent first.
+/// * `POST /api/collections` - Authentication required, creates a new collection.
+/// * `GET /api/collections/:slug` - Returns a single collection along with its articles.
+/// * `PUT /api/collections/:slug` - Authentication required, updates an existing collection.
+/// * `DELETE /api/collections/:slug` - Authentication required, deletes an existing collection.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new()
+        .route(
+            "/api/collections",
+            get(list_collections).post(create_collection),
+        )
+        .route(
+            "/api/collections/:slug",
+            get(get_collection)
+                .put(update_collection)
+                .delete(delete_collection),
+        )
+}
+
+/// The [`Collection`] struct contains data that represents a collection as returned from the API.
+#[derive(Debug, Serialize)]
+struct Collection {
+    /// Slugified title of the collection.
+    slug: String,
+    /// Title of the collection.
+    title: String,
+    /// Description of the collection.
+    description: String,
+    /// Time the collection was created.
+    #[serde(rename = "createdAt")]
+    created: DateTime<Utc>,
+    /// Time the collection was last modified.
+    #[serde(rename = "updatedAt")]
+    updated: Option<DateTime<Utc>>,
+    /// Count of the articles in the collection that are visible to the user.
+    #[serde(rename = "articlesCount")]
+    articles_count: i64,
+    /// Articles of the collection in order. Not included when listing collections.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    articles: Option<Vec<Article>>,
+    /// Public [`Profile`] of the user who owns the collection.
+    author: Profile,
+}
+
+impl Collection {
+    /// Creates a new [`Collection`] populated from the given
+    /// [`crate::db::collection::CollectionView`] without its articles.
+    fn with_db_view(view: db::collection::CollectionView) -> Self {
+        Self {
+            slug: view.slug,
+            title: view.title,
+            description: view.description,
+            created: view.created,
+            updated: view.updated,
+            articles_count: view.articles_count,
+            articles: None,
+            author: Profile {
+                id: view.author_id,
+                name: view.author_name,
+                bio: view.author_bio,
+                image: view.author_image,
+                following: view.author_followed,
+            },
+        }
+    }
+}
+
+/// The [`CollectionBody`] struct is the envelope in which different data for a collection is
+/// returned to the client or accepted from the client.
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionBody<T> {
+    /// Collection related data.
+    collection: T,
+}
+
+/// The [`CollectionsBody`] struct is the envelope in which multiple [`Collection`]s are returned to
+/// the client.
+#[derive(Debug, Serialize)]
+struct CollectionsBody {
+    /// Collections that make up the response body.
+    collections: Vec<Collection>,
+    /// Total count of the collections matching any filters.
+    #[serde(rename = "collectionsCount")]
+    collections_count: i64,
+    /// Cursors that select the pages adjacent to the returned collections.
+    #[serde(flatten)]
+    cursors: Cursors,
+}
+
+/// The [`CreateCollection`] struct contains the data received from the HTTP request to create a new
+/// collection.
+#[derive(Debug, Deserialize)]
+struct CreateCollection {
+    /// Title of the collection.
+    title: String,
+    /// Description of the collection.
+    description: String,
+    /// Slugs of the articles of the collection in order.
+    #[serde(default)]
+    articles: Vec<String>,
+}
+
+/// The [`UpdateCollection`] struct contains the data received from the HTTP request to update an
+/// existing collection.
+#[derive(Debug, Deserialize)]
+struct UpdateCollection {
+    /// New title of the collection.
+    title: Option<String>,
+    /// Description of the collection.
+    description: Option<String>,
+    /// Slugs of the articles of the collection in order, replacing the existing articles.
+    articles: Option<Vec<String>>,
+}
+
+/// The [`CollectionFilters`] struct contains the filters and paging parameters available to the
+/// list collections API.
+#[derive(Debug, Deserialize)]
+struct CollectionFilters {
+    /// Name of the user who owns the collection.
+    author: Option<String>,
+    /// Maximum number of results to return for a single request.
+    #[serde(default = "crate::http::default_limit")]
+    limit: i32,
+    /// Starting offset into the entire set of results.
+    #[serde(default)]
+    offset: i32,
+    /// Opaque cursor returned with a previous page of results that selects an adjacent page.
+    cursor: Option<String>,
+}
+
+/// Returns the position of the collection in listings ordered by the time collections were created.
+fn collection_key(view: &db::collection::CollectionView) -> Keyset {
+    Keyset {
+        created: view.created,
+        id: view.id,
+    }
+}
+
+/// Resolves the slugs of the articles of a collection to their ids in the same order. Returns a
+/// validation error if a slug is repeated, does not identify an article authored by the user, or
+/// identifies an article that is already part of another collection.
+async fn resolve_collection_articles(
+    cxn: &mut PgConnection,
+    user_id: &Uuid,
+    collection_id: Option<&Uuid>,
+    slugs: &[String],
+) -> Result<Vec<Uuid>, Error> {
+    let candidates: HashMap<String, db::collection::CollectionCandidate> =
+        db::collection::query_collection_candidates(cxn, slugs)
+            .await?
+            .into_iter()
+            .map(|c| (c.slug.clone(), c))
+            .collect();
+
+    let mut ids = Vec::with_capacity(slugs.len());
+    for slug in slugs {
+        match candidates.get(slug) {
+            Some(c)
+                if c.user_id == *user_id
+                    && (c.collection_id.is_none() || c.collection_id.as_ref() == collection_id)
+                    && !ids.contains(&c.id) =>
+            {
+                ids.push(c.id)
+            }
+            _ => return Err(Error::Validation),
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Queries the [`Collection`] with the given slug along with its articles that are visible to the
+/// user, if it exists.
+async fn query_collection(
+    cxn: &mut PgConnection,
+    slug: &str,
+    user_ctx: Option<Uuid>,
+) -> Result<Option<Collection>, Error> {
+    let view = match db::collection::query_collection_view_by_slug(cxn, slug, user_ctx).await? {
+        Some(view) => view,
+        None => return Ok(None),
+    };
+
+    let articles = db::collection::query_collection_articles(cxn, &view.id, user_ctx)
+        .await?
+        .into_iter()
+        .map(Article::with_db_view)
+        .collect();
+
+    Ok(Some(Collection {
+        articles: Some(articles),
+        ..Collection::with_db_view(view)
+    }))
+}
+
+/// Handles the list collections API endpoint at `GET /api/collections`. The `author` query
+/// parameter can be used to only list the collections of a user.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "collections": [{
+///     "slug": "dragon-training",
+///     "title": "Dragon training",
+///     "description": "Everything you need to know",
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": null,
+///     "articlesCount": 3,
+///     "author": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     }
+///   }],
+///   "collectionsCount": 1,
+///   "nextCursor": null,
+///   "prevCursor": null
+/// }
+/// ```
+async fn list_collections(
+    ctx: State<AppContext>,
+    auth_ctx: Option<AuthContext>,
+    Query(filters): Query<CollectionFilters>,
+) -> Result<Json<CollectionsBody>, Error> {
+    let user_ctx = auth_ctx.map(|ac| ac.user_id);
+
+    let page = cursor::page(filters.limit, filters.offset, filters.cursor.as_deref())?;
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let author = match filters.author {
+        Some(author) => Some(profile::resolve_username(&mut cxn, &ctx, author).await?),
+        None => None,
+    };
+
+    let views = db::collection::query_collections(
+        &mut cxn,
+        user_ctx,
+        author.as_deref(),
+        &cursor::lookahead(&page),
+    )
+    .await?;
+
+    let (views, cursors) = cursor::paginate(views, &page, collection_key);
+
+    let collections = views.into_iter().map(Collection::with_db_view).collect();
+
+    let collections_count = db::collection::count_collections(&mut cxn, author.as_deref()).await?;
+
+    Ok(Json(CollectionsBody {
+        collections,
+        collections_count,
+        cursors,
+    }))
+}
+
+/// Handles the create collection API endpoint at `POST /api/collections`.
+///
+/// # Request Body Format
+///
+/// ```json
+/// {
+///   "collection": {
+///     "title": "Dragon training",
+///     "description": "Everything you need to know",
+///     "articles": ["choosing-a-dragon", "how-to-train-your-dragon"]
+///   }
+/// }
+/// ```
+///
+/// # Field Validation
+///
+/// * `title` - required
+/// * `description` - required
+/// * `articles` - optional, slugs of the articles of the collection in order. Each article must be
+///   authored by the authenticated user, may only be listed once and must not already be part of
+///   another collection.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "collection": {
+///     "slug": "dragon-training",
+///     "title": "Dragon training",
+///     "description": "Everything you need to know",
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": null,
+///     "articlesCount": 2,
+///     "articles": [{
+///       "slug": "choosing-a-dragon",
+///       "title": "Choosing a dragon",
+///       "description": "Ever wonder which?",
+///       "body": "It takes a Jacobian",
+///       "bodyHtml": "<p>It takes a Jacobian</p>\n",
+///       "tagList": ["dragons", "training"],
+///       "createdAt": "2016-02-18T03:22:56.637Z",
+///       "updatedAt": "2016-02-18T03:48:35.824Z",
+///       "status": "published",
+///       "publishedAt": "2016-02-18T03:22:56.637Z",
+///       "publishAt": null,
+///       "favorited": false,
+///       "favoritesCount": 0,
+///       "commentsCount": 0,
+///       "viewsCount": 0,
+///       "wordCount": 4,
+///       "readingTimeMinutes": 1,
+///       "bookmarked": false,
+///       "series": {
+///         "slug": "dragon-training",
+///         "title": "Dragon training",
+///         "position": 1,
+///         "length": 2,
+///         "previous": null,
+///         "next": { "slug": "how-to-train-your-dragon", "title": "How to train your dragon" }
+///       },
+///       "author": {
+///         "username": "jake",
+///         "bio": "I work at statefarm",
+///         "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///         "following": false
+///       },
+///       "coauthors": []
+///     }],
+///     "author": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     }
+///   }
+/// }
+/// ```
+async fn create_collection(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Json(request): Json<CollectionBody<CreateCollection>>,
+) -> Result<Response, Error> {
+    if slug::slugify(&request.collection.title).is_empty() {
+        return Err(Error::Validation);
+    }
+
+    let mut tx = ctx.db.begin().await?;
+
+    let article_ids = resolve_collection_articles(
+        &mut tx,
+        &auth_ctx.user_id,
+        None,
+        &request.collection.articles,
+    )
+    .await?;
+
+    let create_collection = db::collection::CreateCollection {
+        title: &request.collection.title,
+        description: &request.collection.description,
+    };
+
+    let row =
+        db::collection::create_collection(&mut tx, &auth_ctx.user_id, create_collection).await?;
+
+    db::collection::set_collection_articles(&mut tx, &row.id, &article_ids).await?;
+
+    let collection = query_collection(&mut tx, &row.slug, Some(auth_ctx.user_id))
+        .await?
+        .ok_or(Error::Internal)?;
+
+    tx.commit().await?;
+
+    Ok(Json(CollectionBody { collection }).into_response())
+}
+
+/// Handles the get collection API endpoint at `GET /api/collections/:slug`. The handler returns the
+/// collection along with its articles in order if it exists, otherwise it will return a 404
+/// response. Articles that are not published are only included for their author.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "collection": {
+///     "slug": "dragon-training",
+///     "title": "Dragon training",
+///     "description": "Everything you need to know",
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": null,
+///     "articlesCount": 2,
+///     "articles": [{
+///       "slug": "choosing-a-dragon",
+///       "title": "Choosing a dragon",
+///       "description": "Ever wonder which?",
+///       "body": "It takes a Jacobian",
+///       "bodyHtml": "<p>It takes a Jacobian</p>\n",
+///       "tagList": ["dragons", "training"],
+///       "createdAt": "2016-02-18T03:22:56.637Z",
+///       "updatedAt": "2016-02-18T03:48:35.824Z",
+///       "status": "published",
+///       "publishedAt": "2016-02-18T03:22:56.637Z",
+///       "publishAt": null,
+///       "favorited": false,
+///       "favoritesCount": 0,
+///       "commentsCount": 0,
+///       "viewsCount": 0,
+///       "wordCount": 4,
+///       "readingTimeMinutes": 1,
+///       "bookmarked": false,
+///       "series": {
+///         "slug": "dragon-training",
+///         "title": "Dragon training",
+///         "position": 1,
+///         "length": 2,
+///         "previous": null,
+///         "next": { "slug": "how-to-train-your-dragon", "title": "How to train your dragon" }
+///       },
+///       "author": {
+///         "username": "jake",
+///         "bio": "I work at statefarm",
+///         "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///         "following": false
+///       },
+///       "coauthors": []
+///     }],
+///     "author": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     }
+///   }
+/// }
+/// ```
+async fn get_collection(
+    ctx: State<AppContext>,
+    auth_ctx: Option<AuthContext>,
+    Path(slug): Path<String>,
+) -> Result<Response, Error> {
+    let user_ctx = auth_ctx.map(|ac| ac.user_id);
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    match query_collection(&mut cxn, &slug, user_ctx).await? {
+        Some(collection) => Ok(Json(CollectionBody { collection }).into_response()),
+        None => Ok(StatusCode::NOT_FOUND.into_response()),
+    }
+}
+
+/// Handles the update collection API endpoint at `PUT /api/collections/:slug`. If the collection
+/// does not exist then a 404 response is returned and if the authenticated user does not own the
+/// collection, then a 403 response is returned.
+///
+/// # Request Body Format
+///
+/// ```json
+/// {
+///   "collection": {
+///     "articles": ["choosing-a-dragon", "how-to-train-your-dragon", "flying-your-dragon"]
+///   }
+/// }
+/// ```
+///
+/// # Accepted Fields
+///
+/// * `title`
+/// * `description`
+/// * `articles` - replaces the articles of the collection, following the same rules as when
+///   creating a collection
+///
+/// # Response Body Format
+///
+/// The response body has the same format as that of `GET /api/collections/:slug`.
+async fn update_collection(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+    Json(request): Json<CollectionBody<UpdateCollection>>,
+) -> Result<Response, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    let row = match db::collection::query_collection_by_slug(&mut tx, &slug).await? {
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+        Some(row) if row.user_id != auth_ctx.user_id => {
+            return Ok(StatusCode::FORBIDDEN.into_response())
+        }
+        Some(row) => row,
+    };
+
+    let title = request.collection.title.as_ref().unwrap_or(&row.title);
+    if slug::slugify(title).is_empty() {
+        return Err(Error::Validation);
+    }
+
+    let description = request
+        .collection
+        .description
+        .as_ref()
+        .unwrap_or(&row.description);
+
+    if let Some(slugs) = &request.collection.articles {
+        let article_ids =
+            resolve_collection_articles(&mut tx, &auth_ctx.user_id, Some(&row.id), slugs).await?;
+
+        db::collection::set_collection_articles(&mut tx, &row.id, &article_ids).await?;
+    }
+
+    let update_collection = db::collection::UpdateCollection { title, description };
+
+    let updated = db::collection::update_collection(&mut tx, &row.id, update_collection).await?;
+
+    let collection = query_collection(&mut tx, &updated.slug, Some(auth_ctx.user_id))
+        .await?
+        .ok_or(Error::Internal)?;
+
+    tx.commit().await?;
+
+    Ok(Json(CollectionBody { collection }).into_response())
+}
+
+/// Handles the delete collection API endpoint at `DELETE /api/collections/:slug`. The articles of
+/// the collection are not deleted. If the collection does not exist then a 404 response is returned
+/// and if the authenticated user does not own the collection, then a 403 response is returned.
+async fn delete_collection(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+) -> Result<Response, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    match db::collection::query_collection_by_slug(&mut tx, &slug).await? {
+        None => Ok(StatusCode::NOT_FOUND.into_response()),
+        Some(row) if row.user_id != auth_ctx.user_id => Ok(StatusCode::FORBIDDEN.into_response()),
+        Some(row) => {
+            db::collection::delete_collection_by_id(&mut tx, &row.id).await?;
+
+            tx.commit().await?;
+
+            Ok(StatusCode::NO_CONTENT.into_response())
+        }
+    }
+}
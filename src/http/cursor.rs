@@ -0,0 +1,181 @@
+use crate::{
+    db::{Keyset, Page},
+    http::Error,
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::DateTime;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Prefix of a cursor that selects the page of results following a position.
+const NEXT: &str = "n";
+
+/// Prefix of a cursor that selects the page of results preceding a position.
+const PREVIOUS: &str = "p";
+
+/// The [`Cursors`] struct contains the opaque cursors that the client can send back in the `cursor`
+/// query parameter to fetch the pages of results adjacent to the current one. Unlike paging by
+/// offset, rows that are created while the client is paging do not cause duplicate results.
+#[derive(Debug, Default, Serialize)]
+pub(super) struct Cursors {
+    /// Cursor that selects the next page of results, if there is one.
+    #[serde(rename = "nextCursor")]
+    next: Option<String>,
+    /// Cursor that selects the previous page of results, if there is one.
+    #[serde(rename = "prevCursor")]
+    prev: Option<String>,
+}
+
+/// Creates the [`Page`] to select given the paging parameters received from the client. A cursor
+/// takes the place of the offset so the two cannot be combined.
+pub(super) fn page(limit: i32, offset: i32, cursor: Option<&str>) -> Result<Page, Error> {
+    match cursor {
+        None => Ok(Page::Offset { limit, offset }),
+        Some(_) if offset != 0 => Err(Error::Validation),
+        Some(cursor) => decode(cursor, limit).ok_or(Error::Validation),
+    }
+}
+
+/// Returns the [`Page`] that should be queried in order to select the given page along with one
+/// extra row, which is used by [`paginate`] to determine whether there are more results.
+pub(super) fn lookahead(page: &Page) -> Page {
+    match *page {
+        Page::Offset { limit, offset } => Page::Offset {
+            limit: limit.saturating_add(1),
+            offset,
+        },
+        Page::After { limit, key } => Page::After {
+            limit: limit.saturating_add(1),
+            key,
+        },
+        Page::Before { limit, key } => Page::Before {
+            limit: limit.saturating_add(1),
+            key,
+        },
+    }
+}
+
+/// Removes the extra row from the rows selected using the [`lookahead`] of the given [`Page`] and
+/// creates the [`Cursors`] for the adjacent pages using the position of the first and last rows.
+pub(super) fn paginate<T>(
+    mut rows: Vec<T>,
+    page: &Page,
+    key: impl Fn(&T) -> Keyset,
+) -> (Vec<T>, Cursors) {
+    let limit = usize::try_from(page.limit()).unwrap_or_default();
+
+    let more = rows.len() > limit;
+    if more {
+        // the rows preceding a position are in order so the extra row is the first one
+        match page {
+            Page::Before { .. } => {
+                rows.drain(..rows.len() - limit);
+            }
+            _ => rows.truncate(limit),
+        }
+    }
+
+    let (has_next, has_prev) = match page {
+        Page::Offset { offset, .. } => (more, *offset > 0),
+        Page::After { .. } => (more, true),
+        Page::Before { .. } => (true, more),
+    };
+
+    let cursors = Cursors {
+        next: rows
+            .last()
+            .filter(|_| has_next)
+            .map(|row| encode(NEXT, &key(row))),
+        prev: rows
+            .first()
+            .filter(|_| has_prev)
+            .map(|row| encode(PREVIOUS, &key(row))),
+    };
+
+    (rows, cursors)
+}
+
+/// Encodes the direction and [`Keyset`] position into an opaque cursor.
+fn encode(direction: &str, key: &Keyset) -> String {
+    let cursor = format!(
+        "{}:{}:{}",
+        direction,
+        key.created.timestamp_micros(),
+        key.id
+    );
+
+    URL_SAFE_NO_PAD.encode(cursor)
+}
+
+/// Decodes the opaque cursor into a [`Page`] of the given size, if it is valid.
+fn decode(cursor: &str, limit: i32) -> Option<Page> {
+    let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    let decoded = String::from_utf8(bytes).ok()?;
+
+    let mut parts = decoded.splitn(3, ':');
+    let direction = parts.next()?;
+    let created = DateTime::from_timestamp_micros(parts.next()?.parse().ok()?)?;
+    let id = Uuid::parse_str(parts.next()?).ok()?;
+
+    let key = Keyset { created, id };
+
+    match direction {
+        NEXT => Some(Page::After { limit, key }),
+        PREVIOUS => Some(Page::Before { limit, key }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{TimeZone, Utc};
+
+    /// Verifies that the cursors of a page select the adjacent pages and that the extra row
+    /// selected by the lookahead is removed from the correct end of the page.
+    #[test]
+    fn verify_paginate() {
+        let keys: Vec<Keyset> = (0..4)
+            .map(|i| Keyset {
+                created: Utc.timestamp_opt(1_700_000_000 + i, 123_456_000).unwrap(),
+                id: Uuid::new_v4(),
+            })
+            .collect();
+
+        let first = Page::Offset {
+            limit: 2,
+            offset: 0,
+        };
+        let (rows, cursors) = paginate(keys[..3].to_vec(), &first, |k| *k);
+        assert_eq!(keys[..2], rows[..]);
+        assert!(cursors.prev.is_none());
+
+        let next = page(2, 0, cursors.next.as_deref()).unwrap();
+        assert_eq!(
+            Page::After {
+                limit: 2,
+                key: keys[1]
+            },
+            next
+        );
+
+        let before = Page::Before {
+            limit: 2,
+            key: keys[3],
+        };
+        let (rows, cursors) = paginate(keys[..3].to_vec(), &before, |k| *k);
+        assert_eq!(keys[1..3], rows[..]);
+        assert_eq!(
+            Page::Before {
+                limit: 2,
+                key: keys[1]
+            },
+            page(2, 0, cursors.prev.as_deref()).unwrap()
+        );
+
+        assert!(page(2, 10, cursors.next.as_deref()).is_err());
+        assert!(page(2, 0, Some("not-a-cursor")).is_err());
+    }
+}
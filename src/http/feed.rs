@@ -0,0 +1,678 @@
+use std::fmt::Write;
+
+use crate::{
+    config::{Feeds, Reading},
+    db::{self, article::ArticleView, Page},
+    http::{auth::AuthContext, markdown, profile, tag, AppContext, Error},
+};
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use http::{header, HeaderMap, HeaderValue, StatusCode};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Content type of an Atom feed.
+const ATOM_CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+
+/// Content type of an RSS feed.
+const RSS_CONTENT_TYPE: &str = "application/rss+xml; charset=utf-8";
+
+/// Creates the [`Router`] for the HTTP endpoints that serve the RSS and Atom feeds of articles and
+/// requires the [`AppContext`] to be the state type. Feeds only ever contain published articles.
+///
+/// The following list enumerates the endpoints which are exposed by the `feed` API.
+///
+/// * `GET /feeds/articles.atom` - Atom feed of the most recent articles.
+/// * `GET /feeds/tags/:tag.rss` - RSS feed of the most recent articles with a tag.
+/// * `GET /feeds/profiles/:username.atom` - Atom feed of the most recent articles by a user.
+/// * `GET /feeds/private/:token.atom` - Atom feed of the most recent articles by the users followed
+///   by the user identified by the secret token.
+/// * `GET /api/user/feed-token` - Authentication required, retrieves the URL of the private feed of
+///   the user.
+/// * `POST /api/user/feed-token` - Authentication required, creates a new URL for the private feed
+///   of the user which replaces any previous URL.
+/// * `DELETE /api/user/feed-token` - Authentication required, revokes the URL of the private feed
+///   of the user.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new()
+        .route("/feeds/articles.atom", get(get_articles_feed))
+        .route("/feeds/tags/:tag", get(get_tag_feed))
+        .route("/feeds/profiles/:username", get(get_profile_feed))
+        .route("/feeds/private/:token", get(get_private_feed))
+        .route(
+            "/api/user/feed-token",
+            get(get_feed_token)
+                .post(create_feed_token)
+                .delete(delete_feed_token),
+        )
+}
+
+/// The [`PrivateFeed`] struct contains data that represents the private feed of a user as returned
+/// from the API.
+#[derive(Debug, Serialize)]
+struct PrivateFeed {
+    /// URL of the private feed, which contains the secret token of the user. Only a hash of the
+    /// token is stored so the URL is only available when it is created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    /// Time the URL was created.
+    #[serde(rename = "createdAt")]
+    created: DateTime<Utc>,
+}
+
+impl PrivateFeed {
+    /// Creates a new [`PrivateFeed`] from the given [`db::feed::FeedToken`] along with the secret
+    /// token itself when it has just been created.
+    fn with_db_token(config: &Feeds, feed_token: db::feed::FeedToken, token: Option<&str>) -> Self {
+        Self {
+            url: token.map(|token| format!("{}/private/{}.atom", config.public_url, token)),
+            created: feed_token.created,
+        }
+    }
+}
+
+/// The [`PrivateFeedBody`] struct is the envelope in which the [`PrivateFeed`] of a user is
+/// returned to the client.
+#[derive(Debug, Serialize)]
+struct PrivateFeedBody {
+    /// Private feed of the user.
+    feed: PrivateFeed,
+}
+
+/// The [`Feed`] struct contains the data that is rendered as an RSS or Atom feed.
+struct Feed<'a> {
+    /// Title of the feed.
+    title: String,
+    /// URL of the web page that the feed corresponds to.
+    link: String,
+    /// URL of the feed itself.
+    self_url: String,
+    /// Articles in the feed, most recent first.
+    articles: &'a [ArticleView],
+}
+
+impl Feed<'_> {
+    /// Returns the time that the most recently modified article in the feed was modified, if the
+    /// feed has any articles.
+    fn last_modified(&self) -> Option<DateTime<Utc>> {
+        self.articles.iter().map(article_modified).max()
+    }
+}
+
+/// Returns the time that the article was first published, falling back to the time that it was
+/// created for articles published before publication times were recorded.
+fn article_published(view: &ArticleView) -> DateTime<Utc> {
+    view.published_at.unwrap_or(view.created)
+}
+
+/// Returns the time that the article was last modified, which is never before it was published.
+fn article_modified(view: &ArticleView) -> DateTime<Utc> {
+    let published = article_published(view);
+    view.updated.map_or(published, |u| u.max(published))
+}
+
+/// Returns the rendered HTML of the body of the article, which has been set by
+/// [`render_missing_html`] for articles whose HTML has not been stored yet.
+fn article_html(view: &ArticleView) -> &str {
+    view.body_html.as_deref().unwrap_or_default()
+}
+
+/// Renders the HTML of the bodies of the articles that were created before the HTML was stored and
+/// have not been rendered by the backfill at startup yet.
+async fn render_missing_html(config: &Reading, articles: &mut [ArticleView]) -> Result<(), Error> {
+    for view in articles.iter_mut().filter(|view| view.body_html.is_none()) {
+        let rendered = markdown::render_body(config, &view.body).await?;
+        view.body_html = Some(rendered.html);
+    }
+
+    Ok(())
+}
+
+/// Returns the hex encoded SHA-256 hash of the secret token in the URL of a private feed, which is
+/// what is stored in the database.
+fn hash_feed_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Escapes the characters in the text that have special meaning in XML.
+pub(super) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders the [`Feed`] as an Atom document.
+fn render_atom(config: &Feeds, feed: &Feed) -> String {
+    let updated = feed.last_modified().unwrap_or(DateTime::UNIX_EPOCH);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    let _ = writeln!(xml, "  <id>{}</id>", escape_xml(&feed.self_url));
+    let _ = writeln!(xml, "  <title>{}</title>", escape_xml(&feed.title));
+    let _ = writeln!(
+        xml,
+        "  <updated>{}</updated>",
+        updated.to_rfc3339_opts(SecondsFormat::Secs, true)
+    );
+    let _ = writeln!(
+        xml,
+        "  <link rel=\"self\" type=\"application/atom+xml\" href=\"{}\"/>",
+        escape_xml(&feed.self_url)
+    );
+    let _ = writeln!(
+        xml,
+        "  <link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>",
+        escape_xml(&feed.link)
+    );
+
+    for view in feed.articles {
+        xml.push_str("  <entry>\n");
+        let _ = writeln!(xml, "    <id>urn:uuid:{}</id>", view.id);
+        let _ = writeln!(xml, "    <title>{}</title>", escape_xml(&view.title));
+        let _ = writeln!(
+            xml,
+            "    <link rel=\"alternate\" type=\"text/html\" href=\"{}/article/{}\"/>",
+            escape_xml(&config.site_url),
+            escape_xml(&view.slug)
+        );
+        let _ = writeln!(
+            xml,
+            "    <published>{}</published>",
+            article_published(view).to_rfc3339_opts(SecondsFormat::Secs, true)
+        );
+        let _ = writeln!(
+            xml,
+            "    <updated>{}</updated>",
+            article_modified(view).to_rfc3339_opts(SecondsFormat::Secs, true)
+        );
+        let _ = writeln!(
+            xml,
+            "    <author><name>{}</name><uri>{}/profile/{}</uri></author>",
+            escape_xml(&view.author_name),
+            escape_xml(&config.site_url),
+            escape_xml(&view.author_name)
+        );
+        for tag in view.tags.iter() {
+            let _ = writeln!(xml, "    <category term=\"{}\"/>", escape_xml(tag));
+        }
+        let _ = writeln!(
+            xml,
+            "    <summary>{}</summary>",
+            escape_xml(&view.description)
+        );
+        let _ = writeln!(
+            xml,
+            "    <content type=\"html\">{}</content>",
+            escape_xml(article_html(view))
+        );
+        xml.push_str("  </entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// Renders the [`Feed`] as an RSS 2.0 document.
+fn render_rss(config: &Feeds, feed: &Feed) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n");
+    xml.push_str("  <channel>\n");
+    let _ = writeln!(xml, "    <title>{}</title>", escape_xml(&feed.title));
+    let _ = writeln!(xml, "    <link>{}</link>", escape_xml(&feed.link));
+    let _ = writeln!(
+        xml,
+        "    <description>{}</description>",
+        escape_xml(&feed.title)
+    );
+    let _ = writeln!(
+        xml,
+        "    <atom:link rel=\"self\" type=\"application/rss+xml\" href=\"{}\"/>",
+        escape_xml(&feed.self_url)
+    );
+    if let Some(last_modified) = feed.last_modified() {
+        let _ = writeln!(
+            xml,
+            "    <lastBuildDate>{}</lastBuildDate>",
+            last_modified.to_rfc2822()
+        );
+    }
+
+    for view in feed.articles {
+        xml.push_str("    <item>\n");
+        let _ = writeln!(xml, "      <title>{}</title>", escape_xml(&view.title));
+        let _ = writeln!(
+            xml,
+            "      <link>{}/article/{}</link>",
+            escape_xml(&config.site_url),
+            escape_xml(&view.slug)
+        );
+        let _ = writeln!(
+            xml,
+            "      <guid isPermaLink=\"false\">urn:uuid:{}</guid>",
+            view.id
+        );
+        let _ = writeln!(
+            xml,
+            "      <pubDate>{}</pubDate>",
+            article_published(view).to_rfc2822()
+        );
+        let _ = writeln!(
+            xml,
+            "      <dc:creator>{}</dc:creator>",
+            escape_xml(&view.author_name)
+        );
+        for tag in view.tags.iter() {
+            let _ = writeln!(xml, "      <category>{}</category>", escape_xml(tag));
+        }
+        let _ = writeln!(
+            xml,
+            "      <description>{}</description>",
+            escape_xml(article_html(view))
+        );
+        xml.push_str("    </item>\n");
+    }
+
+    xml.push_str("  </channel>\n");
+    xml.push_str("</rss>\n");
+    xml
+}
+
+/// Determines whether the client already has the current version of the feed identified by the
+/// entity tag and last modified time based on the conditional request headers. As with HTTP caches,
+/// `If-None-Match` takes precedence over `If-Modified-Since` when both are present.
+fn is_not_modified(headers: &HeaderMap, etag: &str, last_modified: Option<DateTime<Utc>>) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+        return if_none_match.to_str().is_ok_and(|v| {
+            v.split(',')
+                .map(|t| t.trim())
+                .any(|t| t == "*" || t.trim_start_matches("W/") == etag)
+        });
+    }
+
+    let if_modified_since = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| DateTime::parse_from_rfc2822(v).ok());
+
+    match (last_modified, if_modified_since) {
+        (Some(modified), Some(since)) => modified.timestamp() <= since.timestamp(),
+        _ => false,
+    }
+}
+
+/// Creates the response for a rendered feed document with `ETag` and `Last-Modified` headers so
+/// that feed readers can make conditional requests. A 304 response without a body is returned when
+/// the client already has the current version of the feed.
+fn feed_response(
+    headers: &HeaderMap,
+    content_type: &'static str,
+    cache_control: &'static str,
+    document: String,
+    last_modified: Option<DateTime<Utc>>,
+) -> Response {
+    let etag = format!(
+        "\"{}\"",
+        URL_SAFE_NO_PAD.encode(Sha256::digest(document.as_bytes()))
+    );
+
+    let mut response = if is_not_modified(headers, &etag, last_modified) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        ([(header::CONTENT_TYPE, content_type)], document).into_response()
+    };
+
+    let response_headers = response.headers_mut();
+    response_headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(cache_control),
+    );
+    if let Ok(value) = etag.parse() {
+        response_headers.insert(header::ETAG, value);
+    }
+    if let Some(modified) = last_modified {
+        let http_date = modified.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        if let Ok(value) = http_date.parse() {
+            response_headers.insert(header::LAST_MODIFIED, value);
+        }
+    }
+
+    response
+}
+
+/// Returns the first page of the configured size of the feed.
+fn feed_page(config: &Feeds) -> Page {
+    Page::Offset {
+        limit: config.size,
+        offset: 0,
+    }
+}
+
+/// Handles the articles feed endpoint at `GET /feeds/articles.atom`. The handler returns an Atom
+/// feed of the most recently created published articles.
+///
+/// The response includes `ETag` and `Last-Modified` headers and a 304 response is returned to
+/// requests with an `If-None-Match` or `If-Modified-Since` header that matches the current feed.
+async fn get_articles_feed(ctx: State<AppContext>, headers: HeaderMap) -> Result<Response, Error> {
+    let config = &ctx.config.feeds;
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let filters = db::article::ListFilters::default();
+    let mut articles =
+        db::article::query_articles(&mut cxn, None, &filters, &feed_page(config)).await?;
+    render_missing_html(&ctx.config.reading, &mut articles).await?;
+
+    let feed = Feed {
+        title: config.title.clone(),
+        link: config.site_url.clone(),
+        self_url: format!("{}/articles.atom", config.public_url),
+        articles: &articles,
+    };
+
+    let document = render_atom(config, &feed);
+
+    Ok(feed_response(
+        &headers,
+        ATOM_CONTENT_TYPE,
+        "public, max-age=300",
+        document,
+        feed.last_modified(),
+    ))
+}
+
+/// Handles the tag feed endpoint at `GET /feeds/tags/:tag.rss`. The handler returns an RSS feed of
+/// the most recently created published articles with the tag. The name of the tag is normalized in
+/// the same way as when filtering articles by tag.
+///
+/// The response includes `ETag` and `Last-Modified` headers and a 304 response is returned to
+/// requests with an `If-None-Match` or `If-Modified-Since` header that matches the current feed.
+async fn get_tag_feed(
+    ctx: State<AppContext>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, Error> {
+    let name = match file.strip_suffix(".rss") {
+        Some(name) if !name.is_empty() => name.to_owned(),
+        _ => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    let config = &ctx.config.feeds;
+
+    let tags = tag::normalize_filter_tags(&ctx.config.tags, &[name]);
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let filters = db::article::ListFilters {
+        tags: &tags,
+        ..Default::default()
+    };
+    let mut articles =
+        db::article::query_articles(&mut cxn, None, &filters, &feed_page(config)).await?;
+    render_missing_html(&ctx.config.reading, &mut articles).await?;
+
+    let feed = Feed {
+        title: format!("{}: {}", config.title, tags[0]),
+        link: config.site_url.clone(),
+        self_url: format!("{}/tags/{}.rss", config.public_url, tags[0]),
+        articles: &articles,
+    };
+
+    let document = render_rss(config, &feed);
+
+    Ok(feed_response(
+        &headers,
+        RSS_CONTENT_TYPE,
+        "public, max-age=300",
+        document,
+        feed.last_modified(),
+    ))
+}
+
+/// Handles the profile feed endpoint at `GET /feeds/profiles/:username.atom`. The handler returns
+/// an Atom feed of the most recently created published articles authored or co-authored by the
+/// user. A previous username that is still within the configured grace period resolves to the
+/// user. If no user exists with the username, then a 404 response is returned.
+///
+/// The response includes `ETag` and `Last-Modified` headers and a 304 response is returned to
+/// requests with an `If-None-Match` or `If-Modified-Since` header that matches the current feed.
+async fn get_profile_feed(
+    ctx: State<AppContext>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, Error> {
+    let username = match file.strip_suffix(".atom") {
+        Some(username) if !username.is_empty() => username.to_owned(),
+        _ => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    let config = &ctx.config.feeds;
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let username = profile::resolve_username(&mut cxn, &ctx, username).await?;
+    let profile = match db::user::query_profile_by_username(&mut cxn, &username, None).await? {
+        Some(profile) => profile,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    let authors = [profile.name];
+    let filters = db::article::ListFilters {
+        authors: &authors,
+        ..Default::default()
+    };
+    let mut articles =
+        db::article::query_articles(&mut cxn, None, &filters, &feed_page(config)).await?;
+    render_missing_html(&ctx.config.reading, &mut articles).await?;
+
+    let feed = Feed {
+        title: format!("{}: {}", config.title, authors[0]),
+        link: format!("{}/profile/{}", config.site_url, authors[0]),
+        self_url: format!("{}/profiles/{}.atom", config.public_url, authors[0]),
+        articles: &articles,
+    };
+
+    let document = render_atom(config, &feed);
+
+    Ok(feed_response(
+        &headers,
+        ATOM_CONTENT_TYPE,
+        "public, max-age=300",
+        document,
+        feed.last_modified(),
+    ))
+}
+
+/// Handles the private feed endpoint at `GET /feeds/private/:token.atom`. The handler returns an
+/// Atom feed of the same articles as the feed of the user identified by the secret token, i.e. the
+/// most recently created published articles by the users they follow. Feed readers cannot send the
+/// authentication header so the token in the URL is used instead. If the token does not exist,
+/// including when it has been replaced or revoked, then a 404 response is returned.
+///
+/// The response includes `ETag` and `Last-Modified` headers and a 304 response is returned to
+/// requests with an `If-None-Match` or `If-Modified-Since` header that matches the current feed.
+async fn get_private_feed(
+    ctx: State<AppContext>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, Error> {
+    let token = match file.strip_suffix(".atom") {
+        Some(token) if !token.is_empty() => token,
+        _ => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    let config = &ctx.config.feeds;
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let feed_token = match db::feed::query_feed_token(&mut cxn, &hash_feed_token(token)).await? {
+        Some(feed_token) => feed_token,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    let mut articles =
+        db::article::query_user_feed(&mut cxn, &feed_token.user_id, &feed_page(config)).await?;
+    render_missing_html(&ctx.config.reading, &mut articles).await?;
+
+    let feed = Feed {
+        title: format!("{}: Your Feed", config.title),
+        link: config.site_url.clone(),
+        self_url: format!("{}/private/{}.atom", config.public_url, token),
+        articles: &articles,
+    };
+
+    let document = render_atom(config, &feed);
+
+    Ok(feed_response(
+        &headers,
+        ATOM_CONTENT_TYPE,
+        "private, max-age=300",
+        document,
+        feed.last_modified(),
+    ))
+}
+
+/// Handles the get feed token API endpoint at `GET /api/user/feed-token`. The handler returns when
+/// the URL of the private feed of the currently authenticated user was created. The URL itself is
+/// only returned when it is created since only a hash of the token is stored. If the user has not
+/// created one, then a 404 response is returned.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "feed": {
+///     "createdAt": "2016-02-18T03:22:56.637Z"
+///   }
+/// }
+/// ```
+async fn get_feed_token(ctx: State<AppContext>, auth_ctx: AuthContext) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    match db::feed::query_feed_token_by_user_id(&mut cxn, &auth_ctx.user_id).await? {
+        Some(token) => {
+            let feed = PrivateFeed::with_db_token(&ctx.config.feeds, token, None);
+            Ok(Json(PrivateFeedBody { feed }).into_response())
+        }
+        None => Ok(StatusCode::NOT_FOUND.into_response()),
+    }
+}
+
+/// Handles the create feed token API endpoint at `POST /api/user/feed-token`. The handler creates a
+/// new secret token for the private feed of the currently authenticated user and returns the URL
+/// of the feed. Any previous URL stops working so this can be used if the URL has been leaked. This
+/// is the only time the URL is returned so the client must keep it.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "feed": {
+///     "url": "http://localhost:7100/feeds/private/6b1f0e4c2d9a4f7e8c3b5a1d0e9f8c7b.atom",
+///     "createdAt": "2016-02-18T03:22:56.637Z"
+///   }
+/// }
+/// ```
+async fn create_feed_token(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    let token = Uuid::new_v4().simple().to_string();
+
+    let feed_token =
+        db::feed::upsert_feed_token(&mut cxn, &auth_ctx.user_id, &hash_feed_token(&token)).await?;
+    let feed = PrivateFeed::with_db_token(&ctx.config.feeds, feed_token, Some(&token));
+
+    Ok((StatusCode::CREATED, Json(PrivateFeedBody { feed })).into_response())
+}
+
+/// Handles the delete feed token API endpoint at `DELETE /api/user/feed-token`. The handler revokes
+/// the URL of the private feed of the currently authenticated user. If the user has no URL, then a
+/// 404 response is returned.
+async fn delete_feed_token(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    match db::feed::delete_feed_token(&mut cxn, &auth_ctx.user_id).await? {
+        true => Ok(StatusCode::NO_CONTENT.into_response()),
+        false => Ok(StatusCode::NOT_FOUND.into_response()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    /// Verifies that the characters with special meaning in XML are escaped.
+    #[test]
+    fn verify_escape_xml() {
+        assert_eq!(
+            "&lt;p&gt;Tom &amp; &quot;Jerry&quot; &apos;99&lt;/p&gt;",
+            escape_xml("<p>Tom & \"Jerry\" '99</p>")
+        );
+    }
+
+    /// Verifies that a feed is only considered not modified when the conditional request headers
+    /// match it and that the entity tag takes precedence over the modification time.
+    #[test]
+    fn verify_is_not_modified() {
+        let etag = "\"abc\"";
+        let modified = Utc.with_ymd_and_hms(2016, 2, 18, 3, 22, 56).unwrap();
+
+        let mut headers = HeaderMap::new();
+        assert!(!is_not_modified(&headers, etag, Some(modified)));
+
+        headers.insert(
+            header::IF_MODIFIED_SINCE,
+            HeaderValue::from_static("Thu, 18 Feb 2016 03:22:56 GMT"),
+        );
+        assert!(is_not_modified(&headers, etag, Some(modified)));
+        assert!(!is_not_modified(
+            &headers,
+            etag,
+            Some(modified + chrono::Duration::seconds(1))
+        ));
+        assert!(!is_not_modified(&headers, etag, None));
+
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_static("\"xyz\", W/\"abc\""),
+        );
+        assert!(is_not_modified(&headers, etag, None));
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"xyz\""));
+        assert!(!is_not_modified(&headers, etag, Some(modified)));
+    }
+
+    /// Verifies that feed tokens are hashed the same way as the migration that hashed the existing
+    /// tokens in the database.
+    #[test]
+    fn verify_hash_feed_token() {
+        assert_eq!(
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            hash_feed_token("abc")
+        );
+    }
+}
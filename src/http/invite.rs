@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use crate::{
+    db,
+    http::{auth::AuthContext, AppContext, Error},
+};
+
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Creates the [`Router`] for the HTTP endpoints that correspond to the `invite` domain and requires
+/// the [`AppContext`] to be the state type.
+///
+/// The following list enumerates the endpoints which are exposed by the `invite` API. All of the
+/// endpoints require the authenticated user to be an admin.
+///
+/// * `GET /api/invites` - Lists all of the invites that have been created.
+/// * `POST /api/invites` - Creates a new invite that can be used to register when the application
+///   is in invite-only mode.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new().route("/api/invites", get(list_invites).post(create_invite))
+}
+
+/// The [`Invite`] struct contains data that represents an invite as returned from the API.
+#[derive(Debug, Serialize)]
+struct Invite {
+    /// Code that must be provided to register using the invite.
+    code: String,
+    /// Maximum number of times the invite can be redeemed.
+    #[serde(rename = "maxUses")]
+    max_uses: i32,
+    /// Number of times the invite has been redeemed.
+    uses: i32,
+    /// Time after which the invite can no longer be redeemed, if any.
+    #[serde(rename = "expiresAt")]
+    expires: Option<DateTime<Utc>>,
+    /// Time the invite was created.
+    #[serde(rename = "createdAt")]
+    created: DateTime<Utc>,
+}
+
+impl Invite {
+    /// Creates a new [`Invite`] from the given [`db::invite::Invite`].
+    fn with_db_invite(invite: db::invite::Invite) -> Self {
+        Self {
+            code: invite.code,
+            max_uses: invite.max_uses,
+            uses: invite.uses,
+            expires: invite.expires,
+            created: invite.created,
+        }
+    }
+}
+
+/// The [`InviteBody`] struct is the envelope in which data for an invite is returned to the client
+/// or accepted from the client.
+#[derive(Debug, Deserialize, Serialize)]
+struct InviteBody<T> {
+    /// Invite data contained in the envelope.
+    invite: T,
+}
+
+/// The [`InvitesBody`] struct is the envelope in which multiple [`Invite`]s are returned to the
+/// client.
+#[derive(Debug, Serialize)]
+struct InvitesBody {
+    /// Invites that make up the response body.
+    invites: Vec<Invite>,
+}
+
+/// Returns the default number of times an invite can be redeemed. Used for defining the default
+/// value during the deserialization of the request body.
+const fn default_max_uses() -> i32 {
+    1
+}
+
+/// The [`CreateInvite`] struct contains the data received from the HTTP request to create a new
+/// invite.
+#[derive(Debug, Deserialize)]
+struct CreateInvite {
+    /// Maximum number of times the invite can be redeemed.
+    #[serde(rename = "maxUses", default = "default_max_uses")]
+    max_uses: i32,
+    /// Time after which the invite can no longer be redeemed, if any.
+    #[serde(rename = "expiresAt")]
+    expires: Option<DateTime<Utc>>,
+}
+
+/// The [`InviteEvent`] struct contains event data related to an invite that is published to Kafka
+/// when the invite is created. The code is intentionally left out of the event.
+#[derive(Debug, Serialize)]
+struct InviteEvent {
+    /// Id of the invite.
+    id: Uuid,
+    /// Id of the user who created the invite.
+    user_id: Uuid,
+    /// Maximum number of times the invite can be redeemed.
+    max_uses: i32,
+    /// Time after which the invite can no longer be redeemed, if any.
+    expires: Option<DateTime<Utc>>,
+}
+
+/// Determines whether the user with the given id exists and is an admin.
+async fn is_admin(cxn: &mut PgConnection, user_id: &Uuid) -> Result<bool, Error> {
+    let user = db::user::query_user_by_id(cxn, user_id).await?;
+
+    Ok(user.is_some_and(|u| u.admin))
+}
+
+/// Handles the list invites API endpoint at `GET /api/invites`. If the authenticated user is not an
+/// admin then a 403 response is returned.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "invites": [{
+///     "code": "6b1f0e4c2d9a4f7e8c3b5a1d0e9f8c7b",
+///     "maxUses": 5,
+///     "uses": 1,
+///     "expiresAt": "2016-02-18T03:22:56.637Z",
+///     "createdAt": "2016-02-11T03:22:56.637Z"
+///   }]
+/// }
+/// ```
+async fn list_invites(ctx: State<AppContext>, auth_ctx: AuthContext) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    if !is_admin(&mut cxn, &auth_ctx.user_id).await? {
+        return Ok(StatusCode::FORBIDDEN.into_response());
+    }
+
+    let invites = db::invite::query_invites(&mut cxn)
+        .await?
+        .into_iter()
+        .map(Invite::with_db_invite)
+        .collect();
+
+    Ok(Json(InvitesBody { invites }).into_response())
+}
+
+/// Handles the create invite API endpoint at `POST /api/invites`. If the authenticated user is not
+/// an admin then a 403 response is returned.
+///
+/// # Request Body Format
+///
+/// ``` json
+/// {
+///   "invite": {
+///     "maxUses": 5,
+///     "expiresAt": "2016-02-18T03:22:56.637Z"
+///   }
+/// }
+/// ```
+///
+/// # Field Validation
+///
+/// * `maxUses` - optional, defaults to a single use and must be greater than zero
+/// * `expiresAt` - optional, must be in the future and the invite never expires if omitted
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "invite": {
+///     "code": "6b1f0e4c2d9a4f7e8c3b5a1d0e9f8c7b",
+///     "maxUses": 5,
+///     "uses": 0,
+///     "expiresAt": "2016-02-18T03:22:56.637Z",
+///     "createdAt": "2016-02-11T03:22:56.637Z"
+///   }
+/// }
+/// ```
+async fn create_invite(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Json(request): Json<InviteBody<CreateInvite>>,
+) -> Result<Response, Error> {
+    if request.invite.max_uses < 1 || request.invite.expires.is_some_and(|e| e <= Utc::now()) {
+        return Err(Error::Validation);
+    }
+
+    let mut tx = ctx.db.begin().await?;
+
+    if !is_admin(&mut tx, &auth_ctx.user_id).await? {
+        return Ok(StatusCode::FORBIDDEN.into_response());
+    }
+
+    let code = Uuid::new_v4().simple().to_string();
+
+    let create_invite = db::invite::CreateInvite {
+        code: &code,
+        max_uses: request.invite.max_uses,
+        expires: request.invite.expires,
+    };
+
+    let db_invite = db::invite::create_invite(&mut tx, &auth_ctx.user_id, create_invite).await?;
+
+    let invite_event = InviteEvent {
+        id: db_invite.id,
+        user_id: db_invite.user_id,
+        max_uses: db_invite.max_uses,
+        expires: db_invite.expires,
+    };
+
+    let mut headers = HashMap::with_capacity(1);
+    headers.insert(String::from("type"), String::from("INVITE_CREATED"));
+
+    let create_outbox_entry = db::outbox::CreateOutboxEntry {
+        topic: String::from("user"),
+        partition_key: Some(auth_ctx.user_id.to_string()),
+        headers: Some(headers),
+        payload: Some(invite_event),
+    };
+
+    let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
+
+    tx.commit().await?;
+
+    match ctx.outbox_tx.send(()).await {
+        Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+        Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+    }
+
+    let invite = Invite::with_db_invite(db_invite);
+
+    Ok(Json(InviteBody { invite }).into_response())
+}
@@ -0,0 +1,125 @@
+use crate::{
+    config::Reading,
+    http::{auth::AuthContext, AppContext, Error},
+    markdown,
+};
+
+use axum::{extract::State, routing::post, Json, Router};
+use serde::{Deserialize, Serialize};
+
+/// Creates the [`Router`] for the HTTP endpoints that correspond to rendering Markdown and requires
+/// the [`AppContext`] to be the state type.
+///
+/// The following list enumerates the endpoints which are exposed by the `markdown` API.
+///
+/// * `POST /api/markdown/preview` - Authentication required, renders Markdown to sanitized HTML the
+///   same way the body of an article is rendered.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new().route("/api/markdown/preview", post(preview_markdown))
+}
+
+/// The [`PreviewBody`] struct is the envelope in which the data for a Markdown preview is accepted
+/// from and returned to the client.
+#[derive(Debug, Deserialize, Serialize)]
+struct PreviewBody<T> {
+    /// Preview related data.
+    preview: T,
+}
+
+/// The [`PreviewRequest`] struct contains the data received from the HTTP request to preview the
+/// rendered Markdown.
+#[derive(Debug, Deserialize)]
+struct PreviewRequest {
+    /// Markdown to render.
+    body: String,
+}
+
+/// The [`Preview`] struct contains the rendered Markdown returned to the client.
+#[derive(Debug, Serialize)]
+struct Preview {
+    /// Sanitized HTML rendered from the Markdown.
+    #[serde(rename = "bodyHtml")]
+    body_html: String,
+    /// Number of words in the Markdown, excluding code.
+    #[serde(rename = "wordCount")]
+    word_count: i32,
+    /// Estimated time to read the Markdown in minutes.
+    #[serde(rename = "readingTimeMinutes")]
+    reading_time_minutes: i32,
+}
+
+/// The [`RenderedBody`] struct contains the data computed from the Markdown body of an article
+/// that is stored along with it.
+#[derive(Debug)]
+pub(super) struct RenderedBody {
+    /// Sanitized HTML rendered from the Markdown body.
+    pub(super) html: String,
+    /// Number of words in the body, excluding code.
+    pub(super) word_count: i32,
+    /// Estimated time to read the body in minutes.
+    pub(super) reading_time_minutes: i32,
+}
+
+/// Renders the Markdown body of an article to sanitized HTML and estimates the time to read it at
+/// the configured reading speed, mapping any error to an [`Error`] that can be returned from a
+/// handler.
+pub(super) async fn render_body(config: &Reading, body: &str) -> Result<RenderedBody, Error> {
+    let rendered = markdown::render_html(body.to_owned()).await.map_err(|e| {
+        tracing::error!("error rendering markdown: {}", e);
+        Error::Internal
+    })?;
+
+    Ok(RenderedBody {
+        html: rendered.html,
+        word_count: i32::try_from(rendered.word_count).unwrap_or(i32::MAX),
+        reading_time_minutes: reading_time_minutes(config, rendered.word_count),
+    })
+}
+
+/// Estimates the number of minutes it takes to read the given number of words at the configured
+/// reading speed, rounded up to the next whole minute.
+fn reading_time_minutes(config: &Reading, word_count: usize) -> i32 {
+    let words_per_minute = config.words_per_minute.max(1) as usize;
+
+    i32::try_from((word_count + words_per_minute - 1) / words_per_minute).unwrap_or(i32::MAX)
+}
+
+/// Handles the Markdown preview API endpoint at `POST /api/markdown/preview`. Editors can use the
+/// endpoint to show exactly how the body of an article will be rendered before saving it.
+///
+/// # Request Body Format
+///
+/// ``` json
+/// {
+///   "preview": {
+///     "body": "# How to train your dragon"
+///   }
+/// }
+/// ```
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "preview": {
+///     "bodyHtml": "<h1>How to train your dragon</h1>\n",
+///     "wordCount": 5,
+///     "readingTimeMinutes": 1
+///   }
+/// }
+/// ```
+async fn preview_markdown(
+    ctx: State<AppContext>,
+    _auth_ctx: AuthContext,
+    Json(request): Json<PreviewBody<PreviewRequest>>,
+) -> Result<Json<PreviewBody<Preview>>, Error> {
+    let rendered = render_body(&ctx.config.reading, &request.preview.body).await?;
+
+    Ok(Json(PreviewBody {
+        preview: Preview {
+            body_html: rendered.html,
+            word_count: rendered.word_count,
+            reading_time_minutes: rendered.reading_time_minutes,
+        },
+    }))
+}
@@ -1,11 +1,26 @@
-mod article;
+pub(crate) mod article;
+mod asset;
 mod auth;
+mod avatar;
+mod bookmark;
+mod bulk;
+mod coauthor;
+mod collection;
+mod cursor;
+mod feed;
 mod health;
+mod invite;
+mod markdown;
 mod profile;
+mod related;
+mod revision;
+mod sitemap;
 mod tag;
+mod trash;
 mod user;
+mod view;
 
-use crate::config::Config;
+use crate::{cache::Caches, config::Config, db::Page, storage, storage::ObjectStore};
 
 use axum::{
     http::StatusCode,
@@ -14,7 +29,7 @@ use axum::{
 };
 use serde::Deserialize;
 use sqlx::PgPool;
-use std::sync::Arc;
+use std::{fmt::Write, sync::Arc};
 use tokio::sync::mpsc::Sender;
 
 /// The [`AppContext`] is the state that is shared between all HTTP handler functions and makes
@@ -27,26 +42,64 @@ pub struct AppContext {
     pub db: PgPool,
     /// Sender used to notify the outbox processor channel that an entry has been created.
     pub outbox_tx: Sender<()>,
+    /// In-memory caches shared with the event consumer which invalidates them.
+    pub caches: Arc<Caches>,
+    /// Store in which objects uploaded to the application are kept.
+    pub store: Arc<dyn ObjectStore>,
 }
 
 /// Creates the [`Router`] that exposes all of the routes that the application serves over HTTP.
-pub fn router(db: PgPool, config: Arc<Config>, outbox_tx: Sender<()>) -> Router {
+pub fn router(
+    db: PgPool,
+    config: Arc<Config>,
+    outbox_tx: Sender<()>,
+    caches: Arc<Caches>,
+    store: Arc<dyn ObjectStore>,
+) -> Router {
     let context = AppContext {
         config,
         db,
         outbox_tx,
+        caches,
+        store,
     };
 
+    let asset_router = asset::router().with_state(context.clone());
     let article_router = article::router().with_state(context.clone());
+    let bookmark_router = bookmark::router().with_state(context.clone());
+    let bulk_router = bulk::router().with_state(context.clone());
+    let coauthor_router = coauthor::router().with_state(context.clone());
+    let collection_router = collection::router().with_state(context.clone());
+    let feed_router = feed::router().with_state(context.clone());
+    let invite_router = invite::router().with_state(context.clone());
+    let markdown_router = markdown::router().with_state(context.clone());
     let profile_router = profile::router().with_state(context.clone());
+    let related_router = related::router().with_state(context.clone());
+    let revision_router = revision::router().with_state(context.clone());
+    let sitemap_router = sitemap::router().with_state(context.clone());
     let tag_router = tag::router().with_state(context.clone());
-    let user_router = user::router().with_state(context);
+    let trash_router = trash::router().with_state(context.clone());
+    let user_router = user::router().with_state(context.clone());
+    let view_router = view::router().with_state(context);
     let health_router = health::router();
 
     article_router
+        .merge(asset_router)
+        .merge(bookmark_router)
+        .merge(bulk_router)
+        .merge(coauthor_router)
+        .merge(collection_router)
+        .merge(feed_router)
+        .merge(invite_router)
+        .merge(markdown_router)
         .merge(profile_router)
+        .merge(related_router)
+        .merge(revision_router)
+        .merge(sitemap_router)
         .merge(tag_router)
+        .merge(trash_router)
         .merge(user_router)
+        .merge(view_router)
         .merge(health_router)
 }
 
@@ -62,6 +115,12 @@ pub enum Error {
         #[from]
         source: sqlx::Error,
     },
+    /// Occurs when an error is encountered in the object storage layer.
+    #[error("error occurred in object storage")]
+    Storage {
+        #[from]
+        source: storage::Error,
+    },
     /// Occurs when there is an internal server error that cannot be recovered from.
     #[error("internal server error")]
     Internal,
@@ -77,6 +136,10 @@ impl IntoResponse for Error {
                 tracing::error!("database error: {}", source);
                 StatusCode::INTERNAL_SERVER_ERROR.into_response()
             }
+            Error::Storage { source } => {
+                tracing::error!("storage error: {}", source);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
             Error::Internal => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
         }
     }
@@ -98,4 +161,44 @@ struct Pagination {
     /// Starting offset into the entire set of results.
     #[serde(default)]
     offset: i32,
+    /// Opaque cursor returned with a previous page of results that selects an adjacent page. Takes
+    /// the place of the offset.
+    cursor: Option<String>,
+}
+
+impl Pagination {
+    /// Creates the [`Page`] of results to select from the paging parameters.
+    fn page(&self) -> Result<Page, Error> {
+        cursor::page(self.limit, self.offset, self.cursor.as_deref())
+    }
+}
+
+/// Percent-encodes all of the characters in the value that are not unreserved in a URL so that it
+/// can be used as a single segment of a path.
+fn encode_path_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(b as char)
+            }
+            _ => {
+                let _ = write!(encoded, "%{:02X}", b);
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that characters which are not unreserved in a URL are percent-encoded.
+    #[test]
+    fn verify_encode_path_segment() {
+        assert_eq!("how-to_train.v2~", encode_path_segment("how-to_train.v2~"));
+        assert_eq!("c%2B%2B%20tips", encode_path_segment("c++ tips"));
+        assert_eq!("caf%C3%A9", encode_path_segment("café"));
+    }
 }
@@ -1,34 +1,50 @@
+use std::collections::HashMap;
+
 use crate::{
     db,
     db::user::Profile,
-    http::{auth::AuthContext, AppContext, Error},
+    http::{auth::AuthContext, encode_path_segment, AppContext, Error},
 };
 
 use axum::{
     extract::{Path, State},
-    response::{IntoResponse, Response},
+    response::{IntoResponse, Redirect, Response},
     routing::{get, post},
     Json, Router,
 };
 use http::StatusCode;
 use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Maximum number of follow suggestions returned to a user.
+const MAX_FOLLOW_SUGGESTIONS: i64 = 20;
 
 /// Creates the [`Router`] for the HTTP endpoints that correspond to the `profile` domain and requires
 /// the [`AppContext`] to be the state type.
 ///
 /// The following list enumerates the endpoints which are exposed by the `profile` API.
 ///
+/// * `GET /api/profiles/suggestions` - Authentication required, retrieves profiles that the
+///   authenticated user may be interested in following.
 /// * `GET /api/profiles/:username` - Retrieves the public profile for a user identified by
-/// `:username` and whether or not the authenticated user, if available, is following them.
+///   `:username` and whether or not the authenticated user, if available, is following them.
 /// * `POST /api/profiles/:username/follow` - Follows the user identified by `:username`.
 /// * `DELETE /api/profiles/:username/follow` - Unfollows the user identified by `:username`.
+/// * `POST /api/profiles/:username/block` - Blocks the user identified by `:username`.
+/// * `DELETE /api/profiles/:username/block` - Unblocks the user identified by `:username`.
 pub(super) fn router() -> Router<AppContext> {
     Router::new()
+        .route("/api/profiles/suggestions", get(get_follow_suggestions))
         .route("/api/profiles/:username", get(get_profile))
         .route(
             "/api/profiles/:username/follow",
             post(follow_profile).delete(unfollow_profile),
         )
+        .route(
+            "/api/profiles/:username/block",
+            post(block_profile).delete(unblock_profile),
+        )
 }
 
 /// The [`ProfileBody`] struct is the envelope in which the [`Profile`] for a user is returned to the
@@ -39,6 +55,79 @@ struct ProfileBody {
     profile: Profile,
 }
 
+/// The [`ProfilesBody`] struct is the envelope in which multiple [`Profile`]s are returned to the
+/// client.
+#[derive(Debug, Serialize)]
+struct ProfilesBody {
+    /// Public profiles for users of the application.
+    profiles: Vec<Profile>,
+}
+
+/// The [`FollowEvent`] struct contains event data related to a profile being followed or
+/// unfollowed that is published to Kafka when the action occurs.
+#[derive(Debug, Serialize)]
+struct FollowEvent {
+    /// Id of the user who was followed or unfollowed.
+    user_id: Uuid,
+    /// Id of the user who took the action.
+    follower_id: Uuid,
+}
+
+/// The [`BlockEvent`] struct contains event data related to a profile being blocked or unblocked
+/// that is published to Kafka when the action occurs.
+#[derive(Debug, Serialize)]
+struct BlockEvent {
+    /// Id of the user who was blocked or unblocked.
+    user_id: Uuid,
+    /// Id of the user who took the action.
+    blocker_id: Uuid,
+}
+
+/// Handles the follow suggestions API endpoint at `GET /api/profiles/suggestions`. The handler
+/// returns profiles that the authenticated user does not yet follow ranked first by how many of the
+/// profiles the user already follows also follow them and then by how many tags are shared between
+/// the articles they have each favorited.
+///
+/// Profiles that the user has blocked or that have blocked the user are never suggested.
+///
+/// The suggestions are cached per user and the cached entry is invalidated when an event is
+/// consumed indicating that the user has followed or unfollowed a profile, or that the user has
+/// blocked or unblocked a profile or been blocked or unblocked by one.
+///
+/// # Response Body Format
+///
+/// ``` json
+/// {
+///   "profiles": [{
+///     "username": "jake",
+///     "bio": "I work at statefarm",
+///     "image": "https://api.realworld.io/images/smiley-cyrus.jpg",
+///     "following": false
+///   }]
+/// }
+/// ```
+async fn get_follow_suggestions(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+) -> Result<Json<ProfilesBody>, Error> {
+    if let Some(profiles) = ctx.caches.suggestions.get(&auth_ctx.user_id) {
+        tracing::debug!("returning cached follow suggestions");
+        return Ok(Json(ProfilesBody { profiles }));
+    }
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let profiles =
+        db::user::query_follow_suggestions(&mut cxn, &auth_ctx.user_id, MAX_FOLLOW_SUGGESTIONS)
+            .await?;
+
+    ctx.caches
+        .suggestions
+        .insert(auth_ctx.user_id, profiles.clone());
+
+    Ok(Json(ProfilesBody { profiles }))
+}
+
 /// Handles the get user public profile API endpoint at `GET /api/profiles/:username`. The handler
 /// will read the `username` path parameter value and return the profile data for the matching user
 /// if it exists.
@@ -47,6 +136,11 @@ struct ProfileBody {
 /// whether the currently authenticated user is following the profile. If the request is made
 /// unauthenticated, then the `follows` property will still exists but always be set to `false`.
 ///
+/// If the `username` is a previous username of a user that was changed within the configured grace
+/// period, then a temporary redirect to the profile at the current username is returned. The
+/// redirect is not permanent since the previous username can be claimed by another user once the
+/// grace period has passed.
+///
 /// # Response Body Format
 ///
 /// ``` json
@@ -68,12 +162,37 @@ async fn get_profile(
 
     let mut cxn = ctx.db.acquire().await?;
 
-    match db::user::query_profile_by_username(&mut cxn, &username, auth_id).await? {
-        None => Ok(StatusCode::NOT_FOUND.into_response()),
-        Some(profile) => Ok(Json(ProfileBody { profile }).into_response()),
+    if let Some(profile) = db::user::query_profile_by_username(&mut cxn, &username, auth_id).await?
+    {
+        return Ok(Json(ProfileBody { profile }).into_response());
+    }
+
+    let grace_period = ctx.config.username.grace_period;
+
+    match db::user::resolve_username(&mut cxn, &username, grace_period).await? {
+        Some(current) if current != username => {
+            let location = format!("/api/profiles/{}", encode_path_segment(&current));
+            Ok(Redirect::temporary(&location).into_response())
+        }
+        _ => Ok(StatusCode::NOT_FOUND.into_response()),
     }
 }
 
+/// Resolves the username to the current username of a user, taking into account previous usernames
+/// that are still within the configured grace period. If the username cannot be resolved then it is
+/// returned as is.
+pub(super) async fn resolve_username(
+    cxn: &mut PgConnection,
+    ctx: &AppContext,
+    username: String,
+) -> Result<String, Error> {
+    let grace_period = ctx.config.username.grace_period;
+
+    Ok(db::user::resolve_username(cxn, &username, grace_period)
+        .await?
+        .unwrap_or(username))
+}
+
 /// Handles the follow user public profile API endpoint at `POST /api/profiles/:username/follow`.
 /// The handler will read the `username` path parameter value, the `user_id` from the
 /// [`AuthContext`] and use those values to create a record of the profile follow in the database.
@@ -95,17 +214,46 @@ async fn follow_profile(
     ctx: State<AppContext>,
     auth_ctx: AuthContext,
 ) -> Result<Response, Error> {
-    let mut cxn = ctx.db.acquire().await?;
+    let mut tx = ctx.db.begin().await?;
+
+    let username = resolve_username(&mut tx, &ctx, username).await?;
 
-    match db::user::add_profile_follow(&mut cxn, &username, auth_ctx.user_id).await? {
+    match db::user::add_profile_follow(&mut tx, &username, auth_ctx.user_id).await? {
         None => Ok(StatusCode::NOT_FOUND.into_response()),
-        Some(profile) => Ok(Json(ProfileBody { profile }).into_response()),
+        Some(profile) => {
+            let mut headers = HashMap::with_capacity(1);
+            headers.insert(String::from("type"), String::from("USER_FOLLOWED"));
+
+            let follow_event = FollowEvent {
+                user_id: profile.id,
+                follower_id: auth_ctx.user_id,
+            };
+
+            let create_outbox_entry = db::outbox::CreateOutboxEntry {
+                topic: String::from("user"),
+                partition_key: Some(auth_ctx.user_id.to_string()),
+                headers: Some(headers),
+                payload: Some(follow_event),
+            };
+
+            let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
+
+            tx.commit().await?;
+
+            match ctx.outbox_tx.send(()).await {
+                Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+                Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+            }
+
+            Ok(Json(ProfileBody { profile }).into_response())
+        }
     }
 }
 
 /// Handles the unfollow user public profile API endpoint at `POST /api/profiles/:username/unfollow`.
 /// The handler will read the `username` path parameter value, the `user_id` from the [`AuthContext`]
-/// and use those values to delete the record of the profile follow from the database.
+/// and use those values to delete the record of the profile follow from the database. The
+/// `USER_UNFOLLOWED` event is only published if the user was following the profile.
 ///
 /// # Response Body Format
 ///
@@ -124,10 +272,163 @@ async fn unfollow_profile(
     ctx: State<AppContext>,
     auth_ctx: AuthContext,
 ) -> Result<Response, Error> {
-    let mut cxn = ctx.db.acquire().await?;
+    let mut tx = ctx.db.begin().await?;
+
+    let username = resolve_username(&mut tx, &ctx, username).await?;
+
+    let unfollowed = db::user::remove_profile_follow(&mut tx, &username, auth_ctx.user_id).await?;
+
+    match db::user::query_profile_by_username(&mut tx, &username, Some(auth_ctx.user_id)).await? {
+        None => Ok(StatusCode::NOT_FOUND.into_response()),
+        Some(profile) if !unfollowed => Ok(Json(ProfileBody { profile }).into_response()),
+        Some(profile) => {
+            let mut headers = HashMap::with_capacity(1);
+            headers.insert(String::from("type"), String::from("USER_UNFOLLOWED"));
+
+            let follow_event = FollowEvent {
+                user_id: profile.id,
+                follower_id: auth_ctx.user_id,
+            };
+
+            let create_outbox_entry = db::outbox::CreateOutboxEntry {
+                topic: String::from("user"),
+                partition_key: Some(auth_ctx.user_id.to_string()),
+                headers: Some(headers),
+                payload: Some(follow_event),
+            };
+
+            let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
+
+            tx.commit().await?;
+
+            match ctx.outbox_tx.send(()).await {
+                Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+                Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+            }
+
+            Ok(Json(ProfileBody { profile }).into_response())
+        }
+    }
+}
+
+/// Handles the block user public profile API endpoint at `POST /api/profiles/:username/block`. The
+/// handler will read the `username` path parameter value, the `user_id` from the [`AuthContext`]
+/// and use those values to create a record of the profile block in the database. Blocked profiles
+/// are excluded from the follow suggestions of the user and the user is excluded from theirs. The
+/// `USER_BLOCKED` event is only published if the profile was not already blocked.
+///
+/// # Response Body Format
+///
+/// ``` json
+/// {
+///   "profile": {
+///     "username": "jake",
+///     "bio": "I work at statefarm",
+///     "image": "https://api.realworld.io/images/smiley-cyrus.jpg",
+///     "follows": false
+///   }
+/// }
+/// ```
+async fn block_profile(
+    Path(username): Path<String>,
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+) -> Result<Response, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    let username = resolve_username(&mut tx, &ctx, username).await?;
 
-    match db::user::remove_profile_follow(&mut cxn, &username, auth_ctx.user_id).await? {
+    let blocked = db::user::add_profile_block(&mut tx, &username, auth_ctx.user_id).await?;
+
+    match db::user::query_profile_by_username(&mut tx, &username, Some(auth_ctx.user_id)).await? {
         None => Ok(StatusCode::NOT_FOUND.into_response()),
-        Some(profile) => Ok(Json(ProfileBody { profile }).into_response()),
+        Some(profile) if !blocked => Ok(Json(ProfileBody { profile }).into_response()),
+        Some(profile) => {
+            create_block_outbox_entry(&mut tx, &profile, auth_ctx.user_id, "USER_BLOCKED").await?;
+
+            tx.commit().await?;
+
+            match ctx.outbox_tx.send(()).await {
+                Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+                Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+            }
+
+            Ok(Json(ProfileBody { profile }).into_response())
+        }
     }
 }
+
+/// Handles the unblock user public profile API endpoint at `DELETE /api/profiles/:username/block`.
+/// The handler will read the `username` path parameter value, the `user_id` from the
+/// [`AuthContext`] and use those values to delete the record of the profile block from the
+/// database. The `USER_UNBLOCKED` event is only published if the user had blocked the profile.
+///
+/// # Response Body Format
+///
+/// ``` json
+/// {
+///   "profile": {
+///     "username": "jake",
+///     "bio": "I work at statefarm",
+///     "image": "https://api.realworld.io/images/smiley-cyrus.jpg",
+///     "follows": false
+///   }
+/// }
+/// ```
+async fn unblock_profile(
+    Path(username): Path<String>,
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+) -> Result<Response, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    let username = resolve_username(&mut tx, &ctx, username).await?;
+
+    let unblocked = db::user::remove_profile_block(&mut tx, &username, auth_ctx.user_id).await?;
+
+    match db::user::query_profile_by_username(&mut tx, &username, Some(auth_ctx.user_id)).await? {
+        None => Ok(StatusCode::NOT_FOUND.into_response()),
+        Some(profile) if !unblocked => Ok(Json(ProfileBody { profile }).into_response()),
+        Some(profile) => {
+            create_block_outbox_entry(&mut tx, &profile, auth_ctx.user_id, "USER_UNBLOCKED")
+                .await?;
+
+            tx.commit().await?;
+
+            match ctx.outbox_tx.send(()).await {
+                Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+                Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+            }
+
+            Ok(Json(ProfileBody { profile }).into_response())
+        }
+    }
+}
+
+/// Creates the outbox entry for a `USER_BLOCKED` or `USER_UNBLOCKED` event of the given profile by
+/// the user with the given id.
+async fn create_block_outbox_entry(
+    cxn: &mut PgConnection,
+    profile: &Profile,
+    blocker_id: Uuid,
+    event_type: &str,
+) -> Result<(), sqlx::Error> {
+    let mut headers = HashMap::with_capacity(1);
+    headers.insert(String::from("type"), String::from(event_type));
+
+    let block_event = BlockEvent {
+        user_id: profile.id,
+        blocker_id,
+    };
+
+    let create_outbox_entry = db::outbox::CreateOutboxEntry {
+        topic: String::from("user"),
+        partition_key: Some(blocker_id.to_string()),
+        headers: Some(headers),
+        payload: Some(block_event),
+    };
+
+    let _ = db::outbox::create_outbox_entry(cxn, create_outbox_entry).await?;
+
+    Ok(())
+}
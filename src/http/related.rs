@@ -0,0 +1,147 @@
+use crate::{
+    db,
+    http::{article::Article, auth::AuthContext, AppContext, Error},
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+/// Number of related articles returned when no limit is requested.
+const DEFAULT_RELATED_ARTICLES: usize = 5;
+
+/// Maximum number of related articles that are computed for an article and can be requested.
+const MAX_RELATED_ARTICLES: usize = 20;
+
+/// Creates the [`Router`] for the HTTP endpoints that correspond to related articles and requires
+/// the [`AppContext`] to be the state type.
+///
+/// The following list enumerates the endpoints which are exposed by the `related` API.
+///
+/// * `GET /api/articles/:slug/related` - Retrieves the published articles that are most similar to
+///   an article.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new().route("/api/articles/:slug/related", get(list_related_articles))
+}
+
+/// The [`RelatedArticlesBody`] struct is the envelope in which related [`Article`]s are returned to
+/// the client.
+#[derive(Debug, Serialize)]
+struct RelatedArticlesBody {
+    /// Related articles, most similar first.
+    articles: Vec<Article>,
+}
+
+/// The [`RelatedParams`] struct contains the query parameters accepted by the related articles
+/// endpoint.
+#[derive(Debug, Deserialize)]
+struct RelatedParams {
+    /// Maximum number of related articles to return.
+    limit: Option<usize>,
+}
+
+/// Handles the related articles API endpoint at `GET /api/articles/:slug/related`. The handler
+/// returns the published articles that are most similar to the article identified by the `slug`
+/// path parameter, most similar first, and never the article itself. Articles are scored by the
+/// number of tags they share with the article, the number of users who favorited both articles and
+/// whether they have the same author. Articles that have nothing in common with the article are not
+/// included so fewer articles than requested may be returned.
+///
+/// The related articles are cached per article and the cached entry is invalidated when an event is
+/// consumed indicating that the article, or one of the articles related to it, has been updated or
+/// deleted. Other newly similar articles are included once the cached entry expires.
+///
+/// If the article does not exist or is not visible to the user, then a 404 response is returned.
+///
+/// # Query Parameters
+///
+/// * `limit` - optional, number of articles to return from `1` up to `20`, defaults to `5`
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "articles": [{
+///     "slug": "how-to-train-your-dragon-2",
+///     "title": "How to train your dragon 2",
+///     "description": "So toothless",
+///     "body": "It a dragon",
+///     "bodyHtml": "<p>It a dragon</p>\n",
+///     "tagList": ["dragons", "training"],
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
+///     "favorited": false,
+///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 3,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
+///     "author": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     },
+///     "coauthors": []
+///   }]
+/// }
+/// ```
+async fn list_related_articles(
+    ctx: State<AppContext>,
+    auth_ctx: Option<AuthContext>,
+    Path(slug): Path<String>,
+    Query(params): Query<RelatedParams>,
+) -> Result<Response, Error> {
+    let limit = params.limit.unwrap_or(DEFAULT_RELATED_ARTICLES);
+    if limit == 0 || limit > MAX_RELATED_ARTICLES {
+        return Err(Error::Validation);
+    }
+
+    let user_ctx = auth_ctx.map(|ac| ac.user_id);
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let article =
+        match db::article::query_visible_article_by_slug(&mut cxn, &slug, user_ctx).await? {
+            Some(article) => article,
+            None => return Ok(StatusCode::NOT_FOUND.into_response()),
+        };
+
+    let related_ids = match ctx.caches.related.get(&article.id) {
+        Some(ids) => {
+            tracing::debug!("returning cached related articles");
+            ids
+        }
+        None => {
+            let ids = db::article::query_related_article_ids(
+                &mut cxn,
+                &article.id,
+                MAX_RELATED_ARTICLES as i64,
+            )
+            .await?;
+
+            ctx.caches.related.insert(article.id, ids.clone());
+
+            ids
+        }
+    };
+
+    let ids = &related_ids[..limit.min(related_ids.len())];
+
+    let articles = db::article::query_published_article_views_by_ids(&mut cxn, ids, user_ctx)
+        .await?
+        .into_iter()
+        .map(Article::with_db_view)
+        .collect();
+
+    Ok(Json(RelatedArticlesBody { articles }).into_response())
+}
@@ -0,0 +1,458 @@
+use crate::{
+    db,
+    db::user::Profile,
+    http::{
+        article::{self, Article, ArticleBody},
+        auth::AuthContext,
+        markdown, AppContext, Error,
+    },
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+
+/// Creates the [`Router`] for the HTTP endpoints that correspond to the revisions of articles and
+/// requires the [`AppContext`] to be the state type. All of the endpoints require authentication
+/// and are only available to the author of the article.
+///
+/// The following list enumerates the endpoints which are exposed by the `revision` API.
+///
+/// * `GET /api/articles/:slug/revisions` - Lists the revisions of an article, latest first.
+/// * `GET /api/articles/:slug/revisions/diff` - Returns a line-level diff between two revisions of
+///   an article identified by the `from` and `to` query parameters.
+/// * `GET /api/articles/:slug/revisions/:revision` - Returns a single revision of an article.
+/// * `POST /api/articles/:slug/revisions/:revision/restore` - Restores the content of an article to
+///   that of a revision, which creates a new revision.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new()
+        .route("/api/articles/:slug/revisions", get(list_revisions))
+        .route("/api/articles/:slug/revisions/diff", get(diff_revisions))
+        .route("/api/articles/:slug/revisions/:revision", get(get_revision))
+        .route(
+            "/api/articles/:slug/revisions/:revision/restore",
+            post(restore_revision),
+        )
+}
+
+/// The [`Revision`] struct contains data that represents a revision of an article as returned from
+/// the API.
+#[derive(Debug, Serialize)]
+struct Revision {
+    /// Number of the revision, starting at one for the content the article was created with.
+    revision: i32,
+    /// Title of the article at the revision.
+    title: String,
+    /// Description of the article at the revision.
+    description: String,
+    /// Body of the article at the revision. Not included when listing revisions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    /// Time the revision was created.
+    #[serde(rename = "createdAt")]
+    created: DateTime<Utc>,
+    /// Public [`Profile`] of the user who made the revision.
+    editor: Profile,
+}
+
+impl Revision {
+    /// Creates a new [`Revision`] populated from the given [`crate::db::revision::RevisionView`].
+    fn with_db_view(view: db::revision::RevisionView) -> Self {
+        Self {
+            revision: view.revision,
+            title: view.title,
+            description: view.description,
+            body: Some(view.body),
+            created: view.created,
+            editor: Profile {
+                id: view.editor_id,
+                name: view.editor_name,
+                bio: view.editor_bio,
+                image: view.editor_image,
+                following: view.editor_followed,
+            },
+        }
+    }
+}
+
+/// The [`RevisionBody`] struct is the envelope in which a single [`Revision`] is returned to the
+/// client.
+#[derive(Debug, Serialize)]
+struct RevisionBody {
+    /// Revision data contained in the envelope.
+    revision: Revision,
+}
+
+/// The [`RevisionsBody`] struct is the envelope in which multiple [`Revision`]s of an article are
+/// returned to the client.
+#[derive(Debug, Serialize)]
+struct RevisionsBody {
+    /// Revisions of the article, latest first.
+    revisions: Vec<Revision>,
+}
+
+/// The [`DiffParams`] struct contains the query parameters that identify the revisions to diff.
+#[derive(Debug, Deserialize)]
+struct DiffParams {
+    /// Number of the revision to diff from.
+    from: i32,
+    /// Number of the revision to diff to.
+    to: i32,
+}
+
+/// Enumerates the kinds of change that a line in a diff can represent.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum LineOp {
+    /// The line is the same in both revisions.
+    Equal,
+    /// The line only exists in the revision being diffed to.
+    Insert,
+    /// The line only exists in the revision being diffed from.
+    Delete,
+}
+
+/// The [`LineChange`] struct represents a single line of a diff.
+#[derive(Debug, PartialEq, Serialize)]
+struct LineChange {
+    /// Kind of change the line represents.
+    op: LineOp,
+    /// Text of the line without the trailing line break.
+    line: String,
+}
+
+/// The [`Diff`] struct contains the line-level diff of each of the fields of an article between
+/// two revisions.
+#[derive(Debug, Serialize)]
+struct Diff {
+    /// Number of the revision diffed from.
+    from: i32,
+    /// Number of the revision diffed to.
+    to: i32,
+    /// Changes to the title of the article.
+    title: Vec<LineChange>,
+    /// Changes to the description of the article.
+    description: Vec<LineChange>,
+    /// Changes to the body of the article.
+    body: Vec<LineChange>,
+}
+
+/// The [`DiffBody`] struct is the envelope in which a [`Diff`] is returned to the client.
+#[derive(Debug, Serialize)]
+struct DiffBody {
+    /// Diff data contained in the envelope.
+    diff: Diff,
+}
+
+/// Computes the line-level changes required to turn the `old` text into the `new` text.
+fn diff_lines(old: &str, new: &str) -> Vec<LineChange> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| LineChange {
+            op: match change.tag() {
+                ChangeTag::Equal => LineOp::Equal,
+                ChangeTag::Insert => LineOp::Insert,
+                ChangeTag::Delete => LineOp::Delete,
+            },
+            line: change.value().trim_end_matches(['\r', '\n']).to_owned(),
+        })
+        .collect()
+}
+
+/// Queries the article identified by the slug and verifies that the authenticated user is one of
+/// its authors, and is therefore able to access its revisions. Returns the response that should be
+/// sent to the client if they are not.
+async fn query_editable_article(
+    cxn: &mut sqlx::PgConnection,
+    slug: &str,
+    auth_ctx: &AuthContext,
+) -> Result<Result<db::article::Article, Response>, Error> {
+    match db::article::query_article_by_slug(&mut *cxn, slug).await? {
+        Some(article) => {
+            if db::article::is_article_editor(cxn, &article.id, &auth_ctx.user_id).await? {
+                Ok(Ok(article))
+            } else if article.is_public() {
+                Ok(Err(StatusCode::FORBIDDEN.into_response()))
+            } else {
+                Ok(Err(StatusCode::NOT_FOUND.into_response()))
+            }
+        }
+        None => Ok(Err(StatusCode::NOT_FOUND.into_response())),
+    }
+}
+
+/// Handles the list article revisions API endpoint at `GET /api/articles/:slug/revisions`. The
+/// revisions are returned latest first and do not include the body of the article. If the
+/// authenticated user is not an author of the article, then a 403 response is returned.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "revisions": [{
+///     "revision": 2,
+///     "title": "How to train your dragon",
+///     "description": "Ever wonder how?",
+///     "createdAt": "2016-02-18T03:48:35.824Z",
+///     "editor": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     }
+///   }]
+/// }
+/// ```
+async fn list_revisions(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    let article = match query_editable_article(&mut cxn, &slug, &auth_ctx).await? {
+        Ok(article) => article,
+        Err(response) => return Ok(response),
+    };
+
+    let revisions = db::revision::query_revisions(&mut cxn, &article.id, &auth_ctx.user_id)
+        .await?
+        .into_iter()
+        .map(|view| Revision {
+            body: None,
+            ..Revision::with_db_view(view)
+        })
+        .collect();
+
+    Ok(Json(RevisionsBody { revisions }).into_response())
+}
+
+/// Handles the get article revision API endpoint at `GET /api/articles/:slug/revisions/:revision`.
+/// If the revision does not exist then a 404 response is returned and if the authenticated user is
+/// not an author of the article, then a 403 response is returned.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "revision": {
+///     "revision": 2,
+///     "title": "How to train your dragon",
+///     "description": "Ever wonder how?",
+///     "body": "It takes a Jacobian",
+///     "createdAt": "2016-02-18T03:48:35.824Z",
+///     "editor": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     }
+///   }
+/// }
+/// ```
+async fn get_revision(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path((slug, revision)): Path<(String, i32)>,
+) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    let article = match query_editable_article(&mut cxn, &slug, &auth_ctx).await? {
+        Ok(article) => article,
+        Err(response) => return Ok(response),
+    };
+
+    match db::revision::query_revision(&mut cxn, &article.id, revision, &auth_ctx.user_id).await? {
+        None => Ok(StatusCode::NOT_FOUND.into_response()),
+        Some(view) => {
+            let revision = Revision::with_db_view(view);
+
+            Ok(Json(RevisionBody { revision }).into_response())
+        }
+    }
+}
+
+/// Handles the diff article revisions API endpoint at `GET /api/articles/:slug/revisions/diff`. The
+/// `from` and `to` query parameters identify the revisions to diff and the response contains the
+/// line-level changes to each field of the article. If either revision does not exist then a 404
+/// response is returned and if the authenticated user is not an author of the article, then a 403
+/// response is returned.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "diff": {
+///     "from": 1,
+///     "to": 2,
+///     "title": [{ "op": "equal", "line": "How to train your dragon" }],
+///     "description": [{ "op": "equal", "line": "Ever wonder how?" }],
+///     "body": [
+///       { "op": "delete", "line": "You have to believe" },
+///       { "op": "insert", "line": "It takes a Jacobian" }
+///     ]
+///   }
+/// }
+/// ```
+async fn diff_revisions(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+    Query(params): Query<DiffParams>,
+) -> Result<Response, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    let article = match query_editable_article(&mut cxn, &slug, &auth_ctx).await? {
+        Ok(article) => article,
+        Err(response) => return Ok(response),
+    };
+
+    let from =
+        db::revision::query_revision(&mut cxn, &article.id, params.from, &auth_ctx.user_id).await?;
+    let to =
+        db::revision::query_revision(&mut cxn, &article.id, params.to, &auth_ctx.user_id).await?;
+
+    match (from, to) {
+        (Some(from), Some(to)) => {
+            let diff = Diff {
+                from: from.revision,
+                to: to.revision,
+                title: diff_lines(&from.title, &to.title),
+                description: diff_lines(&from.description, &to.description),
+                body: diff_lines(&from.body, &to.body),
+            };
+
+            Ok(Json(DiffBody { diff }).into_response())
+        }
+        _ => Ok(StatusCode::NOT_FOUND.into_response()),
+    }
+}
+
+/// Handles the restore article revision API endpoint at
+/// `POST /api/articles/:slug/revisions/:revision/restore`. The title, description and body of the
+/// article are set to those of the revision, which records a new revision, and an
+/// `ARTICLE_UPDATED` event is published. If the revision does not exist then a 404 response is
+/// returned and if the authenticated user is not an author of the article, then a 403 response is
+/// returned.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "article": {
+///     "slug": "how-to-train-your-dragon",
+///     "title": "How to train your dragon",
+///     "description": "Ever wonder how?",
+///     "body": "It takes a Jacobian",
+///     "bodyHtml": "<p>It takes a Jacobian</p>\n",
+///     "tagList": ["dragons", "training"],
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "updatedAt": "2016-02-18T03:48:35.824Z",
+///     "status": "published",
+///     "publishedAt": "2016-02-18T03:22:56.637Z",
+///     "publishAt": null,
+///     "favorited": false,
+///     "favoritesCount": 0,
+///     "commentsCount": 0,
+///     "viewsCount": 0,
+///     "wordCount": 4,
+///     "readingTimeMinutes": 1,
+///     "bookmarked": false,
+///     "author": {
+///       "username": "jake",
+///       "bio": "I work at statefarm",
+///       "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///       "following": false
+///     },
+///     "coauthors": []
+///   }
+/// }
+/// ```
+async fn restore_revision(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path((slug, revision)): Path<(String, i32)>,
+) -> Result<Response, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    let row = match query_editable_article(&mut tx, &slug, &auth_ctx).await? {
+        Ok(article) => article,
+        Err(response) => return Ok(response),
+    };
+
+    let revision =
+        match db::revision::query_revision(&mut tx, &row.id, revision, &auth_ctx.user_id).await? {
+            None => return Ok(StatusCode::NOT_FOUND.into_response()),
+            Some(revision) => revision,
+        };
+
+    let rendered = markdown::render_body(&ctx.config.reading, &revision.body).await?;
+
+    let update_article = db::article::UpdateArticle {
+        title: &revision.title,
+        description: &revision.description,
+        body: &revision.body,
+        body_html: &rendered.html,
+        word_count: rendered.word_count,
+        reading_time_minutes: rendered.reading_time_minutes,
+        status: row.status,
+        publish_at: row.publish_at,
+    };
+
+    let db_view =
+        db::article::update_article(&mut tx, &row.id, update_article, &auth_ctx.user_id).await?;
+
+    let create_revision = db::revision::CreateRevision {
+        user_id: &auth_ctx.user_id,
+        title: &revision.title,
+        description: &revision.description,
+        body: &revision.body,
+    };
+
+    let _ = db::revision::create_revision(&mut tx, &row.id, create_revision).await?;
+
+    let article = Article::with_db_view(db_view);
+
+    article::create_article_outbox_entry(&mut tx, &article, "ARTICLE_UPDATED").await?;
+
+    tx.commit().await?;
+
+    match ctx.outbox_tx.send(()).await {
+        Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+        Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+    }
+
+    Ok(Json(ArticleBody { article }).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that the changes between two texts are computed line by line.
+    #[test]
+    fn verify_diff_lines() {
+        let changes = diff_lines("one\ntwo\nthree", "one\n2\nthree");
+
+        let expected = vec![
+            (LineOp::Equal, "one"),
+            (LineOp::Delete, "two"),
+            (LineOp::Insert, "2"),
+            (LineOp::Equal, "three"),
+        ];
+
+        assert_eq!(expected.len(), changes.len());
+
+        for (change, (op, line)) in changes.iter().zip(expected) {
+            assert_eq!(op, change.op);
+            assert_eq!(line, change.line);
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use std::{fmt::Write, io};
+
+use crate::{
+    config::Sitemap,
+    db::{
+        self,
+        sitemap::{SitemapPage, SitemapSection},
+    },
+    http::{encode_path_segment, feed::escape_xml, AppContext, Error},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::{SinkExt, TryStreamExt};
+use http::{header, StatusCode};
+
+/// Content type of the documents of the sitemap.
+const SITEMAP_CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+/// Number of entries that are rendered before they are sent to the client when streaming a page of
+/// the sitemap.
+const SITEMAP_CHUNK_SIZE: usize = 500;
+
+/// Creates the [`Router`] for the HTTP endpoints that serve the XML sitemap of the published
+/// content of the application and requires the [`AppContext`] to be the state type. The sitemap
+/// is split into sections for articles, profiles and tags, each of which has one or more pages.
+///
+/// The following list enumerates the endpoints which are exposed by the `sitemap` API.
+///
+/// * `GET /sitemap.xml` - Sitemap index that lists all of the pages of the sitemap.
+/// * `GET /sitemaps/:section-:page.xml` - Page of a section of the sitemap, e.g.
+///   `/sitemaps/articles-1.xml`.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new()
+        .route("/sitemap.xml", get(get_sitemap_index))
+        .route("/sitemaps/:page", get(get_sitemap_page))
+}
+
+impl SitemapSection {
+    /// Returns the name of the section as it appears in the file names of its pages.
+    fn name(&self) -> &'static str {
+        match self {
+            SitemapSection::Articles => "articles",
+            SitemapSection::Profiles => "profiles",
+            SitemapSection::Tags => "tags",
+        }
+    }
+
+    /// Returns the URL of the web page of the entry with the given name in the section.
+    fn entry_url(&self, config: &Sitemap, name: &str) -> String {
+        let path = match self {
+            SitemapSection::Articles => "article",
+            SitemapSection::Profiles => "profile",
+            SitemapSection::Tags => "tag",
+        };
+
+        format!("{}/{}/{}", config.site_url, path, encode_path_segment(name))
+    }
+}
+
+/// Parses the file name of a page of the sitemap, e.g. `articles-1.xml`, into the section and the
+/// zero based number of the page. Returns `None` if the file name is not that of a page.
+fn parse_page(file: &str) -> Option<(SitemapSection, i64)> {
+    let (section, number) = file.strip_suffix(".xml")?.rsplit_once('-')?;
+
+    let section = match section {
+        "articles" => SitemapSection::Articles,
+        "profiles" => SitemapSection::Profiles,
+        "tags" => SitemapSection::Tags,
+        _ => return None,
+    };
+
+    // only canonical page numbers are accepted so that each page has a single URL
+    if number.starts_with('0') || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    match number.parse::<i64>() {
+        Ok(number) if number > 0 => Some((section, number - 1)),
+        _ => None,
+    }
+}
+
+/// Formats the time as a W3C datetime as required by the sitemap protocol.
+fn w3c_datetime(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Formats the time as an HTTP date for the `Last-Modified` header.
+fn http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Retrieves the pages of the sitemap from the cache, querying the database and caching them if
+/// they are not cached.
+async fn sitemap_pages(ctx: &AppContext) -> Result<Vec<SitemapPage>, Error> {
+    if let Some(pages) = ctx.caches.sitemap.get(&()) {
+        tracing::debug!("returning cached sitemap pages");
+        return Ok(pages);
+    }
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let pages = db::sitemap::query_sitemap_pages(&mut cxn, ctx.config.sitemap.page_size).await?;
+
+    ctx.caches.sitemap.insert((), pages.clone());
+
+    Ok(pages)
+}
+
+/// Handles the sitemap index endpoint at `GET /sitemap.xml`. The handler returns the sitemap index
+/// which lists every page of each section of the sitemap along with the time that the most recently
+/// modified entry in the page was modified. The sections cover published articles, the profiles of
+/// the users who authored them and their tags, and the time that an entry was modified is taken
+/// from the articles it represents.
+///
+/// The pages are cached and the cached pages are invalidated when an event is consumed indicating
+/// that an article has been created, published, updated or deleted or that a user has been
+/// updated.
+///
+/// # Response Body Format
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+///   <sitemap>
+///     <loc>http://localhost:7100/sitemaps/articles-1.xml</loc>
+///     <lastmod>2016-02-18T03:48:35Z</lastmod>
+///   </sitemap>
+/// </sitemapindex>
+/// ```
+async fn get_sitemap_index(ctx: State<AppContext>) -> Result<Response, Error> {
+    let config = &ctx.config.sitemap;
+
+    let pages = sitemap_pages(&ctx).await?;
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for page in pages.iter() {
+        xml.push_str("  <sitemap>\n");
+        let _ = writeln!(
+            xml,
+            "    <loc>{}/sitemaps/{}-{}.xml</loc>",
+            escape_xml(&config.public_url),
+            page.section.name(),
+            page.page + 1
+        );
+        let _ = writeln!(
+            xml,
+            "    <lastmod>{}</lastmod>",
+            w3c_datetime(page.modified)
+        );
+        xml.push_str("  </sitemap>\n");
+    }
+    xml.push_str("</sitemapindex>\n");
+
+    let mut response = ([(header::CONTENT_TYPE, SITEMAP_CONTENT_TYPE)], xml).into_response();
+    if let Some(modified) = pages.iter().map(|p| p.modified).max() {
+        if let Ok(value) = http_date(modified).parse() {
+            response.headers_mut().insert(header::LAST_MODIFIED, value);
+        }
+    }
+
+    Ok(response)
+}
+
+/// Handles the sitemap page endpoint at `GET /sitemaps/:section-:page.xml`. The handler returns the
+/// page with the given number, starting at one, of the section of the sitemap. If the page is not
+/// listed in the sitemap index, then a 404 response is returned.
+///
+/// The entries of the page are streamed to the client as they are read from the database rather
+/// than loading the whole page into memory first.
+///
+/// # Response Body Format
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+///   <url>
+///     <loc>http://localhost:4100/article/how-to-train-your-dragon</loc>
+///     <lastmod>2016-02-18T03:48:35Z</lastmod>
+///   </url>
+/// </urlset>
+/// ```
+async fn get_sitemap_page(
+    ctx: State<AppContext>,
+    Path(file): Path<String>,
+) -> Result<Response, Error> {
+    let (section, number) = match parse_page(&file) {
+        Some(page) => page,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    let pages = sitemap_pages(&ctx).await?;
+
+    let page = match pages
+        .into_iter()
+        .find(|p| p.section == section && p.page == number)
+    {
+        Some(page) => page,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    let cxn = ctx.db.acquire().await?;
+
+    // The page is rendered by a separate task that reads the entries from the database and sends
+    // the rendered XML in chunks over a channel which the response body is streamed from.
+    let (mut tx, rx) = futures::channel::mpsc::channel::<Result<Bytes, io::Error>>(4);
+    let page_size = ctx.config.sitemap.page_size;
+    let ctx = ctx.0.clone();
+
+    tokio::spawn(async move {
+        let mut cxn = cxn;
+        let config = &ctx.config.sitemap;
+
+        let mut entries = db::sitemap::stream_sitemap_entries(&mut cxn, section, number, page_size);
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+
+        let mut count = 0;
+        loop {
+            match entries.try_next().await {
+                Ok(Some(entry)) => {
+                    xml.push_str("  <url>\n");
+                    let _ = writeln!(
+                        xml,
+                        "    <loc>{}</loc>",
+                        escape_xml(&section.entry_url(config, &entry.name))
+                    );
+                    let _ = writeln!(
+                        xml,
+                        "    <lastmod>{}</lastmod>",
+                        w3c_datetime(entry.modified)
+                    );
+                    xml.push_str("  </url>\n");
+
+                    count += 1;
+                    if count % SITEMAP_CHUNK_SIZE == 0 {
+                        let chunk = Bytes::from(std::mem::take(&mut xml));
+                        if tx.send(Ok(chunk)).await.is_err() {
+                            // the client disconnected so there is no point reading further
+                            return;
+                        }
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::error!("error streaming sitemap page {}: {}", file, e);
+                    let _ = tx.send(Err(io::Error::new(io::ErrorKind::Other, e))).await;
+                    return;
+                }
+            }
+        }
+
+        xml.push_str("</urlset>\n");
+        let _ = tx.send(Ok(Bytes::from(xml))).await;
+    });
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, String::from(SITEMAP_CONTENT_TYPE)),
+            (header::LAST_MODIFIED, http_date(page.modified)),
+        ],
+        Body::from_stream(rx),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that only the file names of pages of known sections are parsed and that the page
+    /// numbers start at one.
+    #[test]
+    fn verify_parse_page() {
+        assert_eq!(
+            Some((SitemapSection::Articles, 0)),
+            parse_page("articles-1.xml")
+        );
+        assert_eq!(Some((SitemapSection::Tags, 11)), parse_page("tags-12.xml"));
+        assert_eq!(None, parse_page("articles-0.xml"));
+        assert_eq!(None, parse_page("articles-01.xml"));
+        assert_eq!(None, parse_page("articles-+1.xml"));
+        assert_eq!(None, parse_page("articles-1.txt"));
+        assert_eq!(None, parse_page("comments-1.xml"));
+        assert_eq!(None, parse_page("articles.xml"));
+    }
+}
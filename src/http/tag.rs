@@ -1,4 +1,5 @@
 use crate::{
+    config::Tags,
     db,
     http::{AppContext, Error},
 };
@@ -45,3 +46,101 @@ async fn list_tags(ctx: State<AppContext>) -> Result<Json<TagsBody>, Error> {
 
     Ok(Json(TagsBody { tags }))
 }
+
+/// Normalizes the name of a tag according to the [`Tags`] configuration. Surrounding whitespace is
+/// trimmed and, if configured, the name is converted to lowercase. Returns [`None`] if the name is
+/// empty, longer than the maximum length, or contains a control or forbidden character.
+pub(super) fn normalize_tag(config: &Tags, name: &str) -> Option<String> {
+    let name = name.trim();
+    let name = if config.lowercase {
+        name.to_lowercase()
+    } else {
+        name.to_owned()
+    };
+
+    let valid = !name.is_empty()
+        && name.chars().count() <= config.max_length
+        && !name
+            .chars()
+            .any(|c| c.is_control() || config.forbidden_characters.contains(c));
+
+    valid.then_some(name)
+}
+
+/// Normalizes the names of the tags being set on an article, removing any duplicates while
+/// preserving the order in which they were specified. Returns a validation error if any of the
+/// names is invalid.
+pub(super) fn normalize_tags(config: &Tags, names: &[String]) -> Result<Vec<String>, Error> {
+    let mut tags: Vec<String> = Vec::with_capacity(names.len());
+    for name in names {
+        let tag = normalize_tag(config, name).ok_or(Error::Validation)?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    Ok(tags)
+}
+
+/// Normalizes the names of the tags used to filter articles. Invalid names cannot match any tag so
+/// they are passed through as given rather than rejecting the request.
+pub(super) fn normalize_filter_tags(config: &Tags, names: &[String]) -> Vec<String> {
+    names
+        .iter()
+        .map(|name| normalize_tag(config, name).unwrap_or_else(|| name.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(lowercase: bool) -> Tags {
+        Tags {
+            lowercase,
+            max_length: 8,
+            forbidden_characters: ",/?#&%".to_owned(),
+        }
+    }
+
+    /// Verifies that tag names are trimmed and lowercased and that invalid names are rejected.
+    #[test]
+    fn verify_normalize_tag() {
+        let config = config(true);
+
+        assert_eq!(Some("rust".to_owned()), normalize_tag(&config, "  Rust\t"));
+        assert_eq!(Some("café".to_owned()), normalize_tag(&config, "CAFÉ"));
+        assert_eq!(
+            Some("ünïcödé!".to_owned()),
+            normalize_tag(&config, "ünïcödé!")
+        );
+        assert_eq!(None, normalize_tag(&config, "   "));
+        assert_eq!(None, normalize_tag(&config, "too-long-tag"));
+        assert_eq!(None, normalize_tag(&config, "a,b"));
+        assert_eq!(None, normalize_tag(&config, "a\u{7}b"));
+
+        assert_eq!(
+            Some("Rust".to_owned()),
+            normalize_tag(&self::config(false), "Rust")
+        );
+    }
+
+    /// Verifies that duplicate tags are removed after normalization and that any invalid tag
+    /// rejects the whole list.
+    #[test]
+    fn verify_normalize_tags() {
+        let config = config(true);
+
+        let names = ["Rust", "web", " rust "].map(ToOwned::to_owned);
+        assert_eq!(
+            vec!["rust", "web"],
+            normalize_tags(&config, &names).unwrap()
+        );
+
+        let names = ["rust", "c#"].map(ToOwned::to_owned);
+        assert!(matches!(
+            normalize_tags(&config, &names),
+            Err(Error::Validation)
+        ));
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{
+    config::Trash,
+    db::{self, article::ArticleStatus, Keyset},
+    http::{
+        auth::AuthContext,
+        cursor::{self, Cursors},
+        AppContext, Error, Pagination,
+    },
+};
+
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Creates the [`Router`] for the HTTP endpoints that list the articles and comments that the
+/// authenticated user has deleted and requires the [`AppContext`] to be the state type. Deleted
+/// articles and comments are kept in the trash until they are purged once the configured retention
+/// period has passed and can be restored until then using the restore endpoints of the `article`
+/// API. All of the endpoints require authentication.
+///
+/// The following list enumerates the endpoints which are exposed by the `trash` API.
+///
+/// * `GET /api/user/trash/articles` - Lists the articles in the trash, most recently deleted first.
+/// * `GET /api/user/trash/comments` - Lists the comments in the trash, most recently deleted first.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new()
+        .route("/api/user/trash/articles", get(list_trashed_articles))
+        .route("/api/user/trash/comments", get(list_trashed_comments))
+}
+
+/// The [`TrashedArticle`] struct contains data that represents an article in the trash as returned
+/// from the API.
+#[derive(Debug, Serialize)]
+struct TrashedArticle {
+    /// Slugified title of the article, which remains reserved while the article is in the trash.
+    slug: String,
+    /// Title of the article.
+    title: String,
+    /// Description of the article.
+    description: String,
+    /// Publication status of the article when it was deleted, which it has again once restored.
+    status: ArticleStatus,
+    /// Time the article was created.
+    #[serde(rename = "createdAt")]
+    created: DateTime<Utc>,
+    /// Time the article was moved to the trash.
+    #[serde(rename = "deletedAt")]
+    deleted_at: DateTime<Utc>,
+    /// Time after which the article is purged and can no longer be restored.
+    #[serde(rename = "purgeAt")]
+    purge_at: DateTime<Utc>,
+}
+
+impl TrashedArticle {
+    /// Creates a new [`TrashedArticle`] from the given [`crate::db::trash::TrashedArticleView`].
+    fn with_db_view(config: &Trash, view: db::trash::TrashedArticleView) -> Self {
+        Self {
+            slug: view.slug,
+            title: view.title,
+            description: view.description,
+            status: view.status,
+            created: view.created,
+            deleted_at: view.deleted_at,
+            purge_at: purge_at(config, view.deleted_at),
+        }
+    }
+}
+
+/// The [`TrashedComment`] struct contains data that represents a comment in the trash as returned
+/// from the API.
+#[derive(Debug, Serialize)]
+struct TrashedComment {
+    /// Id of the comment.
+    id: Uuid,
+    /// Body text of the comment.
+    body: String,
+    /// Time at which the comment was originally created.
+    #[serde(rename = "createdAt")]
+    created: DateTime<Utc>,
+    /// Time the comment was moved to the trash.
+    #[serde(rename = "deletedAt")]
+    deleted_at: DateTime<Utc>,
+    /// Time after which the comment is purged and can no longer be restored.
+    #[serde(rename = "purgeAt")]
+    purge_at: DateTime<Utc>,
+    /// Article that the comment was made on.
+    article: CommentedArticle,
+}
+
+/// The [`CommentedArticle`] struct identifies the article that a comment in the trash was made on.
+#[derive(Debug, Serialize)]
+struct CommentedArticle {
+    /// Slugified title of the article.
+    slug: String,
+    /// Title of the article.
+    title: String,
+}
+
+impl TrashedComment {
+    /// Creates a new [`TrashedComment`] from the given [`crate::db::trash::TrashedCommentView`].
+    fn with_db_view(config: &Trash, view: db::trash::TrashedCommentView) -> Self {
+        Self {
+            id: view.id,
+            body: view.body,
+            created: view.created,
+            deleted_at: view.deleted_at,
+            purge_at: purge_at(config, view.deleted_at),
+            article: CommentedArticle {
+                slug: view.article_slug,
+                title: view.article_title,
+            },
+        }
+    }
+}
+
+/// The [`TrashedArticlesBody`] struct is the envelope in which the [`TrashedArticle`]s are returned
+/// to the client.
+#[derive(Debug, Serialize)]
+struct TrashedArticlesBody {
+    /// Articles in the trash that make up the response body.
+    articles: Vec<TrashedArticle>,
+    /// Total count of the articles in the trash.
+    #[serde(rename = "articlesCount")]
+    articles_count: i64,
+    /// Cursors that select the pages adjacent to the returned articles.
+    #[serde(flatten)]
+    cursors: Cursors,
+}
+
+/// The [`TrashedCommentsBody`] struct is the envelope in which the [`TrashedComment`]s are returned
+/// to the client.
+#[derive(Debug, Serialize)]
+struct TrashedCommentsBody {
+    /// Comments in the trash that make up the response body.
+    comments: Vec<TrashedComment>,
+    /// Total count of the comments in the trash.
+    #[serde(rename = "commentsCount")]
+    comments_count: i64,
+    /// Cursors that select the pages adjacent to the returned comments.
+    #[serde(flatten)]
+    cursors: Cursors,
+}
+
+/// Returns the time at which an article or comment that was moved to the trash at the given time is
+/// purged.
+fn purge_at(config: &Trash, deleted_at: DateTime<Utc>) -> DateTime<Utc> {
+    deleted_at + Duration::seconds(config.retention as i64)
+}
+
+/// Handles the list trashed articles API endpoint at `GET /api/user/trash/articles` which returns
+/// the articles of the currently authenticated user that are in the trash, most recently deleted
+/// first. Cursors select pages by the time the articles were deleted.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "articles": [{
+///     "slug": "how-to-train-your-dragon",
+///     "title": "How to train your dragon",
+///     "description": "Ever wonder how?",
+///     "status": "published",
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "deletedAt": "2016-02-19T10:12:05.118Z",
+///     "purgeAt": "2016-03-20T10:12:05.118Z"
+///   }],
+///   "articlesCount": 1,
+///   "nextCursor": null,
+///   "prevCursor": null
+/// }
+/// ```
+async fn list_trashed_articles(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    page: Query<Pagination>,
+) -> Result<Json<TrashedArticlesBody>, Error> {
+    let page = page.page()?;
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let views =
+        db::trash::query_trashed_articles(&mut cxn, &auth_ctx.user_id, &cursor::lookahead(&page))
+            .await?;
+
+    let (views, cursors) = cursor::paginate(views, &page, |a| Keyset {
+        created: a.deleted_at,
+        id: a.id,
+    });
+
+    let articles = views
+        .into_iter()
+        .map(|view| TrashedArticle::with_db_view(&ctx.config.trash, view))
+        .collect();
+
+    let articles_count = db::trash::count_trashed_articles(&mut cxn, &auth_ctx.user_id).await?;
+
+    Ok(Json(TrashedArticlesBody {
+        articles,
+        articles_count,
+        cursors,
+    }))
+}
+
+/// Handles the list trashed comments API endpoint at `GET /api/user/trash/comments` which returns
+/// the comments of the currently authenticated user that are in the trash, most recently deleted
+/// first. Comments on articles that are themselves in the trash are not included. Cursors select
+/// pages by the time the comments were deleted.
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "comments": [{
+///     "id": "f5a2a8b8-0f4c-4bd4-8b5e-1f6f0c8d7f0e",
+///     "body": "It takes a Jacobian",
+///     "createdAt": "2016-02-18T03:22:56.637Z",
+///     "deletedAt": "2016-02-19T10:12:05.118Z",
+///     "purgeAt": "2016-03-20T10:12:05.118Z",
+///     "article": {
+///       "slug": "how-to-train-your-dragon",
+///       "title": "How to train your dragon"
+///     }
+///   }],
+///   "commentsCount": 1,
+///   "nextCursor": null,
+///   "prevCursor": null
+/// }
+/// ```
+async fn list_trashed_comments(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    page: Query<Pagination>,
+) -> Result<Json<TrashedCommentsBody>, Error> {
+    let page = page.page()?;
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let views =
+        db::trash::query_trashed_comments(&mut cxn, &auth_ctx.user_id, &cursor::lookahead(&page))
+            .await?;
+
+    let (views, cursors) = cursor::paginate(views, &page, |c| Keyset {
+        created: c.deleted_at,
+        id: c.id,
+    });
+
+    let comments = views
+        .into_iter()
+        .map(|view| TrashedComment::with_db_view(&ctx.config.trash, view))
+        .collect();
+
+    let comments_count = db::trash::count_trashed_comments(&mut cxn, &auth_ctx.user_id).await?;
+
+    Ok(Json(TrashedCommentsBody {
+        comments,
+        comments_count,
+        cursors,
+    }))
+}
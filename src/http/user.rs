@@ -2,18 +2,20 @@ use std::collections::HashMap;
 
 use crate::{
     db,
-    http::{auth, auth::AuthContext, AppContext, Error},
+    http::{auth, auth::AuthContext, avatar, AppContext, Error},
+    storage::Object,
 };
 
 use axum::{
-    extract::State,
+    extract::{DefaultBodyLimit, Multipart, State},
     response::{IntoResponse, Response},
-    routing::{get, post},
+    routing::{get, post, put},
     Json, Router,
 };
 use chrono::{DateTime, Utc};
 use http::StatusCode;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use uuid::Uuid;
 
 /// Creates the [`Router`] for the HTTP endpoints that correspond to the user domain and requires
@@ -25,13 +27,29 @@ use uuid::Uuid;
 /// * `POST /api/users` - Allows a new user to register.
 /// * `PUT /api/users` - Allows a user to update their information.
 /// * `POST /api/users/login` - Allows a user to authenticate and retrieve a valid JWT.
+/// * `PUT /api/user/image` - Allows a user to upload a new avatar image.
+/// * `GET /api/user/settings` - Retrieves the notification and privacy preferences of the user.
+/// * `PUT /api/user/settings` - Allows a user to update their notification and privacy preferences.
 pub(super) fn router() -> Router<AppContext> {
     Router::new()
         .route("/api/users/login", post(login_user))
         .route("/api/users", post(create_user))
         .route("/api/user", get(get_user).put(update_user))
+        .route(
+            "/api/user/settings",
+            get(get_user_settings).put(update_user_settings),
+        )
+        // The size of the upload is enforced by the handler using the configured maximum so the
+        // default body limit is disabled for the route.
+        .route(
+            "/api/user/image",
+            put(update_user_image).layer(DefaultBodyLimit::disable()),
+        )
 }
 
+/// Name of the multipart form field that contains the uploaded avatar image.
+const IMAGE_FIELD: &str = "image";
+
 /// The [`CreateUserRequest`] struct contains the data received from the HTTP request to register a new
 /// user.
 #[derive(Debug, Deserialize)]
@@ -42,6 +60,9 @@ struct CreateUserRequest {
     email: String,
     /// Plain text password for the new user.
     password: String,
+    /// Invite code that is required to register when the application is in invite-only mode.
+    #[serde(rename = "inviteCode")]
+    invite_code: Option<String>,
 }
 
 /// The [`LoginUserRequest`] struct contains the data received from the HTTP request to authenticate a
@@ -69,6 +90,27 @@ struct UpdateUserRequest {
     image: Option<String>,
 }
 
+/// The [`UpdateSettingsRequest`] struct contains the data received from the HTTP request to update
+/// the settings of a user.
+#[derive(Debug, Deserialize)]
+struct UpdateSettingsRequest {
+    /// Flag indicating whether the user is emailed when their article is commented on.
+    #[serde(rename = "emailOnComment")]
+    email_on_comment: Option<bool>,
+    /// Flag indicating whether the user is emailed when another user follows them.
+    #[serde(rename = "emailOnFollow")]
+    email_on_follow: Option<bool>,
+    /// How often the user receives an email digest of activity.
+    #[serde(rename = "digestFrequency")]
+    digest_frequency: Option<db::user::DigestFrequency>,
+    /// Flag indicating whether the articles favorited by the user are visible to others.
+    #[serde(rename = "showFavoritesPublicly")]
+    show_favorites_publicly: Option<bool>,
+    /// Visibility of articles created by the user when none is specified.
+    #[serde(rename = "defaultArticleVisibility")]
+    default_article_visibility: Option<db::user::ArticleVisibility>,
+}
+
 /// The [`User`] struct contains data that repesents a user of the application as well as a JWT
 /// that allows the user to authenticate with the application.
 #[derive(Debug, Deserialize, Serialize)]
@@ -83,18 +125,25 @@ struct User {
     bio: String,
     /// URL to the image of the user.
     image: Option<String>,
+    /// Notification and privacy preferences of the user.
+    settings: db::user::UserSettings,
 }
 
 impl User {
-    /// Creates a new [`User`] from the given [`db::user::User`] retrieved from the database and the
-    /// specified authentication token.
-    fn from_db_user_with_token(user: db::user::User, token: String) -> User {
+    /// Creates a new [`User`] from the given [`db::user::User`] and [`db::user::UserSettings`]
+    /// retrieved from the database and the specified authentication token.
+    fn from_db_user_with_settings_and_token(
+        user: db::user::User,
+        settings: db::user::UserSettings,
+        token: String,
+    ) -> User {
         User {
             username: user.name,
             email: user.email,
             token,
             bio: user.bio,
             image: user.image,
+            settings,
         }
     }
 }
@@ -107,6 +156,14 @@ struct UserBody<T> {
     user: T,
 }
 
+/// The [`SettingsBody`] struct is the envelope in which the settings for a user are returned to
+/// the client or accepted from the client.
+#[derive(Debug, Deserialize, Serialize)]
+struct SettingsBody<T> {
+    /// Settings data contained in the envelope.
+    settings: T,
+}
+
 /// The [`UserEvent`] struct contains event data related to a user that is published to Kafka
 /// when a user is created, authenticated or updated.
 #[derive(Debug, Serialize)]
@@ -142,6 +199,30 @@ impl UserEvent {
     }
 }
 
+/// The [`SettingsEvent`] struct contains event data related to the settings of a user that is
+/// published to Kafka when the settings are updated.
+#[derive(Debug, Serialize)]
+struct SettingsEvent {
+    /// Id of the user the settings belong to.
+    user_id: Uuid,
+    /// Updated settings of the user.
+    settings: db::user::UserSettings,
+}
+
+/// The [`InviteRedeemedEvent`] struct contains event data related to an invite that is published
+/// to Kafka when a user registers using it.
+#[derive(Debug, Serialize)]
+struct InviteRedeemedEvent {
+    /// Id of the invite that was redeemed.
+    invite_id: Uuid,
+    /// Id of the user who redeemed the invite.
+    user_id: Uuid,
+    /// Number of times the invite has been redeemed including this redemption.
+    uses: i32,
+    /// Maximum number of times the invite can be redeemed.
+    max_uses: i32,
+}
+
 /// Handles the user registration API endpoint at `POST /api/users`.
 ///
 /// # Request Body Format
@@ -158,9 +239,15 @@ impl UserEvent {
 ///
 /// # Field Validation
 ///
-/// * `username` - required and must be unique across all users
+/// * `username` - required, must be unique across all users and must not be a username that was
+///   recently used by another user
 /// * `email` - required and must be unique across all users
 /// * `password` - required
+/// * `inviteCode` - required when the application is in invite-only mode and must be a code that
+///   has not expired or been redeemed the maximum number of times
+///
+/// If the application is in invite-only mode and the invite code is missing or invalid then a 403
+/// response is returned.
 ///
 /// Note that in a real application you would probably want to set some minimum requirements on
 /// the password but not be too overbearing with your maximum requirements if you impose any.
@@ -174,14 +261,28 @@ impl UserEvent {
 ///     "email": "jake@jake.jake",
 ///     "token": "jwt.token.here",
 ///     "bio": "I work at statefarm",
-///     "image": null
+///     "image": null,
+///     "settings": {
+///       "emailOnComment": true,
+///       "emailOnFollow": true,
+///       "digestFrequency": "weekly",
+///       "showFavoritesPublicly": true,
+///       "defaultArticleVisibility": "public"
+///     }
 ///   }
 /// }
 /// ```
 async fn create_user(
     ctx: State<AppContext>,
     Json(request): Json<UserBody<CreateUserRequest>>,
-) -> Result<Json<UserBody<User>>, Error> {
+) -> Result<Response, Error> {
+    let invite_only = ctx.config.registration.invite_only;
+
+    if invite_only && request.user.invite_code.is_none() {
+        tracing::debug!("rejecting registration without an invite code");
+        return Ok(StatusCode::FORBIDDEN.into_response());
+    }
+
     let password_hash = auth::hash_password(request.user.password)
         .await
         .map_err(|e| {
@@ -199,6 +300,17 @@ async fn create_user(
 
     let mut tx = ctx.db.begin().await?;
 
+    if db::user::is_username_reserved(
+        &mut tx,
+        data.username,
+        &Uuid::nil(),
+        ctx.config.username.reservation_period,
+    )
+    .await?
+    {
+        return Err(Error::Validation);
+    }
+
     let db_user: db::user::User = db::user::create_user(&mut tx, data).await?;
 
     let user_event = UserEvent::with_db_user(&db_user);
@@ -215,12 +327,45 @@ async fn create_user(
 
     let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
 
+    // The invite is redeemed in the same transaction that creates the user so that a use of the
+    // invite is only consumed if the registration succeeds and vice versa.
+    if let (true, Some(code)) = (invite_only, &request.user.invite_code) {
+        match db::invite::redeem_invite(&mut tx, code, &db_user.id).await? {
+            None => {
+                tracing::debug!("rejecting registration with an invalid invite code");
+                return Ok(StatusCode::FORBIDDEN.into_response());
+            }
+            Some(invite) => {
+                let invite_event = InviteRedeemedEvent {
+                    invite_id: invite.id,
+                    user_id: db_user.id,
+                    uses: invite.uses,
+                    max_uses: invite.max_uses,
+                };
+
+                let mut headers = HashMap::with_capacity(1);
+                headers.insert(String::from("type"), String::from("INVITE_REDEEMED"));
+
+                let create_outbox_entry = db::outbox::CreateOutboxEntry {
+                    topic: String::from("user"),
+                    partition_key: Some(db_user.id.to_string()),
+                    headers: Some(headers),
+                    payload: Some(invite_event),
+                };
+
+                let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
+            }
+        }
+    }
+
     let token = auth::mint_jwt(db_user.id, &ctx.config.http.signing_key).map_err(|e| {
         tracing::error!("error minting jwt: {}", e);
         Error::Internal
     })?;
 
-    let user = User::from_db_user_with_token(db_user, token);
+    let settings = db::user::query_user_settings(&mut tx, &db_user.id).await?;
+
+    let user = User::from_db_user_with_settings_and_token(db_user, settings, token);
 
     tx.commit().await?;
 
@@ -229,7 +374,7 @@ async fn create_user(
         Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
     }
 
-    Ok(Json(UserBody { user }))
+    Ok(Json(UserBody { user }).into_response())
 }
 
 /// Handles the user authentication API endpoint at `GET /api/users/login`.
@@ -259,7 +404,14 @@ async fn create_user(
 ///     "email": "jake@jake.jake",
 ///     "token": "jwt.token.here",
 ///     "bio": "I work at statefarm",
-///     "image": null
+///     "image": null,
+///     "settings": {
+///       "emailOnComment": true,
+///       "emailOnFollow": true,
+///       "digestFrequency": "weekly",
+///       "showFavoritesPublicly": true,
+///       "defaultArticleVisibility": "public"
+///     }
 ///   }
 /// }
 /// ```
@@ -297,7 +449,9 @@ async fn login_user(
                         Error::Internal
                     })?;
 
-                let user = User::from_db_user_with_token(db_user, token);
+                let settings = db::user::query_user_settings(&mut cxn, &db_user.id).await?;
+
+                let user = User::from_db_user_with_settings_and_token(db_user, settings, token);
 
                 match ctx.outbox_tx.send(()).await {
                     Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
@@ -330,7 +484,14 @@ async fn login_user(
 ///     "email": "jake@jake.jake",
 ///     "token": "jwt.token.here",
 ///     "bio": "I work at statefarm",
-///     "image": null
+///     "image": null,
+///     "settings": {
+///       "emailOnComment": true,
+///       "emailOnFollow": true,
+///       "digestFrequency": "weekly",
+///       "showFavoritesPublicly": true,
+///       "defaultArticleVisibility": "public"
+///     }
 ///   }
 /// }
 /// ```
@@ -339,7 +500,10 @@ async fn get_user(ctx: State<AppContext>, auth_ctx: AuthContext) -> Result<Respo
 
     match db::user::query_user_by_id(&mut cxn, &auth_ctx.user_id).await? {
         Some(db_user) => {
-            let user = User::from_db_user_with_token(db_user, auth_ctx.encoded_jwt);
+            let settings = db::user::query_user_settings(&mut cxn, &db_user.id).await?;
+
+            let user =
+                User::from_db_user_with_settings_and_token(db_user, settings, auth_ctx.encoded_jwt);
 
             Ok(Json(UserBody { user }).into_response())
         }
@@ -371,6 +535,11 @@ async fn get_user(ctx: State<AppContext>, auth_ctx: AuthContext) -> Result<Respo
 /// * `image`
 /// * `bio`
 ///
+/// When the username is changed the previous username is recorded so that it continues to resolve
+/// to the user for a grace period and is reserved against being taken by other users for a period
+/// of time. If the username was changed too recently then a 429 response is returned and if the
+/// requested username is reserved for another user then a 422 response is returned.
+///
 /// # Response Body Format
 ///
 /// ``` json
@@ -380,7 +549,14 @@ async fn get_user(ctx: State<AppContext>, auth_ctx: AuthContext) -> Result<Respo
 ///     "email": "jake@jake.com",
 ///     "token": "jwt.token.here",
 ///     "bio": "I like to skateboard",
-///     "image": "https://i.stack.imgur.com/xHWG8.jpg"
+///     "image": "https://i.stack.imgur.com/xHWG8.jpg",
+///     "settings": {
+///       "emailOnComment": true,
+///       "emailOnFollow": true,
+///       "digestFrequency": "weekly",
+///       "showFavoritesPublicly": true,
+///       "defaultArticleVisibility": "public"
+///     }
 ///   }
 /// }
 /// ```
@@ -399,6 +575,34 @@ async fn update_user(
             let bio = request.user.bio.as_ref().unwrap_or(&db_user.bio);
             let image = request.user.image.or(db_user.image);
 
+            if *username != db_user.name {
+                let username_cfg = &ctx.config.username;
+
+                if let Some(last_change) =
+                    db::user::query_last_username_change(&mut tx, &db_user.id).await?
+                {
+                    let interval = Duration::from_secs(username_cfg.change_interval);
+
+                    if last_change + interval > Utc::now() {
+                        tracing::debug!("rejecting username change as the last was too recent");
+                        return Ok(StatusCode::TOO_MANY_REQUESTS.into_response());
+                    }
+                }
+
+                if db::user::is_username_reserved(
+                    &mut tx,
+                    username,
+                    &db_user.id,
+                    username_cfg.reservation_period,
+                )
+                .await?
+                {
+                    return Err(Error::Validation);
+                }
+
+                db::user::add_username_history(&mut tx, &db_user.id, &db_user.name).await?;
+            }
+
             let password_hash = if let Some(password) = request.user.password {
                 auth::hash_password(password).await.map_err(|e| {
                     tracing::error!("error hashing password: {}", e);
@@ -436,7 +640,166 @@ async fn update_user(
 
             let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
 
-            let user = User::from_db_user_with_token(db_user, auth_ctx.encoded_jwt);
+            let settings = db::user::query_user_settings(&mut tx, &db_user.id).await?;
+
+            let user =
+                User::from_db_user_with_settings_and_token(db_user, settings, auth_ctx.encoded_jwt);
+
+            tx.commit().await?;
+
+            match ctx.outbox_tx.send(()).await {
+                Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+                Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+            }
+
+            Ok(Json(UserBody { user }).into_response())
+        }
+    }
+}
+
+/// Handles the upload user image API endpoint at `PUT /api/user/image`. The handler will read the
+/// id of the user from the current authentication token, validate the uploaded image, store resized
+/// variants of it in the object store and then point the `image` property of the user at the
+/// largest variant.
+///
+/// # Request Body Format
+///
+/// A `multipart/form-data` body containing the image in a field named `image`. The image must be
+/// a GIF, JPEG, PNG or WebP, be no larger than the configured maximum upload size and be no more
+/// than 4096 pixels wide or high.
+///
+/// # Response Body Format
+///
+/// ``` json
+/// {
+///   "user": {
+///     "username": "jake",
+///     "email": "jake@jake.jake",
+///     "token": "jwt.token.here",
+///     "bio": "I work at statefarm",
+///     "image": "http://localhost:7100/assets/avatars/:id/:upload-large.png",
+///     "settings": {
+///       "emailOnComment": true,
+///       "emailOnFollow": true,
+///       "digestFrequency": "weekly",
+///       "showFavoritesPublicly": true,
+///       "defaultArticleVisibility": "public"
+///     }
+///   }
+/// }
+/// ```
+async fn update_user_image(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    mut multipart: Multipart,
+) -> Result<Response, Error> {
+    let max_size = ctx.config.storage.max_upload_size;
+
+    let mut upload = None;
+
+    while let Some(mut field) = multipart.next_field().await.map_err(|e| {
+        tracing::debug!("error reading multipart field: {}", e);
+        Error::Validation
+    })? {
+        if field.name() != Some(IMAGE_FIELD) {
+            continue;
+        }
+
+        if field
+            .content_type()
+            .is_some_and(|ct| !ct.starts_with("image/"))
+        {
+            return Ok(StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response());
+        }
+
+        // Read the field in chunks so that the upload can be rejected as soon as it exceeds the
+        // maximum size rather than buffering the entire thing in memory first.
+        let mut data = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(|e| {
+            tracing::debug!("error reading multipart chunk: {}", e);
+            Error::Validation
+        })? {
+            if data.len() + chunk.len() > max_size {
+                return Ok(StatusCode::PAYLOAD_TOO_LARGE.into_response());
+            }
+
+            data.extend_from_slice(&chunk);
+        }
+
+        upload = Some(data);
+        break;
+    }
+
+    let Some(data) = upload else {
+        return Err(Error::Validation);
+    };
+
+    let variants = match avatar::generate_variants(data).await {
+        Ok(variants) => variants,
+        Err(avatar::Error::UnsupportedFormat) => {
+            return Ok(StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response())
+        }
+        Err(avatar::Error::Decode) => return Err(Error::Validation),
+        Err(avatar::Error::TooLarge) => return Ok(StatusCode::PAYLOAD_TOO_LARGE.into_response()),
+        Err(e) => {
+            tracing::error!("error generating avatar variants: {}", e);
+            return Err(Error::Internal);
+        }
+    };
+
+    // Each upload is stored under a unique prefix so that the stored objects are immutable and can
+    // be cached indefinitely by clients.
+    let upload_id = Uuid::new_v4();
+
+    let mut image = None;
+
+    for variant in variants {
+        let key = format!(
+            "avatars/{}/{}-{}.{}",
+            auth_ctx.user_id, upload_id, variant.name, variant.extension
+        );
+
+        if variant.name == avatar::DEFAULT_VARIANT {
+            image = Some(format!(
+                "{}/{}",
+                ctx.config.storage.public_url.trim_end_matches('/'),
+                key
+            ));
+        }
+
+        let object = Object {
+            content_type: variant.content_type.to_owned(),
+            data: variant.data,
+        };
+
+        ctx.store.put(&key, object).await?;
+    }
+
+    let image = image.ok_or(Error::Internal)?;
+
+    let mut tx = ctx.db.begin().await?;
+
+    match db::user::update_user_image(&mut tx, &auth_ctx.user_id, &image).await? {
+        None => Ok(StatusCode::UNAUTHORIZED.into_response()),
+        Some(db_user) => {
+            let user_event = UserEvent::with_db_user(&db_user);
+
+            let mut headers = HashMap::with_capacity(1);
+            headers.insert(String::from("type"), String::from("USER_UPDATED"));
+
+            let create_outbox_entry = db::outbox::CreateOutboxEntry {
+                topic: String::from("user"),
+                partition_key: Some(user_event.id.to_string()),
+                headers: Some(headers),
+                payload: Some(user_event),
+            };
+
+            let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
+
+            let settings = db::user::query_user_settings(&mut tx, &db_user.id).await?;
+
+            let user =
+                User::from_db_user_with_settings_and_token(db_user, settings, auth_ctx.encoded_jwt);
 
             tx.commit().await?;
 
@@ -449,3 +812,129 @@ async fn update_user(
         }
     }
 }
+
+/// Handles the get user settings API endpoint at `GET /api/user/settings`. The handler will read
+/// the id of the user from the current authentication token and return the notification and privacy
+/// preferences of the user.
+///
+/// # Response Body Format
+///
+/// ``` json
+/// {
+///   "settings": {
+///     "emailOnComment": true,
+///     "emailOnFollow": true,
+///     "digestFrequency": "weekly",
+///     "showFavoritesPublicly": true,
+///     "defaultArticleVisibility": "public"
+///   }
+/// }
+/// ```
+async fn get_user_settings(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+) -> Result<Json<SettingsBody<db::user::UserSettings>>, Error> {
+    let mut cxn = ctx.db.acquire().await?;
+
+    let settings = db::user::query_user_settings(&mut cxn, &auth_ctx.user_id).await?;
+
+    Ok(Json(SettingsBody { settings }))
+}
+
+/// Handles the update user settings API endpoint at `PUT /api/user/settings`. The handler will
+/// read the id of the user from the current authentication token and update the notification and
+/// privacy preferences of the user based on the request body.
+///
+/// # Request Body Format
+///
+/// ``` json
+/// {
+///   "settings": {
+///     "emailOnFollow": false,
+///     "digestFrequency": "daily"
+///   }
+/// }
+/// ```
+///
+/// # Accepted Fields
+///
+/// * `emailOnComment`
+/// * `emailOnFollow`
+/// * `digestFrequency` - one of `never`, `daily` or `weekly`
+/// * `showFavoritesPublicly`
+/// * `defaultArticleVisibility` - one of `public` or `unlisted`
+///
+/// # Response Body Format
+///
+/// ``` json
+/// {
+///   "settings": {
+///     "emailOnComment": true,
+///     "emailOnFollow": false,
+///     "digestFrequency": "daily",
+///     "showFavoritesPublicly": true,
+///     "defaultArticleVisibility": "public"
+///   }
+/// }
+/// ```
+async fn update_user_settings(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Json(request): Json<SettingsBody<UpdateSettingsRequest>>,
+) -> Result<Json<SettingsBody<db::user::UserSettings>>, Error> {
+    let mut tx = ctx.db.begin().await?;
+
+    let current = db::user::query_user_settings(&mut tx, &auth_ctx.user_id).await?;
+
+    let data = db::user::UpdateUserSettings {
+        user_id: auth_ctx.user_id,
+        email_on_comment: request
+            .settings
+            .email_on_comment
+            .unwrap_or(current.email_on_comment),
+        email_on_follow: request
+            .settings
+            .email_on_follow
+            .unwrap_or(current.email_on_follow),
+        digest_frequency: request
+            .settings
+            .digest_frequency
+            .unwrap_or(current.digest_frequency),
+        show_favorites_publicly: request
+            .settings
+            .show_favorites_publicly
+            .unwrap_or(current.show_favorites_publicly),
+        default_article_visibility: request
+            .settings
+            .default_article_visibility
+            .unwrap_or(current.default_article_visibility),
+    };
+
+    let settings = db::user::update_user_settings(&mut tx, data).await?;
+
+    let settings_event = SettingsEvent {
+        user_id: auth_ctx.user_id,
+        settings: settings.clone(),
+    };
+
+    let mut headers = HashMap::with_capacity(1);
+    headers.insert(String::from("type"), String::from("USER_SETTINGS_UPDATED"));
+
+    let create_outbox_entry = db::outbox::CreateOutboxEntry {
+        topic: String::from("user"),
+        partition_key: Some(auth_ctx.user_id.to_string()),
+        headers: Some(headers),
+        payload: Some(settings_event),
+    };
+
+    let _ = db::outbox::create_outbox_entry(&mut tx, create_outbox_entry).await?;
+
+    tx.commit().await?;
+
+    match ctx.outbox_tx.send(()).await {
+        Ok(_) => tracing::debug!("successfully notified outbox processor of new entry"),
+        Err(e) => tracing::warn!("failed to notify outbox processor of new entry: {}", e),
+    }
+
+    Ok(Json(SettingsBody { settings }))
+}
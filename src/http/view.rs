@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use crate::{
+    db,
+    http::{auth::AuthContext, AppContext, Error},
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use http::{header, HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Creates the [`Router`] for the HTTP endpoints that correspond to the views of articles and
+/// requires the [`AppContext`] to be the state type. Views themselves are recorded when an article
+/// is retrieved by slug.
+///
+/// The following list enumerates the endpoints which are exposed by the `view` API.
+///
+/// * `GET /api/articles/:slug/stats` - Authentication required, returns the views of an article
+///   along with a daily time series to its authors.
+pub(super) fn router() -> Router<AppContext> {
+    Router::new().route("/api/articles/:slug/stats", get(get_article_stats))
+}
+
+/// The [`ViewEvent`] struct contains event data that is published to Kafka when an article is
+/// viewed. The events are aggregated by the consumer and periodically added to the article.
+#[derive(Debug, Serialize)]
+struct ViewEvent {
+    /// Id of the article that was viewed.
+    article_id: Uuid,
+    /// Time the article was viewed.
+    viewed_at: DateTime<Utc>,
+}
+
+/// The [`Stats`] struct contains the views of an article as returned from the API.
+#[derive(Debug, Serialize)]
+struct Stats {
+    /// Count of the total number of views of the article.
+    #[serde(rename = "viewsCount")]
+    views_count: i64,
+    /// Number of views of the article on each day in the requested range, oldest first.
+    days: Vec<DayStats>,
+}
+
+/// The [`DayStats`] struct contains the views of an article on a single day as returned from the
+/// API.
+#[derive(Debug, Serialize)]
+struct DayStats {
+    /// Day in UTC.
+    date: NaiveDate,
+    /// Number of views of the article on the day.
+    views: i64,
+}
+
+/// The [`StatsBody`] struct is the envelope in which the [`Stats`] of an article are returned to
+/// the client.
+#[derive(Debug, Serialize)]
+struct StatsBody {
+    /// Stats of the article.
+    stats: Stats,
+}
+
+/// The [`StatsParams`] struct contains the query parameters accepted by the stats endpoint.
+#[derive(Debug, Deserialize)]
+struct StatsParams {
+    /// Number of days, ending today, to include in the daily time series.
+    days: Option<u32>,
+}
+
+/// Records a view of the article by the authenticated user, or by the anonymous visitor identified
+/// by the request headers, unless they have already viewed the article within the configured
+/// window. Views by the authors of the article are not counted.
+///
+/// Rather than writing to the article on every view, an `ARTICLE_VIEWED` event is added to the
+/// outbox and the views are aggregated by the Kafka consumer. The outbox processor is not notified
+/// since views are not time sensitive and are picked up by the next sweep of the outbox.
+pub(super) async fn record_view(
+    ctx: &AppContext,
+    cxn: &mut PgConnection,
+    view: &db::article::ArticleView,
+    user_ctx: Option<Uuid>,
+    headers: &HeaderMap,
+) -> Result<(), sqlx::Error> {
+    if user_ctx == Some(view.author_id) || view.coauthors.iter().any(|c| user_ctx == Some(c.id)) {
+        return Ok(());
+    }
+
+    let viewer = match user_ctx {
+        Some(user_id) => user_id.to_string(),
+        None => visitor_fingerprint(headers),
+    };
+
+    if !ctx.caches.views.insert_if_absent((view.id, viewer), ()) {
+        return Ok(());
+    }
+
+    let view_event = ViewEvent {
+        article_id: view.id,
+        viewed_at: Utc::now(),
+    };
+
+    let mut headers = HashMap::with_capacity(1);
+    headers.insert(String::from("type"), String::from("ARTICLE_VIEWED"));
+
+    let create_outbox_entry = db::outbox::CreateOutboxEntry {
+        topic: String::from("article"),
+        partition_key: Some(view_event.article_id.to_string()),
+        headers: Some(headers),
+        payload: Some(view_event),
+    };
+
+    let _ = db::outbox::create_outbox_entry(cxn, create_outbox_entry).await?;
+
+    Ok(())
+}
+
+/// Derives an opaque identifier for an anonymous visitor from the client address forwarded by the
+/// proxy in front of the application and the user agent of the request. The values are hashed so
+/// that the address of the visitor is not kept in memory.
+fn visitor_fingerprint(headers: &HeaderMap) -> String {
+    let address = headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .unwrap_or_default()
+        .trim();
+
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    let digest = Sha256::new()
+        .chain_update(address)
+        .chain_update([0])
+        .chain_update(user_agent)
+        .finalize();
+
+    URL_SAFE_NO_PAD.encode(digest)
+}
+
+/// Handles the article stats API endpoint at `GET /api/articles/:slug/stats`. The handler returns
+/// the total number of views of the article along with the number of views on each of the most
+/// recent days, including today, which defaults to the configured number of days and can be set
+/// with the `days` query parameter. Views are aggregated periodically so the most recent views may
+/// not be included yet.
+///
+/// Only the authors of the article can view its stats. If the authenticated user is not an author
+/// of the article, then a 403 response is returned.
+///
+/// # Query Parameters
+///
+/// * `days` - optional, number of days in the time series from `1` up to the configured maximum
+///
+/// # Response Body Format
+///
+/// ```json
+/// {
+///   "stats": {
+///     "viewsCount": 42,
+///     "days": [
+///       { "date": "2016-02-17", "views": 0 },
+///       { "date": "2016-02-18", "views": 42 }
+///     ]
+///   }
+/// }
+/// ```
+async fn get_article_stats(
+    ctx: State<AppContext>,
+    auth_ctx: AuthContext,
+    Path(slug): Path<String>,
+    Query(params): Query<StatsParams>,
+) -> Result<Response, Error> {
+    let num_days = params.days.unwrap_or(ctx.config.views.stats_days);
+    if num_days == 0 || num_days > ctx.config.views.max_stats_days {
+        return Err(Error::Validation);
+    }
+
+    let mut cxn = ctx.db.acquire().await?;
+
+    let article = match db::article::query_article_by_slug(&mut cxn, &slug).await? {
+        Some(article) => article,
+        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    };
+
+    if !db::article::is_article_editor(&mut cxn, &article.id, &auth_ctx.user_id).await? {
+        return match article.is_public() {
+            true => Ok(StatusCode::FORBIDDEN.into_response()),
+            false => Ok(StatusCode::NOT_FOUND.into_response()),
+        };
+    }
+
+    let last = Utc::now().date_naive();
+    let first = last
+        .checked_sub_days(Days::new(u64::from(num_days - 1)))
+        .ok_or(Error::Validation)?;
+
+    let days = db::view::query_daily_views(&mut cxn, &article.id, first, last)
+        .await?
+        .into_iter()
+        .map(|d| DayStats {
+            date: d.day,
+            views: d.views,
+        })
+        .collect();
+
+    let stats = Stats {
+        views_count: article.views_count,
+        days,
+    };
+
+    Ok(Json(StatsBody { stats }).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http::HeaderValue;
+
+    /// Verifies that anonymous visitors are identified by both their address and user agent and
+    /// that only the address of the client that sent the request is used.
+    #[test]
+    fn verify_visitor_fingerprint() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("203.0.113.7, 10.0.0.1"),
+        );
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("curl/8.0"));
+        let fingerprint = visitor_fingerprint(&headers);
+
+        headers.insert("x-forwarded-for", HeaderValue::from_static("203.0.113.7"));
+        assert_eq!(fingerprint, visitor_fingerprint(&headers));
+
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("curl/8.1"));
+        assert_ne!(fingerprint, visitor_fingerprint(&headers));
+
+        headers.insert("x-forwarded-for", HeaderValue::from_static("203.0.113.8"));
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("curl/8.0"));
+        assert_ne!(fingerprint, visitor_fingerprint(&headers));
+    }
+}
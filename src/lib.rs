@@ -1,4 +1,7 @@
+pub mod cache;
 pub mod config;
 pub mod db;
 pub mod event;
 pub mod http;
+pub mod markdown;
+pub mod storage;
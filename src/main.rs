@@ -1,6 +1,8 @@
+use realworld::cache::Caches;
 use realworld::config::Config;
 use realworld::event;
 use realworld::http;
+use realworld::storage;
 use sqlx::postgres::PgPoolOptions;
 use std::sync::Arc;
 use std::time::Duration;
@@ -66,15 +68,64 @@ async fn main() -> anyhow::Result<()> {
     let outbox_processor_fut =
         event::produce::start_outbox_receiver(Arc::clone(&config), pool.clone(), rx);
 
-    // Start the Kafka consumer.
-    let consumer_fut = event::consume::start_kafka_consumer(Arc::clone(&config));
+    // Start the task that periodically publishes scheduled articles once they are due. The events
+    // for the published articles go through the outbox so the task notifies the outbox processor.
+    let publish_schedule_fut =
+        event::schedule::schedule_article_publishing(Arc::clone(&config), pool.clone(), tx.clone());
+
+    // Start the task that periodically corrects any drift in the favorites, comments and views
+    // counts that are denormalized onto the articles.
+    let reconciliation_schedule_fut =
+        event::schedule::schedule_counter_reconciliation(Arc::clone(&config), pool.clone());
+
+    // Start the task that periodically recomputes the trending scores of articles so that the
+    // favorites they are computed from decay over time.
+    let trending_schedule_fut =
+        event::schedule::schedule_trending_refresh(Arc::clone(&config), pool.clone());
+
+    // Start the task that periodically purges the articles and comments that have been in the trash
+    // for longer than the retention period. The final deletion events go through the outbox too.
+    let trash_purge_schedule_fut =
+        event::schedule::schedule_trash_purge(Arc::clone(&config), pool.clone(), tx.clone());
+
+    // Render the bodies of the articles that were created before the rendered HTML was stored. The
+    // backfill runs once in the background so that it does not delay serving requests.
+    let backfill_pool = pool.clone();
+    tokio::spawn(async move {
+        match event::schedule::backfill_article_bodies(backfill_pool).await {
+            Err(e) => tracing::error!("error backfilling the rendered bodies of articles: {}", e),
+            Ok(0) => {}
+            Ok(num_rendered) => tracing::info!("rendered the bodies of {} articles", num_rendered),
+        }
+    });
+
+    // Create the in-memory caches that are populated by the HTTP handlers and invalidated by the
+    // Kafka consumer as events are received.
+    let caches = Arc::new(Caches::with_config(&config));
+
+    // Start the Kafka consumer, which also periodically writes the views of articles that it has
+    // aggregated to the database before committing the offsets of the events they came from.
+    let consumer_fut = event::consume::start_kafka_consumer(
+        Arc::clone(&config),
+        Arc::clone(&caches),
+        pool.clone(),
+    );
+
+    // Create the store that objects uploaded to the application, e.g. user avatars, are kept in
+    // based on the configured storage backend.
+    let store = storage::from_config(&config.storage)?;
 
     // Configure the routes for the application and start the HTTP server on the configured port.
     let tcp_listener =
         tokio::net::TcpListener::bind(format!("127.0.0.1:{}", config.http.port)).await?;
 
-    let http_fut =
-        async { axum::serve(tcp_listener, http::router(pool, Arc::clone(&config), tx)).await };
+    let http_fut = async {
+        axum::serve(
+            tcp_listener,
+            http::router(pool, Arc::clone(&config), tx, caches, store),
+        )
+        .await
+    };
 
     // If running on a unix system, install a handler for the terminate signal so we can cleanly
     // shutdown. If not running on a unix system then instead use a future that will never return.
@@ -112,6 +163,26 @@ async fn main() -> anyhow::Result<()> {
                 tracing::error!("error processing outbox entries: {}", e);
             }
         }
+        publish_schedule_res = publish_schedule_fut => {
+            if let Err(e) = publish_schedule_res {
+                tracing::error!("error with the article publishing schedule: {}", e);
+            }
+        }
+        reconciliation_schedule_res = reconciliation_schedule_fut => {
+            if let Err(e) = reconciliation_schedule_res {
+                tracing::error!("error with the article counter reconciliation schedule: {}", e);
+            }
+        }
+        trending_schedule_res = trending_schedule_fut => {
+            if let Err(e) = trending_schedule_res {
+                tracing::error!("error with the article trending score schedule: {}", e);
+            }
+        }
+        trash_purge_schedule_res = trash_purge_schedule_fut => {
+            if let Err(e) = trash_purge_schedule_res {
+                tracing::error!("error with the trash purge schedule: {}", e);
+            }
+        }
         consumer_res = consumer_fut => {
             if let Err(e) = consumer_res {
                 tracing::error!("error consuming Kafka events: {}", e);
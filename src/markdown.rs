@@ -0,0 +1,166 @@
+use ammonia::Builder;
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use std::sync::OnceLock;
+
+/// Enumerates the errors that can be generated from the `markdown` module.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Occurs when the result of rendering cannot be received from the rendering task.
+    #[error("error rendering markdown")]
+    Render,
+}
+
+/// The [`Rendered`] struct contains the result of rendering the Markdown body of an article.
+#[derive(Debug)]
+pub struct Rendered {
+    /// Sanitized HTML rendered from the Markdown.
+    pub html: String,
+    /// Number of words in the text of the Markdown, as counted by [`count_words`].
+    pub word_count: usize,
+}
+
+/// Returns the options used to parse Markdown, which enable the GitHub flavored extensions for
+/// tables, strikethrough and task lists in addition to CommonMark.
+fn options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS
+}
+
+/// Returns the sanitizer used to clean the HTML generated from Markdown. In addition to the
+/// defaults, which strip scripts, event handlers and other unsafe markup, the language class of
+/// code blocks and the alignment of table cells are kept so that clients are able to style them.
+fn sanitizer() -> &'static Builder<'static> {
+    static SANITIZER: OnceLock<Builder<'static>> = OnceLock::new();
+
+    SANITIZER.get_or_init(|| {
+        let mut builder = Builder::default();
+
+        builder
+            .add_tag_attributes("code", ["class"])
+            .add_tag_attributes("th", ["style"])
+            .add_tag_attributes("td", ["style"])
+            .add_tag_attributes("input", ["type", "checked", "disabled"])
+            .add_tags(["input"])
+            .attribute_filter(|element, attribute, value| match (element, attribute) {
+                ("code", "class") if value.starts_with("language-") => Some(value.into()),
+                ("th" | "td", "style")
+                    if matches!(
+                        value,
+                        "text-align: left" | "text-align: center" | "text-align: right"
+                    ) =>
+                {
+                    Some(value.into())
+                }
+                ("input", "type") if value == "checkbox" => Some(value.into()),
+                ("code", "class") | ("th" | "td", "style") | ("input", "type") => None,
+                _ => Some(value.into()),
+            });
+
+        builder
+    })
+}
+
+/// Renders the CommonMark formatted Markdown, along with the GitHub flavored extensions for tables,
+/// strikethrough and task lists, to HTML that is safe to embed in a web page.
+pub fn render(markdown: &str) -> String {
+    let mut html = String::with_capacity(markdown.len() * 3 / 2);
+    pulldown_cmark::html::push_html(&mut html, Parser::new_ext(markdown, options()));
+
+    sanitizer().clean(&html).to_string()
+}
+
+/// Counts the words in the text of the Markdown as a reader would see it. Code blocks, whether
+/// fenced or indented, and raw HTML are not counted while the text of inline code, links and image
+/// descriptions is. Inline formatting does not split a word, e.g. `un*believ*able` is one word.
+pub fn count_words(markdown: &str) -> usize {
+    let mut text = String::with_capacity(markdown.len());
+    let mut in_code_block = false;
+
+    for event in Parser::new_ext(markdown, options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => {
+                in_code_block = false;
+                text.push(' ');
+            }
+            Event::Text(t) | Event::Code(t) if !in_code_block => text.push_str(&t),
+            Event::End(
+                TagEnd::Emphasis
+                | TagEnd::Strong
+                | TagEnd::Strikethrough
+                | TagEnd::Link
+                | TagEnd::Image,
+            ) => {}
+            Event::End(_) | Event::SoftBreak | Event::HardBreak | Event::Rule => text.push(' '),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().count()
+}
+
+/// Renders the Markdown to sanitized HTML as described by [`render`] and counts its words as
+/// described by [`count_words`].
+///
+/// Parsing and sanitizing large documents can be CPU intensive so spawn a task to be run in the
+/// rayon thread pool which is good for that kind of work.
+pub async fn render_html(markdown: String) -> Result<Rendered, Error> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    rayon::spawn(move || {
+        let rendered = Rendered {
+            html: render(&markdown),
+            word_count: count_words(&markdown),
+        };
+
+        if tx.send(rendered).is_err() {
+            tracing::error!("failed to send markdown render result over channel");
+        }
+    });
+
+    rx.await.map_err(|e| {
+        tracing::debug!("error rendering markdown: {}", e);
+        Error::Render
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that tables and fenced code blocks are rendered and the language class is kept.
+    #[test]
+    fn verify_render_tables_and_code() {
+        let html = render("| a | b |\n|:--|--:|\n| 1 | 2 |\n\n```rust\nfn main() {}\n```\n");
+
+        assert!(html.contains("<table>"));
+        assert!(html.contains(r#"<th style="text-align: left">a</th>"#));
+        assert!(html.contains(r#"<code class="language-rust">fn main() {}"#));
+    }
+
+    /// Verifies that words are counted across inline formatting and that code blocks are ignored.
+    #[test]
+    fn verify_count_words() {
+        assert_eq!(0, count_words(""));
+        assert_eq!(5, count_words("# A title\n\nun*believ*able `code` here"));
+        assert_eq!(
+            3,
+            count_words("one\n\n```rust\nfn main() {}\n```\n\n    indented code\n\ntwo\nthree")
+        );
+        assert_eq!(
+            6,
+            count_words("* first item\n* second item\n\n[a link](https://x.io)")
+        );
+    }
+
+    /// Verifies that unsafe markup is removed from the rendered HTML.
+    #[test]
+    fn verify_render_sanitizes_html() {
+        let html = render(
+            "<script>alert(1)</script>\n\n<a href=\"javascript:alert(1)\" onclick=\"x()\">hi</a>",
+        );
+
+        assert!(!html.contains("script"));
+        assert!(!html.contains("javascript"));
+        assert!(!html.contains("onclick"));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::storage::{is_valid_key, Error, Object, ObjectStore};
+
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+/// The [`LocalObjectStore`] is an [`ObjectStore`] that stores objects as files in a directory on
+/// the local file system. The content type of an object is not persisted and is instead derived
+/// from the extension of the key when the object is retrieved.
+#[derive(Debug)]
+pub struct LocalObjectStore {
+    /// Directory in which the objects are stored.
+    root: PathBuf,
+}
+
+impl LocalObjectStore {
+    /// Creates a new [`LocalObjectStore`] that stores objects in the given directory.
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Resolves the path of the file for the object with the given key.
+    fn path_for(&self, key: &str) -> Result<PathBuf, Error> {
+        if !is_valid_key(key) {
+            return Err(Error::InvalidKey);
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl ObjectStore for LocalObjectStore {
+    /// Writes the object data to a file at the path derived from the key, creating any parent
+    /// directories that do not yet exist.
+    async fn put(&self, key: &str, object: Object) -> Result<(), Error> {
+        let path = self.path_for(key)?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        tokio::fs::write(path, object.data).await?;
+
+        Ok(())
+    }
+
+    /// Reads the object data from the file at the path derived from the key.
+    async fn get(&self, key: &str) -> Result<Option<Object>, Error> {
+        let path = self.path_for(key)?;
+
+        match tokio::fs::read(&path).await {
+            Ok(data) => Ok(Some(Object {
+                content_type: content_type_for(&path).to_owned(),
+                data,
+            })),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Returns the MIME type for a file based on the extension of the path.
+fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("gif") => "image/gif",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that an object can be stored and then retrieved from the local file system.
+    #[tokio::test]
+    async fn verify_put_and_get() {
+        let root = std::env::temp_dir().join(format!("realworld-{}", uuid::Uuid::new_v4()));
+        let store = LocalObjectStore::new(&root);
+
+        let object = Object {
+            content_type: String::from("image/png"),
+            data: vec![1, 2, 3],
+        };
+
+        store.put("avatars/test.png", object).await.unwrap();
+
+        let stored = store.get("avatars/test.png").await.unwrap().unwrap();
+        assert_eq!("image/png", stored.content_type);
+        assert_eq!(vec![1, 2, 3], stored.data);
+
+        assert!(store.get("avatars/missing.png").await.unwrap().is_none());
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+pub mod local;
+pub mod s3;
+
+use crate::config::{Storage, StorageBackend};
+
+use async_trait::async_trait;
+use std::{fmt::Debug, sync::Arc};
+
+/// Enumerates the errors that can be generated from the `storage` module.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Occurs when the key of an object is not valid for the storage backend.
+    #[error("invalid object key")]
+    InvalidKey,
+    /// Occurs when an error is encountered interacting with the local file system.
+    #[error("error interacting with the local file system")]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
+    /// Occurs when an error is encountered interacting with the S3 compatible API.
+    #[error("error interacting with the S3 compatible API")]
+    S3 {
+        #[from]
+        source: ::s3::error::S3Error,
+    },
+    /// Occurs when the S3 compatible API responds with an unexpected status code.
+    #[error("unexpected response status from the S3 compatible API")]
+    UnexpectedStatus,
+}
+
+/// The [`Object`] struct contains the data and metadata of an object that is stored in an
+/// [`ObjectStore`].
+#[derive(Debug)]
+pub struct Object {
+    /// MIME type of the object data.
+    pub content_type: String,
+    /// Raw bytes of the object.
+    pub data: Vec<u8>,
+}
+
+/// The [`ObjectStore`] trait abstracts over the backend that objects uploaded to the application
+/// are stored in so that the rest of the application does not need to be concerned with where the
+/// objects actually live.
+#[async_trait]
+pub trait ObjectStore: Debug + Send + Sync {
+    /// Stores the [`Object`] using the given key replacing any object that already exists.
+    async fn put(&self, key: &str, object: Object) -> Result<(), Error>;
+
+    /// Retrieves the [`Object`] stored using the given key, if it exists.
+    async fn get(&self, key: &str) -> Result<Option<Object>, Error>;
+}
+
+/// Creates the [`ObjectStore`] for the backend specified in the given [`Storage`] configuration.
+pub fn from_config(config: &Storage) -> Result<Arc<dyn ObjectStore>, Error> {
+    let store: Arc<dyn ObjectStore> = match config.backend {
+        StorageBackend::Local => Arc::new(local::LocalObjectStore::new(&config.local.path)),
+        StorageBackend::S3 => Arc::new(s3::S3ObjectStore::new(&config.s3)?),
+    };
+
+    Ok(store)
+}
+
+/// Determines whether the key is safe to use for an object, i.e. it is a relative path that does
+/// not attempt to traverse outside of the root of the store.
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with('/')
+        && !key.contains('\\')
+        && key
+            .split('/')
+            .all(|s| !s.is_empty() && s != "." && s != "..")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that keys which could escape the root of the store are rejected.
+    #[test]
+    fn verify_is_valid_key() {
+        assert!(is_valid_key("avatars/1234/large.png"));
+
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("/etc/passwd"));
+        assert!(!is_valid_key("avatars/../../etc/passwd"));
+        assert!(!is_valid_key("avatars//large.png"));
+        assert!(!is_valid_key("avatars\\large.png"));
+    }
+}
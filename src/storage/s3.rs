@@ -0,0 +1,123 @@
+use crate::{
+    config::S3Storage,
+    storage::{is_valid_key, Error, Object, ObjectStore},
+};
+
+use async_trait::async_trait;
+use s3::{creds::Credentials, Bucket, Region};
+
+/// The [`S3ObjectStore`] is an [`ObjectStore`] that stores objects in an S3 compatible bucket.
+/// Path style requests are used so that the store works with S3 compatible services like MinIO
+/// which can be run locally using the provided `docker-compose.yml` file.
+#[derive(Debug)]
+pub struct S3ObjectStore {
+    /// Bucket in which the objects are stored.
+    bucket: Box<Bucket>,
+}
+
+impl S3ObjectStore {
+    /// Creates a new [`S3ObjectStore`] that stores objects in the bucket described by the given
+    /// [`S3Storage`] configuration.
+    pub fn new(config: &S3Storage) -> Result<Self, Error> {
+        let region = Region::Custom {
+            region: config.region.clone(),
+            endpoint: config.endpoint.clone(),
+        };
+
+        let credentials = Credentials::new(
+            Some(&config.access_key),
+            Some(&config.secret_key),
+            None,
+            None,
+            None,
+        )
+        .map_err(|e| Error::S3 { source: e.into() })?;
+
+        let bucket = Bucket::new(&config.bucket, region, credentials)?.with_path_style();
+
+        Ok(Self { bucket })
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3ObjectStore {
+    /// Uploads the object to the bucket using the key as the object path.
+    async fn put(&self, key: &str, object: Object) -> Result<(), Error> {
+        if !is_valid_key(key) {
+            return Err(Error::InvalidKey);
+        }
+
+        let response = self
+            .bucket
+            .put_object_with_content_type(key, &object.data, &object.content_type)
+            .await?;
+
+        match response.status_code() {
+            200..=299 => Ok(()),
+            status => {
+                tracing::error!("unexpected status {} storing object {}", status, key);
+                Err(Error::UnexpectedStatus)
+            }
+        }
+    }
+
+    /// Downloads the object from the bucket using the key as the object path.
+    async fn get(&self, key: &str) -> Result<Option<Object>, Error> {
+        if !is_valid_key(key) {
+            return Err(Error::InvalidKey);
+        }
+
+        let response = self.bucket.get_object(key).await?;
+
+        match response.status_code() {
+            200..=299 => {
+                let content_type = response
+                    .headers()
+                    .get("content-type")
+                    .cloned()
+                    .unwrap_or_else(|| String::from("application/octet-stream"));
+
+                Ok(Some(Object {
+                    content_type,
+                    data: response.to_vec(),
+                }))
+            }
+            404 => Ok(None),
+            status => {
+                tracing::error!("unexpected status {} retrieving object {}", status, key);
+                Err(Error::UnexpectedStatus)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    /// Verifies that an object can be stored and then retrieved from the bucket. The test requires
+    /// the MinIO container from the `docker-compose.yml` file to be running and the bucket to have
+    /// been created using the `init-minio.sh` script so it is ignored by default.
+    #[tokio::test]
+    #[ignore]
+    async fn verify_put_and_get_against_minio() {
+        let config = Config::default();
+        let store = S3ObjectStore::new(&config.storage.s3).unwrap();
+
+        let key = format!("test/{}.png", uuid::Uuid::new_v4());
+
+        let object = Object {
+            content_type: String::from("image/png"),
+            data: vec![1, 2, 3],
+        };
+
+        store.put(&key, object).await.unwrap();
+
+        let stored = store.get(&key).await.unwrap().unwrap();
+        assert_eq!("image/png", stored.content_type);
+        assert_eq!(vec![1, 2, 3], stored.data);
+
+        assert!(store.get("test/missing.png").await.unwrap().is_none());
+    }
+}